@@ -0,0 +1,291 @@
+//! Baselines of known policy violations.
+//!
+//! A baseline is a committed file listing the violations that existed when
+//! it was created, keyed by policy, file, and a hash of the message. `check`
+//! suppresses violations that match an entry, so only new ones are
+//! reported, and lists the entries that no longer match so the baseline can
+//! be pruned by creating it again.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::explain;
+use crate::report::{CheckReport, FileResult, Outcome};
+
+/// Default baseline file, relative to the git root.
+pub const DEFAULT_BASELINE_FILE: &str = ".repopo-baseline.json";
+
+/// Bumped whenever the on-disk format changes.
+const BASELINE_FORMAT_VERSION: u32 = 1;
+
+/// On-disk representation of a baseline.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BaselineFile {
+    /// Format version of the file.
+    format: u32,
+
+    /// The known violations, sorted so that the file diffs cleanly.
+    entries: Vec<BaselineEntry>,
+}
+
+/// A single known violation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineEntry {
+    /// The name of the policy that reported the violation.
+    pub policy: String,
+
+    /// Repo-relative path to the file.
+    pub file: String,
+
+    /// Hash of the violation's message, so that a different violation of
+    /// the same policy in the same file is reported as new.
+    pub message_hash: String,
+}
+
+impl BaselineEntry {
+    fn new(policy: &str, result: &FileResult) -> Self {
+        let message = result.result.error_message().unwrap_or_default();
+        Self {
+            policy: policy.to_string(),
+            file: result.file.clone(),
+            message_hash: format!("{:016x}", xxh3_64(message.as_bytes())),
+        }
+    }
+}
+
+/// How a baseline changed the results of a check.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineSummary {
+    /// The baseline file that was applied, repo-relative if it is in the
+    /// repository.
+    pub file: String,
+
+    /// Number of violations suppressed because they are in the baseline.
+    pub suppressed: usize,
+
+    /// Entries for files the check ran on that no longer have the recorded
+    /// violation.
+    pub fixed: Vec<BaselineEntry>,
+}
+
+/// A loaded baseline.
+pub struct Baseline {
+    file: String,
+    entries: HashSet<BaselineEntry>,
+}
+
+impl Baseline {
+    /// Load a baseline. Returns `None` if the file does not exist.
+    pub fn load(git_root: &str, path: &Path) -> Result<Option<Self>> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("Failed to read baseline {}", path.display()));
+            }
+        };
+
+        let data: BaselineFile = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse baseline {}", path.display()))?;
+        if data.format != BASELINE_FORMAT_VERSION {
+            anyhow::bail!(
+                "Baseline {} has unsupported format {}. Create it again with `repopo-core baseline create`.",
+                path.display(),
+                data.format
+            );
+        }
+
+        let file = env::current_dir()
+            .ok()
+            .and_then(|cwd| explain::repo_path(git_root, &cwd, &path.to_string_lossy()).ok())
+            .unwrap_or_else(|| path.display().to_string());
+        Ok(Some(Self {
+            file,
+            entries: data.entries.into_iter().collect(),
+        }))
+    }
+
+    /// Write every violation in a report to a new baseline. Returns the
+    /// number of entries written.
+    pub fn create(path: &Path, report: &CheckReport) -> Result<usize> {
+        let mut entries: Vec<BaselineEntry> = report
+            .policies
+            .iter()
+            .flat_map(|p| p.results.iter().map(move |r| (p, r)))
+            .filter(|(_, r)| r.outcome.is_failure())
+            .map(|(p, r)| BaselineEntry::new(&p.name, r))
+            .collect();
+        entries.sort();
+        entries.dedup();
+
+        let count = entries.len();
+        let data = BaselineFile {
+            format: BASELINE_FORMAT_VERSION,
+            entries,
+        };
+        let mut json =
+            serde_json::to_string_pretty(&data).context("Failed to serialize baseline")?;
+        json.push('\n');
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write baseline {}", path.display()))?;
+        Ok(count)
+    }
+
+    /// Mark the violations in a report that match an entry as
+    /// [`Outcome::Baselined`], and record which entries are fixed.
+    ///
+    /// An entry is only reported as fixed if its policy ran on its file, so
+    /// checking a subset of files does not make the rest look fixed.
+    pub fn apply(&self, report: &mut CheckReport) {
+        let mut suppressed = 0;
+        let mut matched: HashSet<BaselineEntry> = HashSet::new();
+        let mut checked: HashSet<(String, String)> = HashSet::new();
+
+        for policy in &mut report.policies {
+            for result in &mut policy.results {
+                checked.insert((policy.name.clone(), result.file.clone()));
+                if !result.outcome.is_failure() {
+                    continue;
+                }
+
+                let entry = BaselineEntry::new(&policy.name, result);
+                if self.entries.contains(&entry) {
+                    result.outcome = Outcome::Baselined;
+                    suppressed += 1;
+                    matched.insert(entry);
+                }
+            }
+        }
+
+        let mut fixed: Vec<BaselineEntry> = self
+            .entries
+            .iter()
+            .filter(|e| !matched.contains(*e))
+            .filter(|e| checked.contains(&(e.policy.clone(), e.file.clone())))
+            .cloned()
+            .collect();
+        fixed.sort();
+
+        report.baseline = Some(BaselineSummary {
+            file: self.file.clone(),
+            suppressed,
+            fixed,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Baseline, DEFAULT_BASELINE_FILE};
+    use crate::engine::PerfStats;
+    use crate::report::{CheckReport, FileResult, Outcome, PolicyReport};
+    use crate::types::{HandlerResult, PolicyErrorResult, Severity};
+    use std::fs;
+
+    fn result(file: &str, error: Option<&str>) -> FileResult {
+        let (outcome, result) = match error {
+            Some(error) => (
+                Outcome::Fail,
+                HandlerResult::Failure(PolicyErrorResult {
+                    error: Some(error.to_string()),
+                    ..PolicyErrorResult::default()
+                }),
+            ),
+            None => (Outcome::Pass, HandlerResult::Pass(true)),
+        };
+        FileResult {
+            file: file.to_string(),
+            outcome,
+            severity: Severity::Error,
+            result,
+        }
+    }
+
+    fn report(results: Vec<(&str, FileResult)>) -> CheckReport {
+        let mut policies: Vec<PolicyReport> = Vec::new();
+        for (name, result) in results {
+            match policies.iter_mut().find(|p| p.name == name) {
+                Some(policy) => policy.results.push(result),
+                None => policies.push(PolicyReport {
+                    name: name.to_string(),
+                    description: String::new(),
+                    results: vec![result],
+                }),
+            }
+        }
+        CheckReport {
+            policies,
+            stats: PerfStats::new(),
+            baseline: None,
+        }
+    }
+
+    fn outcomes(report: &CheckReport) -> Vec<Outcome> {
+        report
+            .policies
+            .iter()
+            .flat_map(|p| &p.results)
+            .map(|r| r.outcome)
+            .collect()
+    }
+
+    #[test]
+    fn suppresses_recorded_violations_and_lists_fixed_entries() {
+        let root = std::env::temp_dir().join(format!("repopo-baseline-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let git_root = root.to_str().unwrap();
+        let path = root.join(DEFAULT_BASELINE_FILE);
+
+        let recorded = report(vec![
+            ("A", result("src/a.txt", Some("bad"))),
+            ("A", result("src/b.txt", None)),
+            ("B", result("src/a.txt", Some("ugly"))),
+            ("B", result("src/c.txt", Some("ugly"))),
+        ]);
+        assert_eq!(Baseline::create(&path, &recorded).unwrap(), 3);
+        let baseline = Baseline::load(git_root, &path).unwrap().unwrap();
+
+        // The same violations are all suppressed.
+        let mut same = report(vec![
+            ("A", result("src/a.txt", Some("bad"))),
+            ("B", result("src/a.txt", Some("ugly"))),
+            ("B", result("src/c.txt", Some("ugly"))),
+        ]);
+        baseline.apply(&mut same);
+        assert_eq!(outcomes(&same), vec![Outcome::Baselined; 3]);
+        let summary = same.baseline.unwrap();
+        assert_eq!(summary.file, DEFAULT_BASELINE_FILE);
+        assert_eq!(summary.suppressed, 3);
+        assert!(summary.fixed.is_empty());
+
+        // A different message is a new violation, and a passing file fixes
+        // its entry. B did not run on src/c.txt, so that entry is kept.
+        let mut changed = report(vec![
+            ("A", result("src/a.txt", Some("worse"))),
+            ("B", result("src/a.txt", None)),
+        ]);
+        baseline.apply(&mut changed);
+        assert_eq!(outcomes(&changed), vec![Outcome::Fail, Outcome::Pass]);
+        let summary = changed.baseline.unwrap();
+        assert_eq!(summary.suppressed, 0);
+        let fixed: Vec<(&str, &str)> = summary
+            .fixed
+            .iter()
+            .map(|e| (e.policy.as_str(), e.file.as_str()))
+            .collect();
+        assert_eq!(fixed, vec![("A", "src/a.txt"), ("B", "src/a.txt")]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
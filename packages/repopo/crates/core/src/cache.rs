@@ -0,0 +1,213 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use xxhash_rust::xxh3::xxh3_128;
+
+use crate::types::{LoadConfigResponse, PolicyMeta};
+
+/// Default cache directory, relative to the git root.
+pub const DEFAULT_CACHE_DIR: &str = ".repopo/cache";
+
+/// Name of the cache file within the cache directory.
+const CACHE_FILE: &str = "check.json";
+
+/// Bumped whenever the on-disk format changes.
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// On-disk representation of the check cache.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheFile {
+    /// Format version of the file.
+    format: u32,
+
+    /// Fingerprint of the config files and tool versions the entries were
+    /// recorded with. A mismatch discards every entry.
+    fingerprint: String,
+
+    /// Cached passing results, by policy key.
+    policies: HashMap<String, PolicyEntries>,
+}
+
+/// Cached passing results for a single policy.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PolicyEntries {
+    /// Fingerprint of the policy's config when the entries were recorded.
+    config_hash: String,
+
+    /// Content hash of each file that passed, by repo-relative path.
+    files: HashMap<String, String>,
+}
+
+/// A persistent cache of passing policy results.
+///
+/// A result is reused when the policy key, the policy's config fingerprint,
+/// and the hash of the file's content all match what was recorded. Only
+/// passing results are cached, so failures are always re-reported.
+pub struct CheckCache {
+    path: PathBuf,
+    git_root: PathBuf,
+    data: CacheFile,
+    content_hashes: HashMap<String, Option<String>>,
+    dirty: bool,
+}
+
+impl CheckCache {
+    /// Load the cache from `cache_dir` (relative to `git_root` unless absolute).
+    ///
+    /// Entries recorded against different config files, sidecar version, or
+    /// repopo-core version are discarded. A missing or unreadable cache file
+    /// starts an empty cache.
+    pub fn load(git_root: &str, cache_dir: &Path, config: &LoadConfigResponse) -> Result<Self> {
+        let git_root = PathBuf::from(git_root);
+        let path = git_root.join(cache_dir).join(CACHE_FILE);
+        let fingerprint = fingerprint(config)?;
+
+        let data = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<CacheFile>(&contents).ok())
+            .filter(|data| data.format == CACHE_FORMAT_VERSION && data.fingerprint == fingerprint)
+            .unwrap_or_else(|| CacheFile {
+                format: CACHE_FORMAT_VERSION,
+                fingerprint,
+                policies: HashMap::new(),
+            });
+
+        Ok(Self {
+            path,
+            git_root,
+            data,
+            content_hashes: HashMap::new(),
+            dirty: false,
+        })
+    }
+
+    /// Returns true if `file` has a cached pass for `policy` at its current content.
+    pub fn is_cached_pass(&mut self, policy: &PolicyMeta, file: &str) -> bool {
+        if !policy.cacheable {
+            return false;
+        }
+        let Some(config_hash) = policy.config_hash.as_deref() else {
+            return false;
+        };
+        let Some(content_hash) = self.content_hash(file) else {
+            return false;
+        };
+
+        self.data
+            .policies
+            .get(&policy.key())
+            .is_some_and(|entries| {
+                entries.config_hash == config_hash
+                    && entries.files.get(file).is_some_and(|h| *h == content_hash)
+            })
+    }
+
+    /// Record that `file` passed `policy` at its current content.
+    pub fn record_pass(&mut self, policy: &PolicyMeta, file: &str) {
+        if !policy.cacheable {
+            return;
+        }
+        let Some(config_hash) = policy.config_hash.as_deref() else {
+            return;
+        };
+        let Some(content_hash) = self.content_hash(file) else {
+            return;
+        };
+
+        let entries = self.data.policies.entry(policy.key()).or_default();
+        if entries.config_hash != config_hash {
+            entries.config_hash = config_hash.to_string();
+            entries.files.clear();
+        }
+        entries.files.insert(file.to_string(), content_hash);
+        self.dirty = true;
+    }
+
+    /// Drop any cached pass for `file` under `policy`.
+    pub fn forget(&mut self, policy: &PolicyMeta, file: &str) {
+        if let Some(entries) = self.data.policies.get_mut(&policy.key())
+            && entries.files.remove(file).is_some()
+        {
+            self.dirty = true;
+        }
+    }
+
+    /// Write the cache back to disk if it changed.
+    pub fn save(&self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        let dir = self.path.parent().context("Cache path has no parent")?;
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create cache directory {}", dir.display()))?;
+
+        // Keep the cache out of git without requiring a .gitignore entry.
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            fs::write(&gitignore, "*\n")
+                .with_context(|| format!("Failed to write {}", gitignore.display()))?;
+        }
+
+        let json = serde_json::to_string(&self.data).context("Failed to serialize cache")?;
+        fs::write(&self.path, json)
+            .with_context(|| format!("Failed to write cache file {}", self.path.display()))?;
+        Ok(())
+    }
+
+    /// Record the hash of a file that was read for another purpose, from
+    /// [`hash_contents`], so the cache does not read it again.
+    pub fn seed(&mut self, file: &str, hash: String) {
+        self.content_hashes.insert(file.to_string(), Some(hash));
+    }
+
+    /// Hash a file's current content, memoized for the lifetime of the cache.
+    /// Returns `None` if the file cannot be read.
+    fn content_hash(&mut self, file: &str) -> Option<String> {
+        if let Some(hash) = self.content_hashes.get(file) {
+            return hash.clone();
+        }
+
+        let hash = fs::read(self.git_root.join(file))
+            .ok()
+            .map(|bytes| hash_contents(&bytes));
+        self.content_hashes.insert(file.to_string(), hash.clone());
+        hash
+    }
+}
+
+/// The hash the cache keys a file's content by.
+pub fn hash_contents(contents: &[u8]) -> String {
+    format!("{:032x}", xxh3_128(contents))
+}
+
+/// Fingerprint everything that invalidates the whole cache: the contents of
+/// the config files and the local modules they import, and the versions of
+/// repopo-core and the sidecar's policies.
+fn fingerprint(config: &LoadConfigResponse) -> Result<String> {
+    let mut input = Vec::new();
+    input.extend_from_slice(env!("CARGO_PKG_VERSION").as_bytes());
+    input.push(0);
+    input.extend_from_slice(config.version.as_deref().unwrap_or_default().as_bytes());
+    input.push(0);
+
+    for config_file in config.config_files() {
+        input.extend_from_slice(config_file.as_bytes());
+        input.push(0);
+        let contents = fs::read(config_file)
+            .with_context(|| format!("Failed to read config file {config_file}"))?;
+        input.extend_from_slice(&contents);
+    }
+    for dependency in &config.config_dependencies {
+        input.extend_from_slice(dependency.as_bytes());
+        input.push(0);
+        // A module deleted since the config loaded hashes as empty.
+        input.extend_from_slice(&fs::read(dependency).unwrap_or_default());
+    }
+
+    Ok(format!("{:032x}", xxh3_128(&input)))
+}
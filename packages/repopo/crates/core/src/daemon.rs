@@ -0,0 +1,503 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, SystemTime};
+
+use crate::engine::{self, CheckOptions, PolicySet};
+use crate::files;
+use crate::filter::PolicyFilter;
+use crate::pool::SidecarPool;
+use crate::report::CheckReport;
+use crate::types::LoadConfigResponse;
+
+/// Name of the daemon socket within the git directory.
+const SOCKET_NAME: &str = "repopo-core.sock";
+
+/// How long a client has to send its request, and to take each part of the
+/// response, before the daemon moves on to the next client.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Resolve the daemon socket path for the repository at `git_root`.
+pub fn socket_path(git_root: &str) -> Result<PathBuf> {
+    files::git_path(git_root, SOCKET_NAME)
+}
+
+/// Request sent from a client to the daemon, one JSON line per connection.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+enum DaemonRequest {
+    /// Check files against the loaded policies.
+    Check(CheckParams),
+
+    /// Return the loaded policy metadata.
+    List(ListParams),
+
+    /// Describe the running daemon.
+    Status,
+
+    /// Stop the daemon.
+    Shutdown,
+}
+
+/// Parameters for a check request.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckParams {
+    /// Repo-relative paths to check.
+    files: Vec<String>,
+
+    /// The config path the client was invoked with.
+    config_path: Option<String>,
+
+    /// The client's working directory, which a relative `config_path` is
+    /// relative to.
+    #[serde(default)]
+    cwd: Option<PathBuf>,
+
+    /// Fix policy violations if possible.
+    fix: bool,
+
+    /// Directory for the incremental check cache, or `None` to disable it.
+    cache_dir: Option<PathBuf>,
+
+    /// Which policies to run.
+    #[serde(default)]
+    filter: PolicyFilter,
+}
+
+/// Parameters for a list request.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListParams {
+    /// The config path the client was invoked with.
+    config_path: Option<String>,
+
+    /// The client's working directory, which a relative `config_path` is
+    /// relative to.
+    #[serde(default)]
+    cwd: Option<PathBuf>,
+}
+
+/// Response sent from the daemon to a client.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "status", content = "data", rename_all = "snake_case")]
+enum DaemonResponse {
+    /// The request succeeded.
+    Ok(serde_json::Value),
+
+    /// The daemon cannot serve this request, e.g. because it was started
+    /// with a different config. The client should run it locally.
+    Declined(String),
+
+    /// The request failed.
+    Error(String),
+}
+
+/// Information about a running daemon.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonStatus {
+    /// Process ID of the daemon.
+    pub pid: u32,
+
+    /// Absolute path to the loaded config file, if any.
+    pub config_file: Option<String>,
+
+    /// Number of loaded policies.
+    pub policies: usize,
+
+    /// Number of requests served so far.
+    pub requests: usize,
+}
+
+/// A connection to a running daemon. Each connection carries one request.
+pub struct DaemonClient {
+    stream: UnixStream,
+}
+
+/// Connect to the daemon for the repository at `git_root`.
+///
+/// Returns `None` if no daemon is listening, including when a stale socket
+/// was left behind by one that exited uncleanly.
+pub fn connect(git_root: &str) -> Result<Option<DaemonClient>> {
+    let path = socket_path(git_root)?;
+    Ok(UnixStream::connect(path)
+        .ok()
+        .map(|stream| DaemonClient { stream }))
+}
+
+impl DaemonClient {
+    /// Send a request and wait for the response. Returns `None` if the
+    /// daemon declined the request.
+    fn request(mut self, request: &DaemonRequest) -> Result<Option<serde_json::Value>> {
+        let mut line = serde_json::to_string(request).context("Failed to serialize request")?;
+        line.push('\n');
+        self.stream
+            .write_all(line.as_bytes())
+            .context("Failed to send request to daemon")?;
+
+        let mut response = String::new();
+        BufReader::new(&self.stream)
+            .read_line(&mut response)
+            .context("Failed to read response from daemon")?;
+        if response.is_empty() {
+            anyhow::bail!("Daemon closed the connection unexpectedly");
+        }
+
+        let response: DaemonResponse =
+            serde_json::from_str(&response).context("Failed to parse daemon response")?;
+        match response {
+            DaemonResponse::Ok(data) => Ok(Some(data)),
+            DaemonResponse::Declined(_) => Ok(None),
+            DaemonResponse::Error(err) => anyhow::bail!("Daemon error: {err}"),
+        }
+    }
+
+    /// Check files through the daemon. Returns `None` if the daemon
+    /// cannot serve these options and the check should run locally.
+    pub fn check(self, files: &[String], options: &CheckOptions) -> Result<Option<CheckReport>> {
+        let request = DaemonRequest::Check(CheckParams {
+            files: files.to_vec(),
+            config_path: options.config_path.map(str::to_string),
+            cwd: env::current_dir().ok(),
+            fix: options.fix,
+            cache_dir: options.cache_dir.map(Path::to_path_buf),
+            filter: options.filter.clone(),
+        });
+
+        self.request(&request)?
+            .map(|data| serde_json::from_value(data).context("Failed to parse check report"))
+            .transpose()
+    }
+
+    /// Fetch the loaded policy metadata. Returns `None` if the daemon was
+    /// started with a different config.
+    pub fn list(self, config_path: Option<&str>) -> Result<Option<LoadConfigResponse>> {
+        let request = DaemonRequest::List(ListParams {
+            config_path: config_path.map(str::to_string),
+            cwd: env::current_dir().ok(),
+        });
+
+        self.request(&request)?
+            .map(|data| serde_json::from_value(data).context("Failed to parse policy list"))
+            .transpose()
+    }
+
+    /// Describe the running daemon.
+    pub fn status(self) -> Result<DaemonStatus> {
+        let data = self
+            .request(&DaemonRequest::Status)?
+            .context("Daemon declined status request")?;
+        serde_json::from_value(data).context("Failed to parse daemon status")
+    }
+
+    /// Ask the daemon to stop.
+    pub fn shutdown(self) -> Result<()> {
+        self.request(&DaemonRequest::Shutdown)?;
+        Ok(())
+    }
+}
+
+/// Modification time and size of a file, used to detect config changes.
+type FileStamp = (SystemTime, u64);
+
+fn stamp(path: &str) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Stamps of the loaded config files and the local modules they import,
+/// and of every config file git lists, so that adding a nested config also
+/// counts as a change.
+type ConfigStamps = BTreeMap<String, Option<FileStamp>>;
+
+fn config_stamps(git_root: &str, config: &LoadConfigResponse) -> Result<ConfigStamps> {
+    let listed = files::git_config_files(git_root)?.into_iter().map(|file| {
+        Path::new(git_root)
+            .join(file)
+            .to_string_lossy()
+            .into_owned()
+    });
+    let loaded = config
+        .config_files()
+        .into_iter()
+        .chain(config.config_dependencies.iter().map(String::as_str))
+        .map(str::to_string);
+    Ok(listed
+        .chain(loaded)
+        .map(|file| {
+            let stamp = stamp(&file);
+            (file, stamp)
+        })
+        .collect())
+}
+
+/// Resolve a config path against a working directory, and resolve symlinks
+/// if the file exists, so different spellings of one file compare equal.
+fn resolve_config_path(config_path: Option<&str>, cwd: &Path) -> Option<PathBuf> {
+    config_path.map(|path| {
+        let path = cwd.join(path);
+        fs::canonicalize(&path).unwrap_or(path)
+    })
+}
+
+/// Removes the socket file when the daemon exits.
+struct SocketGuard(PathBuf);
+
+impl Drop for SocketGuard {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Warm state held by the daemon between requests.
+struct Daemon<'a, F> {
+    git_root: &'a str,
+    config_path: Option<&'a str>,
+    /// `config_path` resolved against the daemon's working directory.
+    resolved_config_path: Option<PathBuf>,
+    verbose: bool,
+    spawn: F,
+    pool: SidecarPool,
+    policies: PolicySet,
+    config_stamps: ConfigStamps,
+    needs_restart: bool,
+    requests: usize,
+}
+
+impl<'a, F> Daemon<'a, F>
+where
+    F: Fn() -> Result<SidecarPool>,
+{
+    fn start(
+        git_root: &'a str,
+        config_path: Option<&'a str>,
+        verbose: bool,
+        spawn: F,
+    ) -> Result<Self> {
+        let mut pool = spawn()?;
+        let policies = PolicySet::load(&mut pool, config_path, verbose)?;
+        let config_stamps = config_stamps(git_root, &policies.config)?;
+        let cwd = env::current_dir().context("Failed to get current directory")?;
+
+        Ok(Self {
+            git_root,
+            config_path,
+            resolved_config_path: resolve_config_path(config_path, &cwd),
+            verbose,
+            spawn,
+            pool,
+            policies,
+            config_stamps,
+            needs_restart: false,
+            requests: 0,
+        })
+    }
+
+    /// Restart the sidecars and reload policies if a config file or a
+    /// module it imports changed, a config file was added or removed, or a
+    /// previous request left the pool in an unknown state.
+    ///
+    /// The sidecars are replaced rather than asked to reload, because the
+    /// JS runtime caches imported config modules.
+    fn refresh(&mut self) -> Result<()> {
+        let current = config_stamps(self.git_root, &self.policies.config)?;
+        if !self.needs_restart && current == self.config_stamps {
+            return Ok(());
+        }
+
+        if self.verbose {
+            eprintln!("Config changed; restarting sidecars.");
+        }
+
+        // Until the reload succeeds the old pool is not trusted.
+        self.needs_restart = true;
+        let mut pool = (self.spawn)()?;
+        let policies = PolicySet::load(&mut pool, self.config_path, self.verbose)?;
+        let old = std::mem::replace(&mut self.pool, pool);
+        self.shutdown_pool(old);
+
+        self.config_stamps = config_stamps(self.git_root, &policies.config)?;
+        self.policies = policies;
+        self.needs_restart = false;
+        Ok(())
+    }
+
+    fn shutdown_pool(&self, mut pool: SidecarPool) {
+        if let Err(err) = pool.shutdown()
+            && self.verbose
+        {
+            eprintln!("Failed to shut down sidecars: {err:#}");
+        }
+    }
+
+    /// Reject requests made with a different config than the daemon's.
+    /// Paths are compared after resolving them against the client's and the
+    /// daemon's working directories.
+    fn declines(&self, config_path: Option<&str>, cwd: Option<&Path>) -> Option<DaemonResponse> {
+        let resolved = match cwd {
+            Some(cwd) => resolve_config_path(config_path, cwd),
+            None => config_path.map(PathBuf::from),
+        };
+        (resolved != self.resolved_config_path).then(|| {
+            DaemonResponse::Declined(format!(
+                "daemon was started with config {:?}",
+                self.config_path
+            ))
+        })
+    }
+
+    fn handle(&mut self, request: DaemonRequest) -> Result<DaemonResponse> {
+        self.requests += 1;
+
+        match request {
+            DaemonRequest::Check(params) => {
+                if let Some(declined) =
+                    self.declines(params.config_path.as_deref(), params.cwd.as_deref())
+                {
+                    return Ok(declined);
+                }
+                self.refresh()?;
+                // A bad filter is the client's mistake, not a reason to
+                // restart the daemon.
+                if let Err(err) = params.filter.validate(&self.policies.config) {
+                    return Ok(DaemonResponse::Error(format!("{err:#}")));
+                }
+
+                let options = CheckOptions {
+                    config_path: self.config_path,
+                    fix: params.fix,
+                    verbose: self.verbose,
+                    quiet: true,
+                    cache_dir: params.cache_dir.as_deref(),
+                    filter: &params.filter,
+                };
+                let report = engine::check_files(
+                    &mut self.pool,
+                    &self.policies,
+                    params.files,
+                    self.git_root,
+                    &options,
+                )
+                .inspect_err(|_| self.needs_restart = true)?;
+
+                Ok(DaemonResponse::Ok(serde_json::to_value(&report)?))
+            }
+
+            DaemonRequest::List(params) => {
+                if let Some(declined) =
+                    self.declines(params.config_path.as_deref(), params.cwd.as_deref())
+                {
+                    return Ok(declined);
+                }
+                self.refresh()?;
+                Ok(DaemonResponse::Ok(serde_json::to_value(
+                    &self.policies.config,
+                )?))
+            }
+
+            DaemonRequest::Status => Ok(DaemonResponse::Ok(serde_json::to_value(DaemonStatus {
+                pid: process::id(),
+                config_file: self.policies.config.config_file.clone(),
+                policies: self.policies.config.policies.len(),
+                requests: self.requests,
+            })?)),
+
+            DaemonRequest::Shutdown => Ok(DaemonResponse::Ok(serde_json::Value::Null)),
+        }
+    }
+}
+
+/// Read one request from a connection, handle it, and write the response.
+/// Returns `false` if the daemon should stop.
+///
+/// A client that does not send its request or read the response in time is
+/// dropped, so it cannot hold up the clients queued behind it.
+fn serve_connection<F>(daemon: &mut Daemon<F>, stream: UnixStream) -> Result<bool>
+where
+    F: Fn() -> Result<SidecarPool>,
+{
+    stream
+        .set_read_timeout(Some(CLIENT_TIMEOUT))
+        .and_then(|()| stream.set_write_timeout(Some(CLIENT_TIMEOUT)))
+        .context("Failed to set connection timeouts")?;
+
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .context("Failed to read request")?;
+
+    let request: DaemonRequest = serde_json::from_str(&line).context("Failed to parse request")?;
+    let stop = matches!(request, DaemonRequest::Shutdown);
+
+    let response = daemon
+        .handle(request)
+        .unwrap_or_else(|err| DaemonResponse::Error(format!("{err:#}")));
+
+    let mut out = serde_json::to_string(&response).context("Failed to serialize response")?;
+    out.push('\n');
+    (&stream)
+        .write_all(out.as_bytes())
+        .context("Failed to write response")?;
+
+    Ok(!stop)
+}
+
+/// Run the daemon in the foreground until it is asked to stop.
+///
+/// The daemon keeps a warm sidecar pool and compiled policies behind a Unix
+/// socket in the git directory. `spawn` starts a fresh pool; it is called
+/// again whenever the config changes. Requests are served one at a time, so
+/// concurrent clients queue rather than race on the sidecars.
+pub fn serve<F>(git_root: &str, config_path: Option<&str>, verbose: bool, spawn: F) -> Result<()>
+where
+    F: Fn() -> Result<SidecarPool>,
+{
+    let path = socket_path(git_root)?;
+
+    if UnixStream::connect(&path).is_ok() {
+        anyhow::bail!("A daemon is already running at {}", path.display());
+    }
+
+    // A socket left behind by a daemon that did not exit cleanly.
+    match fs::remove_file(&path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("Failed to remove stale socket {}", path.display()));
+        }
+    }
+
+    let mut daemon = Daemon::start(git_root, config_path, verbose, spawn)?;
+
+    let listener = UnixListener::bind(&path)
+        .with_context(|| format!("Failed to listen on {}", path.display()))?;
+    let _guard = SocketGuard(path.clone());
+
+    eprintln!("Daemon listening on {}", path.display());
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("Failed to accept connection: {err}");
+                continue;
+            }
+        };
+
+        match serve_connection(&mut daemon, stream) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(err) => eprintln!("{err:#}"),
+        }
+    }
+
+    daemon.pool.shutdown()
+}
@@ -1,32 +1,42 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
-use regex::Regex;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::thread;
 use std::time::{Duration, Instant};
 
+use crate::cache::CheckCache;
+use crate::filter::PolicyFilter;
+use crate::gitindex::GitIndex;
 use crate::ipc::Sidecar;
-use crate::types::{LoadConfigResponse, PolicyMeta};
-
-/// A compiled policy ready for matching.
-struct CompiledPolicy {
-    meta: PolicyMeta,
-    match_regex: Regex,
-    exclude_regexes: Vec<Regex>,
-}
+use crate::native::{self, NativePolicies};
+use crate::pool::{BatchKind, BatchTask, SidecarPool};
+use crate::report::{CheckReport, FileResult, Outcome, PolicyReport};
+use crate::routing::Router;
+use crate::suppress::{Suppression, Suppressions};
+use crate::types::{HandlerResult, LoadConfigResponse, PolicyErrorResult, PolicyMeta, Severity};
 
 /// Performance statistics for policy execution.
-struct PerfStats {
-    total_files: usize,
-    handler_times: HashMap<String, Duration>,
-    resolver_times: HashMap<String, Duration>,
+#[derive(Serialize, Deserialize)]
+pub struct PerfStats {
+    pub total_files: usize,
+    pub handler_times: HashMap<String, Duration>,
+    pub resolver_times: HashMap<String, Duration>,
+    pub cache_hits: usize,
+    pub cache_misses: usize,
 }
 
 impl PerfStats {
-    fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             total_files: 0,
             handler_times: HashMap::new(),
             resolver_times: HashMap::new(),
+            cache_hits: 0,
+            cache_misses: 0,
         }
     }
 
@@ -44,7 +54,7 @@ impl PerfStats {
             .or_default() += duration;
     }
 
-    fn log(&self, verbose: bool) {
+    pub fn log(&self, verbose: bool) {
         if !verbose {
             return;
         }
@@ -52,6 +62,16 @@ impl PerfStats {
         eprintln!("\n{}", "Performance Statistics".bold());
         eprintln!("  Files processed: {}", self.total_files);
 
+        let cache_lookups = self.cache_hits + self.cache_misses;
+        if cache_lookups > 0 {
+            eprintln!(
+                "  Cache: {} hits, {} misses ({:.1}% hit rate)",
+                self.cache_hits,
+                self.cache_misses,
+                self.cache_hits as f64 * 100.0 / cache_lookups as f64
+            );
+        }
+
         if !self.handler_times.is_empty() {
             eprintln!("  Handler execution times:");
             let mut entries: Vec<_> = self.handler_times.iter().collect();
@@ -72,259 +92,693 @@ impl PerfStats {
     }
 }
 
-/// Build a Rust regex from a JS regex pattern and flags.
-fn compile_js_regex(pattern: &str, flags: &str) -> Result<Regex> {
-    let case_insensitive = flags.contains('i');
+/// Smallest chunk worth sending to a separate sidecar. Below this, IPC and
+/// scheduling overhead outweighs the parallelism gained by splitting a batch.
+const MIN_CHUNK_SIZE: usize = 32;
+
+/// Split one policy's files into batch tasks sized for the pool.
+fn chunk_tasks(
+    policy_id: usize,
+    files: &[String],
+    kind: BatchKind,
+    workers: usize,
+) -> Vec<BatchTask> {
+    let chunk_size = files.len().div_ceil(workers).max(MIN_CHUNK_SIZE);
+    files
+        .chunks(chunk_size)
+        .map(|chunk| BatchTask {
+            policy_id,
+            files: chunk.to_vec(),
+            kind,
+        })
+        .collect()
+}
 
-    let rust_pattern = if case_insensitive {
-        format!("(?i){pattern}")
-    } else {
-        pattern.to_string()
-    };
+/// A failure for a file whose native policy panicked.
+fn native_panic(name: &str, file: &str, payload: &(dyn Any + Send)) -> HandlerResult {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+    HandlerResult::Failure(PolicyErrorResult {
+        error: Some(format!(
+            "Native policy '{name}' panicked on {file}: {message}"
+        )),
+        fixable: Some(false),
+        ..PolicyErrorResult::default()
+    })
+}
 
-    Regex::new(&rust_pattern)
-        .with_context(|| format!("Failed to compile regex pattern: {pattern} (flags: {flags})"))
+/// Run a native policy on a file, reporting a panic as a failure of the
+/// file rather than ending the run.
+fn run_native_file(
+    policies: &PolicySet,
+    policy_id: usize,
+    file: &str,
+    git_root: &str,
+    index: Option<&GitIndex>,
+    resolve: bool,
+) -> HandlerResult {
+    let policy = policies.native[&policy_id].as_ref();
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        native::run(policy, git_root, file, index, resolve)
+    }))
+    .unwrap_or_else(|payload| {
+        native_panic(
+            &policies.config.policies[policy_id].name,
+            file,
+            payload.as_ref(),
+        )
+    })
 }
 
-/// Compile policy metadata into regex-ready policies.
-fn compile_policies(config: &LoadConfigResponse) -> Result<(Vec<CompiledPolicy>, Vec<Regex>)> {
-    let mut compiled = Vec::with_capacity(config.policies.len());
+/// Run a native policy on files in-process, split across up to `workers`
+/// threads the same way [`chunk_tasks`] splits batches for the pool.
+/// `resolve` decides per file whether to fix it. The files share one
+/// [`GitIndex`], so it is read at most once.
+fn run_native(
+    policies: &PolicySet,
+    policy_id: usize,
+    files: &[String],
+    git_root: &str,
+    workers: usize,
+    resolve: impl Fn(&str) -> bool + Sync,
+) -> Vec<(String, HandlerResult)> {
+    let chunk_size = files.len().div_ceil(workers).max(MIN_CHUNK_SIZE);
+    let resolve = &resolve;
+    let index = &GitIndex::new(git_root);
+    thread::scope(|s| {
+        let handles: Vec<_> = files
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let handle = s.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|file| {
+                            let result = run_native_file(
+                                policies,
+                                policy_id,
+                                file,
+                                git_root,
+                                Some(index),
+                                resolve(file),
+                            );
+                            (file.clone(), result)
+                        })
+                        .collect::<Vec<_>>()
+                });
+                (chunk, handle)
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|(chunk, handle)| {
+                handle.join().unwrap_or_else(|payload| {
+                    let name = &policies.config.policies[policy_id].name;
+                    chunk
+                        .iter()
+                        .map(|file| (file.clone(), native_panic(name, file, payload.as_ref())))
+                        .collect()
+                })
+            })
+            .collect()
+    })
+}
+
+/// Run a set of tasks on the pool and merge the results back per policy.
+///
+/// Results for each policy keep the order of its chunks, so reporting is
+/// deterministic no matter which sidecar finished first.
+fn run_merged(
+    pool: &mut SidecarPool,
+    tasks: Vec<BatchTask>,
+    policies: &[PolicyMeta],
+    stats: &mut PerfStats,
+) -> Result<HashMap<usize, Vec<(String, HandlerResult)>>> {
+    let mut merged: HashMap<usize, Vec<(String, HandlerResult)>> = HashMap::new();
+
+    for output in pool.run_batches(tasks)? {
+        let policy = &policies[output.policy_id];
+        let results = output
+            .results
+            .with_context(|| format!("Error executing batch for policy '{}'", policy.name))?;
+        merged.entry(output.policy_id).or_default().extend(results);
+        match output.kind {
+            BatchKind::Handler { .. } => stats.record_handler(&policy.name, output.elapsed),
+            BatchKind::Resolver => stats.record_resolver(&policy.name, output.elapsed),
+        }
+    }
+
+    Ok(merged)
+}
 
-    for meta in &config.policies {
-        let match_regex = compile_js_regex(&meta.match_pattern, &meta.match_flags)?;
+/// Options controlling a check run.
+pub struct CheckOptions<'a> {
+    /// Path to the config file.
+    pub config_path: Option<&'a str>,
 
-        let exclude_regexes: Vec<Regex> = meta
-            .exclude_files
-            .iter()
-            .map(|pattern| compile_js_regex(pattern, "i"))
-            .collect::<Result<Vec<_>>>()
-            .with_context(|| {
-                format!(
-                    "Failed to compile exclude patterns for policy '{}'",
-                    meta.name
-                )
-            })?;
-
-        compiled.push(CompiledPolicy {
-            meta: meta.clone(),
-            match_regex,
-            exclude_regexes,
-        });
+    /// Fix policy violations if possible.
+    pub fix: bool,
+
+    /// Show verbose output including per-policy timing.
+    pub verbose: bool,
+
+    /// Suppress all output except errors.
+    pub quiet: bool,
+
+    /// Directory for the incremental check cache, or `None` to disable it.
+    pub cache_dir: Option<&'a Path>,
+
+    /// Which policies to run.
+    pub filter: &'a PolicyFilter,
+}
+
+/// Policies loaded from the sidecar and compiled for matching.
+///
+/// Loading is the expensive part of a run, so long-lived modes load a
+/// `PolicySet` once and check many file sets against it.
+pub struct PolicySet {
+    /// The config response from the sidecar, with the native policies it
+    /// enables moved into its policies.
+    pub config: LoadConfigResponse,
+
+    /// Routes files to the policies that apply to them.
+    pub router: Router,
+
+    /// The native policies, by policy index. Every other policy runs in the
+    /// sidecar.
+    pub native: NativePolicies,
+}
+
+impl PolicySet {
+    /// Load the config through the sidecar pool and compile its patterns.
+    pub fn load(pool: &mut SidecarPool, config_path: Option<&str>, verbose: bool) -> Result<Self> {
+        if verbose {
+            eprintln!("Loading configuration...");
+        }
+
+        let config = pool.load_config(config_path)?;
+
+        if verbose {
+            eprintln!("{} policies loaded.", config.policies.len());
+            for p in &config.policies {
+                eprintln!("  - {}", p.name);
+            }
+        }
+
+        Self::new(config)
     }
 
-    let global_excludes: Vec<Regex> = config
-        .exclude_files
-        .iter()
-        .map(|pattern| compile_js_regex(pattern, "i"))
-        .collect::<Result<Vec<_>>>()
-        .context("Failed to compile global exclude patterns")?;
+    /// Create the native policies of an already loaded config and compile
+    /// its patterns.
+    pub fn new(mut config: LoadConfigResponse) -> Result<Self> {
+        let native = native::load(&mut config)?;
+        let router = Router::new(&config)?;
+        Ok(Self {
+            config,
+            router,
+            native,
+        })
+    }
+
+    /// The policies that apply to `file`, honoring global and per-policy
+    /// excludes, with their indexes.
+    pub fn policies_for(&self, file: &str) -> Vec<(usize, &PolicyMeta)> {
+        self.router
+            .route(file)
+            .policies
+            .into_iter()
+            .map(|id| (id, &self.config.policies[id]))
+            .collect()
+    }
 
-    Ok((compiled, global_excludes))
+    /// Run a policy on a file, in-process for a native policy and in the
+    /// sidecar otherwise.
+    pub fn run_one(
+        &self,
+        sidecar: &mut Sidecar,
+        policy_id: usize,
+        file: &str,
+        git_root: &str,
+        resolve: bool,
+    ) -> Result<HandlerResult> {
+        if self.native.contains_key(&policy_id) {
+            return Ok(run_native_file(
+                self, policy_id, file, git_root, None, resolve,
+            ));
+        }
+        let name = &self.config.policies[policy_id].name;
+        if resolve {
+            sidecar.run_resolver(name, file, git_root)
+        } else {
+            sidecar.run_handler(name, file, git_root, false)
+        }
+    }
+}
+
+/// Empty reports for each policy name, in config order, and the index of
+/// each policy's report. The policies a nested config overrides share a
+/// report with the policy they override.
+pub fn policy_reports(metas: &[PolicyMeta]) -> (Vec<PolicyReport>, Vec<usize>) {
+    let mut reports: Vec<PolicyReport> = Vec::new();
+    let report_ids = metas
+        .iter()
+        .map(|p| match reports.iter().position(|r| r.name == p.name) {
+            Some(report_id) => report_id,
+            None => {
+                reports.push(PolicyReport {
+                    name: p.name.clone(),
+                    description: p.description.clone(),
+                    results: Vec::new(),
+                });
+                reports.len() - 1
+            }
+        })
+        .collect();
+    (reports, report_ids)
 }
 
 /// Run the check engine using policy-first batching.
 ///
 /// This is the main entry point for the Rust core. It:
 /// 1. Loads config from the Node sidecar
-/// 2. Compiles regexes
-/// 3. For each policy, collects matching files and runs a single batch IPC call
-/// 4. Reports results
+/// 2. Compiles match and exclude patterns
+/// 3. Checks the files with [`check_files`]
 pub fn run_check(
-    sidecar: &mut Sidecar,
+    pool: &mut SidecarPool,
     files: Vec<String>,
     git_root: &str,
-    config_path: Option<&str>,
-    fix: bool,
-    verbose: bool,
-    quiet: bool,
-) -> Result<bool> {
-    // Step 1: Load config from sidecar
-    if verbose {
-        eprintln!("Loading configuration...");
-    }
+    options: &CheckOptions,
+) -> Result<CheckReport> {
+    let policies = PolicySet::load(pool, options.config_path, options.verbose)?;
+    check_files(pool, &policies, files, git_root, options)
+}
 
-    let config = sidecar.load_config(config_path, git_root)?;
+/// Check files against an already loaded [`PolicySet`].
+///
+/// 1. For each policy, collects matching files not already passing in the
+///    cache and splits them into batches
+/// 2. Dispatches the batches across the sidecar pool
+/// 3. Collects results into a [`CheckReport`] in policy order
+///
+/// Policies that match none of the files are not sent to the sidecar.
+/// When fixing, policies run one at a time so that two policies never
+/// rewrite the same file concurrently; only a single policy's batch is
+/// split across the pool.
+pub fn check_files(
+    pool: &mut SidecarPool,
+    policies: &PolicySet,
+    files: Vec<String>,
+    git_root: &str,
+    options: &CheckOptions,
+) -> Result<CheckReport> {
+    let CheckOptions {
+        fix,
+        verbose,
+        quiet,
+        cache_dir,
+        filter,
+        ..
+    } = *options;
+    let metas = &policies.config.policies;
+    filter.validate(&policies.config)?;
+
+    let mut cache = cache_dir
+        .map(|dir| CheckCache::load(git_root, dir, &policies.config))
+        .transpose()?;
+
+    // Route every non-empty file to its policies in one pass
+    let files: Vec<String> = files.into_iter().filter(|f| !f.is_empty()).collect();
+    let routing = policies.router.table(&files);
 
     if verbose {
-        eprintln!("{} policies loaded.", config.policies.len());
-        for p in &config.policies {
-            eprintln!("  - {}", p.name);
+        for f in &routing.excluded_globally {
+            eprintln!("Excluded all handlers: {f}");
         }
     }
 
-    // Step 2: Compile regexes
-    let (compiled_policies, global_excludes) = compile_policies(&config)?;
+    let mut stats = PerfStats::new();
+    stats.total_files = files.len() - routing.excluded_globally.len();
+    let (mut reports, report_ids) = policy_reports(metas);
 
-    // Step 3: Filter to non-empty, non-globally-excluded files
-    let eligible_files: Vec<&String> = files
+    // Read the suppression directives of every file a selected policy
+    // applies to. A directive for a policy that does not apply is unused.
+    let selected_files: Vec<&String> = routing
+        .files_by_policy
         .iter()
-        .filter(|f| {
-            if f.is_empty() {
-                return false;
-            }
-            if global_excludes.iter().any(|re| re.is_match(f)) {
-                if verbose {
-                    eprintln!("Excluded all handlers: {f}");
-                }
-                return false;
+        .zip(metas)
+        .filter(|(_, policy)| filter.selects(policy))
+        .flat_map(|(files, _)| files)
+        .collect::<BTreeSet<&String>>()
+        .into_iter()
+        .collect();
+    let suppressions = Suppressions::scan(git_root, &selected_files, cache.as_mut())?;
+    let mut not_applying: Vec<(usize, &str, &Suppression)> = Vec::new();
+    let mut unknown: BTreeMap<&str, Vec<(&str, &Suppression)>> = BTreeMap::new();
+    for (file, name, suppression) in suppressions.iter() {
+        let instances: Vec<usize> = (0..metas.len())
+            .filter(|&id| metas[id].name == name)
+            .collect();
+        let Some(&first) = instances.first() else {
+            unknown.entry(name).or_default().push((file, suppression));
+            continue;
+        };
+        if filter.selects(&metas[first])
+            && !instances
+                .iter()
+                .any(|&id| routing.files_by_policy[id].iter().any(|f| f == file))
+        {
+            not_applying.push((report_ids[first], file, suppression));
+        }
+    }
+    not_applying.sort_by_key(|(report_id, file, _)| (*report_id, *file));
+    for (report_id, file, suppression) in not_applying {
+        reports[report_id].results.push(unused_suppression(
+            file,
+            suppression,
+            "the policy does not apply to this file",
+        ));
+    }
+    // A directive naming a policy that is not configured fails its file,
+    // under a report for that name after the configured policies.
+    let unknown_reports: Vec<PolicyReport> = unknown
+        .into_iter()
+        .map(|(name, mut directives)| {
+            directives.sort_by_key(|(file, _)| *file);
+            PolicyReport {
+                name: name.to_string(),
+                description: "Not a configured policy.".to_string(),
+                results: directives
+                    .into_iter()
+                    .map(|(file, suppression)| unknown_suppression(file, name, suppression))
+                    .collect(),
             }
-            true
         })
         .collect();
 
-    let mut stats = PerfStats::new();
-    stats.total_files = eligible_files.len();
-    let mut had_failures = false;
-
     if fix && !quiet {
         eprintln!("Resolving errors if possible.");
     }
 
-    // Step 4: Policy-first batching — one IPC call per policy
-    for (policy_id, policy) in compiled_policies.iter().enumerate() {
-        // Collect files matching this policy
-        let matching_files: Vec<String> = eligible_files
-            .iter()
-            .filter(|f| {
-                if !policy.match_regex.is_match(f) {
-                    return false;
-                }
-                if policy.exclude_regexes.iter().any(|re| re.is_match(f)) {
-                    if verbose {
-                        eprintln!("Excluded from '{}' policy: {f}", policy.meta.name);
-                    }
-                    return false;
-                }
-                true
-            })
-            .map(|f| f.to_string())
-            .collect();
+    // Collect the files matching each policy
+    let workers = pool.max_workers();
+    let mut matched: Vec<(usize, Vec<String>)> = Vec::new();
+    let routed = routing
+        .files_by_policy
+        .into_iter()
+        .zip(routing.excluded_by_policy);
+    for (policy_id, (matching_files, excluded_files)) in routed.enumerate() {
+        let policy = &metas[policy_id];
+        if !filter.selects(policy) {
+            continue;
+        }
+        if verbose {
+            for f in &excluded_files {
+                eprintln!("Excluded from '{}' policy: {f}", policy.name);
+            }
+        }
+
+        // Files that passed this policy at their current content are
+        // reported as passing without another round trip to the sidecar.
+        let (cached_files, matching_files): (Vec<String>, Vec<String>) = match cache {
+            Some(ref mut cache) => matching_files
+                .into_iter()
+                .partition(|f| cache.is_cached_pass(policy, f)),
+            None => (Vec::new(), matching_files),
+        };
+
+        if cache.is_some() {
+            stats.cache_hits += cached_files.len();
+            stats.cache_misses += matching_files.len();
+        }
+
+        reports[report_ids[policy_id]]
+            .results
+            .extend(
+                cached_files
+                    .iter()
+                    .map(|file| match suppressions.get(file, &policy.name) {
+                        Some(suppression) => {
+                            unused_suppression(file, suppression, "the file passes this policy")
+                        }
+                        None => FileResult {
+                            file: file.clone(),
+                            outcome: Outcome::Pass,
+                            severity: policy.severity,
+                            result: HandlerResult::Pass(true),
+                        },
+                    }),
+            );
 
         if matching_files.is_empty() {
             continue;
         }
 
         if verbose {
-            eprintln!(
-                "Policy '{}': checking {} files (batch)",
-                policy.meta.name,
-                matching_files.len()
-            );
+            if cached_files.is_empty() {
+                eprintln!(
+                    "Policy '{}': checking {} files (batch)",
+                    policy.name,
+                    matching_files.len()
+                );
+            } else {
+                eprintln!(
+                    "Policy '{}': checking {} files (batch), {} cached",
+                    policy.name,
+                    matching_files.len(),
+                    cached_files.len()
+                );
+            }
         }
 
-        // Batch handler call — single IPC round-trip for all files
-        let start = Instant::now();
-        let batch_results = sidecar
-            .run_handler_batch(policy_id, &matching_files, fix)
-            .with_context(|| {
-                format!(
-                    "Error executing batch handler for policy '{}'",
-                    policy.meta.name
-                )
-            })?;
-        stats.record_handler(&policy.meta.name, start.elapsed());
-
-        // Collect files that need standalone resolver
-        let mut needs_resolver: Vec<String> = Vec::new();
-
-        for (file, result) in &batch_results {
-            if result.is_pass() {
-                continue;
-            }
+        matched.push((policy_id, matching_files));
+    }
 
-            if result.is_fixed() {
-                if !quiet {
-                    eprintln!(
-                        "Resolved {} policy failure for file: {file}",
-                        policy.meta.name
-                    );
-                }
-                continue;
-            }
+    // Dispatch batches in waves. Without --fix every policy goes
+    // into one wave; with --fix each policy is its own wave.
+    let waves: Vec<&[(usize, Vec<String>)]> = if fix {
+        matched.chunks(1).collect()
+    } else {
+        vec![&matched[..]]
+    };
 
-            if result.is_fix_failed() {
-                had_failures = true;
-                let msg = format!(
-                    "Error fixing {} policy failure in {file}",
-                    policy.meta.name
+    for wave in waves {
+        let tasks: Vec<BatchTask> = wave
+            .iter()
+            .filter(|(policy_id, _)| !policies.native.contains_key(policy_id))
+            .flat_map(|(policy_id, files)| {
+                // Suppressed files are only checked, never fixed.
+                let name = &metas[*policy_id].name;
+                let (suppressed, files): (Vec<String>, Vec<String>) = files
+                    .iter()
+                    .cloned()
+                    .partition(|f| suppressions.get(f, name).is_some());
+                let mut tasks = chunk_tasks(
+                    *policy_id,
+                    &files,
+                    BatchKind::Handler { resolve: fix },
+                    workers,
                 );
-                eprintln!("{}", msg.yellow());
-                if let Some(err) = result.error_message() {
-                    eprintln!("\t{err}");
-                }
+                tasks.extend(chunk_tasks(
+                    *policy_id,
+                    &suppressed,
+                    BatchKind::Handler { resolve: false },
+                    workers,
+                ));
+                tasks
+            })
+            .collect();
+        let mut handler_results = run_merged(pool, tasks, metas, &mut stats)?;
+
+        // Native policies run in-process. Suppressed files are only
+        // checked, never fixed.
+        for (policy_id, files) in wave {
+            if !policies.native.contains_key(policy_id) {
                 continue;
             }
+            let name = &metas[*policy_id].name;
+            let start = Instant::now();
+            let results = run_native(policies, *policy_id, files, git_root, workers, |file| {
+                fix && suppressions.get(file, name).is_none()
+            });
+            stats.record_handler(name, start.elapsed());
+            handler_results.insert(*policy_id, results);
+        }
 
-            // Failure — try standalone resolver if available, otherwise report
-            if fix && policy.meta.has_resolver {
-                needs_resolver.push(file.clone());
-            } else {
-                had_failures = true;
-                let fixable = result.is_fixable();
-                let fixable_tag = if fixable {
-                    " (autofixable)".green().to_string()
+        for (policy_id, _) in wave {
+            let policy = &metas[*policy_id];
+            let report = &mut reports[report_ids[*policy_id]];
+            let batch_results = handler_results.remove(policy_id).unwrap_or_default();
+
+            // Collect files that need standalone resolver
+            let mut needs_resolver: Vec<String> = Vec::new();
+
+            for (file, result) in batch_results {
+                if let Some(suppression) = suppressions.get(&file, &policy.name) {
+                    if let Some(ref mut cache) = cache {
+                        if result.is_pass() {
+                            cache.record_pass(policy, &file);
+                        } else {
+                            cache.forget(policy, &file);
+                        }
+                    }
+                    report.results.push(suppressed_result(
+                        file,
+                        policy,
+                        suppression,
+                        result,
+                        verbose,
+                    ));
+                    continue;
+                }
+
+                let outcome = if result.is_pass() {
+                    Outcome::Pass
+                } else if result.is_fixed() {
+                    Outcome::Fixed
+                } else if result.is_fix_failed() {
+                    Outcome::FixFailed
+                } else if fix && policy.has_resolver {
+                    // Failure — try standalone resolver below
+                    needs_resolver.push(file);
+                    continue;
                 } else {
-                    String::new()
+                    Outcome::Fail
                 };
-                let msg = format!(
-                    "'{}' policy failure{fixable_tag}: {file}",
-                    policy.meta.name.bold()
-                );
-                eprintln!("{}", msg);
-                if let Some(err) = result.error_message() {
-                    eprintln!("\t{err}");
+
+                if let Some(ref mut cache) = cache {
+                    if outcome == Outcome::Pass {
+                        cache.record_pass(policy, &file);
+                    } else {
+                        cache.forget(policy, &file);
+                    }
                 }
-            }
-        }
 
-        // Batch resolver call for failures that need fixing
-        if !needs_resolver.is_empty() {
-            if verbose {
-                eprintln!(
-                    "Policy '{}': resolving {} files (batch)",
-                    policy.meta.name,
-                    needs_resolver.len()
-                );
+                report.results.push(FileResult {
+                    file,
+                    outcome,
+                    severity: result.severity(policy.severity),
+                    result,
+                });
             }
 
-            let start = Instant::now();
-            let resolver_results = sidecar
-                .run_resolver_batch(policy_id, &needs_resolver)
-                .with_context(|| {
-                    format!(
-                        "Error executing batch resolver for policy '{}'",
-                        policy.meta.name
-                    )
-                })?;
-            stats.record_resolver(&policy.meta.name, start.elapsed());
-
-            for (file, resolve_result) in &resolver_results {
-                if resolve_result.is_fixed() || resolve_result.is_pass() {
-                    if !quiet {
-                        eprintln!(
-                            "Resolved {} policy failure for file: {file}",
-                            policy.meta.name
-                        );
-                    }
-                } else {
-                    had_failures = true;
-                    let msg = format!(
-                        "Error fixing {} policy failure in {file}",
-                        policy.meta.name
+            // Batch resolver call for failures that need fixing
+            if !needs_resolver.is_empty() {
+                if verbose {
+                    eprintln!(
+                        "Policy '{}': resolving {} files (batch)",
+                        policy.name,
+                        needs_resolver.len()
                     );
-                    eprintln!("{}", msg.yellow());
-                    if let Some(err) = resolve_result.error_message() {
-                        eprintln!("\t{err}");
+                }
+
+                let tasks = chunk_tasks(*policy_id, &needs_resolver, BatchKind::Resolver, workers);
+                let resolver_results = run_merged(pool, tasks, metas, &mut stats)?
+                    .remove(policy_id)
+                    .unwrap_or_default();
+
+                for (file, result) in resolver_results {
+                    let outcome = if result.is_fixed() || result.is_pass() {
+                        Outcome::Fixed
+                    } else {
+                        Outcome::FixFailed
+                    };
+                    if let Some(ref mut cache) = cache {
+                        cache.forget(policy, &file);
                     }
+                    report.results.push(FileResult {
+                        file,
+                        outcome,
+                        severity: result.severity(policy.severity),
+                        result,
+                    });
                 }
             }
         }
     }
 
-    // Step 5: Log performance stats
-    stats.log(verbose);
+    // Persist newly recorded passes
+    if let Some(ref cache) = cache {
+        cache.save()?;
+    }
+
+    // Policies the filter skipped are left out of the report entirely
+    let reports = reports
+        .into_iter()
+        .filter(|report| {
+            metas
+                .iter()
+                .any(|p| p.name == report.name && filter.selects(p))
+        })
+        .chain(unknown_reports)
+        .collect();
+
+    Ok(CheckReport {
+        policies: reports,
+        stats,
+        baseline: None,
+    })
+}
+
+/// Classify the result of a policy that a directive disables for a file.
+fn suppressed_result(
+    file: String,
+    policy: &PolicyMeta,
+    suppression: &Suppression,
+    result: HandlerResult,
+    verbose: bool,
+) -> FileResult {
+    if result.is_pass() {
+        return unused_suppression(&file, suppression, "the file passes this policy");
+    }
+
+    if verbose {
+        eprintln!(
+            "Suppressed '{}' policy for {file} by {suppression}",
+            policy.name
+        );
+    }
+    FileResult {
+        file,
+        outcome: Outcome::Suppressed,
+        severity: result.severity(policy.severity),
+        result,
+    }
+}
+
+/// An error for a directive that does not suppress a failure.
+fn unused_suppression(file: &str, suppression: &Suppression, why: &str) -> FileResult {
+    FileResult {
+        file: file.to_string(),
+        outcome: Outcome::Fail,
+        severity: Severity::Error,
+        result: HandlerResult::Failure(PolicyErrorResult {
+            error: Some(format!(
+                "Unused repopo-disable at {}:{}: {why}.",
+                suppression.source, suppression.line
+            )),
+            line: suppression.line_in(file),
+            ..PolicyErrorResult::default()
+        }),
+    }
+}
 
-    Ok(!had_failures)
+/// The failure for a directive that names a policy that is not configured.
+fn unknown_suppression(file: &str, name: &str, suppression: &Suppression) -> FileResult {
+    FileResult {
+        file: file.to_string(),
+        outcome: Outcome::Fail,
+        severity: Severity::Error,
+        result: HandlerResult::Failure(PolicyErrorResult {
+            error: Some(format!(
+                "Unknown policy '{name}' in repopo-disable at {}:{}.",
+                suppression.source, suppression.line
+            )),
+            line: suppression.line_in(file),
+            ..PolicyErrorResult::default()
+        }),
+    }
 }
 
 /// List all configured policies.
@@ -335,33 +789,124 @@ pub fn run_list(
     verbose: bool,
 ) -> Result<()> {
     let config = sidecar.load_config(config_path, git_root)?;
+    let policies = PolicySet::new(config)?;
+    print_list(&policies.config, verbose);
+    Ok(())
+}
+
+/// Print loaded policy metadata.
+pub fn print_list(config: &LoadConfigResponse, verbose: bool) {
+    // Leave out policies that a native policy with the same name replaces.
+    let in_effect = config.policies.iter().enumerate().filter(|(id, _)| {
+        config.scopes.is_empty() || config.scopes.iter().any(|s| s.policies.contains(id))
+    });
 
     println!("{}", "Configured policies:".bold());
-    for policy in &config.policies {
+    for (_, policy) in in_effect.clone() {
         let resolver_tag = if policy.has_resolver {
             " [auto-fixable]".green().to_string()
         } else {
             String::new()
         };
+        let native_tag = if policy.native {
+            " [native]".magenta().to_string()
+        } else {
+            String::new()
+        };
+
+        let scope_tag = if policy.scope.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", policy.scope).cyan().to_string()
+        };
 
         println!(
-            "  {} {}{resolver_tag}",
+            "  {} {}{resolver_tag}{native_tag}{scope_tag}",
             policy.name.bold(),
             policy.description.dimmed()
         );
 
         if verbose {
+            if !policy.tags.is_empty() {
+                println!("    tags: {}", policy.tags.join(", "));
+            }
             println!("    match: {}", policy.match_pattern);
             if !policy.exclude_files.is_empty() {
-                println!("    excludes: {}", policy.exclude_files.join(", "));
+                let excludes: Vec<String> =
+                    policy.exclude_files.iter().map(|p| p.to_string()).collect();
+                println!("    excludes: {}", excludes.join(", "));
             }
         }
     }
 
-    println!(
-        "\n{} policies configured.",
-        config.policies.len().to_string().bold()
-    );
+    let names: BTreeSet<&str> = in_effect.map(|(_, p)| p.name.as_str()).collect();
+    println!("\n{} policies configured.", names.len().to_string().bold());
 
-    Ok(())
+    if verbose && config.scopes.len() > 1 {
+        print_scopes(config);
+    }
+}
+
+/// Print how each nested config changes the policies of the config above
+/// it.
+fn print_scopes(config: &LoadConfigResponse) {
+    println!("\n{}", "Nested configs:".bold());
+    for (scope_id, scope) in config.scopes.iter().enumerate() {
+        if scope.directory.is_empty() {
+            continue;
+        }
+
+        // The parent is the deepest scope above this one; scopes come
+        // parents first.
+        let parent = config.scopes[..scope_id].iter().rev().find(|p| {
+            p.directory.is_empty() || scope.directory.starts_with(&format!("{}/", p.directory))
+        });
+        let parent_names: BTreeSet<&str> = parent
+            .map(|p| {
+                p.policies
+                    .iter()
+                    .map(|&id| config.policies[id].name.as_str())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        println!(
+            "  {}/ {}",
+            scope.directory.bold(),
+            scope.config_file.as_deref().unwrap_or_default().dimmed()
+        );
+        if let Some(parent) = parent {
+            let from = if parent.directory.is_empty() {
+                "the root config"
+            } else {
+                &parent.directory
+            };
+            println!("    extends: {from}");
+        }
+
+        let (mut overrides, mut adds, mut inherits) = (Vec::new(), Vec::new(), Vec::new());
+        for &id in &scope.policies {
+            let policy = &config.policies[id];
+            if policy.scope != scope.directory {
+                inherits.push(policy.name.as_str());
+            } else if parent_names.contains(policy.name.as_str()) {
+                overrides.push(policy.name.as_str());
+            } else {
+                adds.push(policy.name.as_str());
+            }
+        }
+        for (label, names) in [
+            ("overrides", overrides),
+            ("adds", adds),
+            ("inherits", inherits),
+        ] {
+            if !names.is_empty() {
+                println!("    {label}: {}", names.join(", "));
+            }
+        }
+        if !scope.exclude_files.is_empty() {
+            let excludes: Vec<String> = scope.exclude_files.iter().map(|p| p.to_string()).collect();
+            println!("    excludes: {}", excludes.join(", "));
+        }
+    }
 }
@@ -0,0 +1,121 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::path::{Component, Path, PathBuf};
+
+use crate::engine::PolicySet;
+use crate::files;
+
+/// Convert a path given on the command line to a repo-relative path with
+/// forward slashes. The path does not need to exist.
+pub fn repo_path(git_root: &str, cwd: &Path, path: &str) -> Result<String> {
+    let mut normalized = PathBuf::new();
+    for component in cwd.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+
+    let relative = normalized
+        .strip_prefix(git_root)
+        .ok()
+        .with_context(|| format!("{path} is outside the repository at {git_root}"))?;
+    Ok(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Print which patterns of which config entries decide whether each policy
+/// applies to each file.
+pub fn print(policies: &PolicySet, git_root: &str, files: &[String]) -> Result<()> {
+    let config = &policies.config;
+    if let Some(config_file) = &config.config_file {
+        println!("{} {config_file}", "Config:".dimmed());
+    }
+
+    for file in files {
+        println!("\n{}", file.bold());
+
+        if files::git_is_ignored(git_root, file)? {
+            println!(
+                "  {}",
+                "Ignored by git; check does not list this file.".yellow()
+            );
+        }
+
+        let explanation = policies.router.explain(file);
+        let scope = explanation
+            .scope
+            .map(|scope_id| &config.scopes[scope_id])
+            .filter(|scope| !scope.directory.is_empty());
+        if let Some(scope) = scope {
+            println!(
+                "  {} {}",
+                "Nested config:".dimmed(),
+                scope.config_file.as_deref().unwrap_or(&scope.directory)
+            );
+        }
+
+        for &index in &explanation.global_excludes {
+            println!(
+                "  {} by excludeFiles[{index}]: {}",
+                "Excluded from all policies".red(),
+                config.exclude_files[index]
+            );
+        }
+        if let Some(scope) = scope {
+            for &index in &explanation.scope_excludes {
+                println!(
+                    "  {} by the excludeFiles of nested configs: {}",
+                    "Excluded from all policies".red(),
+                    scope.exclude_files[index]
+                );
+            }
+        }
+        let excluded_globally =
+            !explanation.global_excludes.is_empty() || !explanation.scope_excludes.is_empty();
+
+        // Policies overridden in the file's directory are not listed.
+        let in_effect = config
+            .policies
+            .iter()
+            .zip(&explanation.policies)
+            .filter(|(_, policy)| policy.in_effect);
+        for (meta, policy) in in_effect {
+            let verdict = if !policy.matched {
+                "does not apply".dimmed()
+            } else if excluded_globally || !policy.excludes.is_empty() {
+                "excluded".red()
+            } else {
+                "applies".green()
+            };
+            if meta.scope.is_empty() {
+                println!("  {}: {verdict}", meta.name.bold());
+            } else {
+                println!(
+                    "  {}: {verdict} {}",
+                    meta.name.bold(),
+                    format!("(configured in {})", meta.scope).dimmed()
+                );
+            }
+
+            let matched = if policy.matched {
+                "matched".green()
+            } else {
+                "did not match".dimmed()
+            };
+            println!("    match {}: {matched}", meta.match_pattern);
+
+            for &index in &policy.excludes {
+                println!(
+                    "    excludeFiles[{index}] {}: {}",
+                    meta.exclude_files[index],
+                    "matched".red()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
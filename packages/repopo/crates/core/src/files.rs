@@ -1,6 +1,12 @@
 use anyhow::{Context, Result};
-use std::io::{self, BufRead};
-use std::process::Command;
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Names of repopo config files, as the sidecar searches for them.
+pub const CONFIG_FILE_NAMES: &[&str] =
+    &["repopo.config.ts", "repopo.config.mjs", "repopo.config.cjs"];
 
 /// Enumerate files tracked by git in the given repository root.
 ///
@@ -29,6 +35,125 @@ pub fn git_ls_files(git_root: &str) -> Result<Vec<String>> {
     Ok(files)
 }
 
+/// List the config files in the repository root and every subdirectory that
+/// git would list, as the sidecar finds nested configs.
+pub fn git_config_files(git_root: &str) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(["ls-files", "-co", "--exclude-standard", "--full-name", "--"])
+        .args(
+            CONFIG_FILE_NAMES
+                .iter()
+                .map(|name| format!(":(glob)**/{name}")),
+        )
+        .current_dir(git_root)
+        .output()
+        .context("Failed to run git ls-files")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git ls-files failed: {}", stderr);
+    }
+
+    let stdout = String::from_utf8(output.stdout).context("git output is not valid UTF-8")?;
+    Ok(stdout
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.replace('\\', "/"))
+        .collect())
+}
+
+/// Returns true if git ignores `file`, a repo-relative path.
+pub fn git_is_ignored(git_root: &str, file: &str) -> Result<bool> {
+    let output = Command::new("git")
+        .args(["check-ignore", "-q", "--", file])
+        .current_dir(git_root)
+        .output()
+        .context("Failed to run git check-ignore")?;
+
+    // Exit code 0 means ignored, 1 means not ignored.
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("git check-ignore failed: {}", stderr);
+        }
+    }
+}
+
+/// The repo-relative `paths` that git ignores. Tracked files are never
+/// ignored.
+pub fn git_ignored(git_root: &str, paths: &[String]) -> Result<HashSet<String>> {
+    if paths.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let mut child = Command::new("git")
+        .args(["check-ignore", "--stdin", "-z"])
+        .current_dir(git_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run git check-ignore")?;
+    let mut input = Vec::new();
+    for path in paths {
+        input.extend_from_slice(path.as_bytes());
+        input.push(0);
+    }
+    child
+        .stdin
+        .take()
+        .context("git check-ignore has no stdin")?
+        .write_all(&input)
+        .context("Failed to write to git check-ignore")?;
+    let output = child
+        .wait_with_output()
+        .context("Failed to run git check-ignore")?;
+
+    // Exit code 0 means some paths are ignored, 1 means none are.
+    match output.status.code() {
+        Some(0) | Some(1) => Ok(output
+            .stdout
+            .split(|&b| b == 0)
+            .filter(|path| !path.is_empty())
+            .map(|path| String::from_utf8_lossy(path).into_owned())
+            .collect()),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("git check-ignore failed: {}", stderr);
+        }
+    }
+}
+
+/// The directories git ignores, such as `node_modules` and `target`, as
+/// repo-relative paths. Directories inside them are not listed.
+pub fn git_ignored_dirs(git_root: &str) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args([
+            "ls-files",
+            "-oi",
+            "--exclude-standard",
+            "--directory",
+            "--full-name",
+        ])
+        .current_dir(git_root)
+        .output()
+        .context("Failed to run git ls-files")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git ls-files failed: {}", stderr);
+    }
+
+    let stdout = String::from_utf8(output.stdout).context("git output is not valid UTF-8")?;
+    Ok(stdout
+        .lines()
+        .filter_map(|line| line.strip_suffix('/'))
+        .map(|dir| dir.replace('\\', "/"))
+        .collect())
+}
+
 /// Read file paths from stdin (one per line).
 pub fn read_stdin_files() -> Result<Vec<String>> {
     let stdin = io::stdin();
@@ -63,3 +188,233 @@ pub fn find_git_root(start_dir: &str) -> Result<String> {
 
     Ok(root)
 }
+
+/// Resolve a path inside the git directory, e.g. `hooks`.
+///
+/// Uses `git rev-parse --git-path`, so settings such as `core.hooksPath`
+/// and linked worktrees are honored.
+pub fn git_path(git_root: &str, name: &str) -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", name])
+        .current_dir(git_root)
+        .output()
+        .context("Failed to run git rev-parse")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to locate git path {name}: {}", stderr);
+    }
+
+    let path = String::from_utf8(output.stdout)
+        .context("git output is not valid UTF-8")?
+        .trim()
+        .to_string();
+
+    // The path is relative to the directory git ran in.
+    Ok(Path::new(git_root).join(path))
+}
+
+/// What to compare the working tree against when listing changed files.
+#[derive(Debug, Clone, Copy)]
+pub enum ChangeBase<'a> {
+    /// Changes in the working tree (including untracked files) since the
+    /// merge base of the given ref and `HEAD`.
+    Ref(&'a str),
+    /// Changes staged in the index relative to `HEAD`.
+    Staged,
+}
+
+/// How a file changed relative to the comparison base.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    /// The file was renamed; `from` is the old path.
+    Renamed {
+        from: String,
+    },
+    /// The file was copied; `from` is the source path.
+    Copied {
+        from: String,
+    },
+    /// The file's type changed, e.g. from a regular file to a symlink.
+    TypeChanged,
+}
+
+/// A file that changed relative to a [`ChangeBase`].
+#[derive(Debug, Clone)]
+pub struct ChangedFile {
+    /// Repo-relative path with forward slashes. For renames and copies this
+    /// is the new path.
+    pub path: String,
+
+    /// How the file changed.
+    pub kind: ChangeKind,
+}
+
+impl ChangedFile {
+    /// Returns true if the file still exists after the change.
+    pub fn exists(&self) -> bool {
+        self.kind != ChangeKind::Deleted
+    }
+}
+
+/// Enumerate files that changed relative to `base`.
+///
+/// Renames are detected so that policies see the new path. Deleted files
+/// are included with [`ChangeKind::Deleted`] so callers can report them;
+/// use [`ChangedFile::exists`] to filter them out before checking.
+pub fn git_changed_files(git_root: &str, base: ChangeBase) -> Result<Vec<ChangedFile>> {
+    let mut args = vec![
+        "diff".to_string(),
+        "--name-status".to_string(),
+        "-z".to_string(),
+        "-M".to_string(),
+        "--no-ext-diff".to_string(),
+    ];
+
+    match base {
+        ChangeBase::Ref(reference) => args.push(git_merge_base(git_root, reference)?),
+        ChangeBase::Staged => args.push("--cached".to_string()),
+    }
+
+    let output = Command::new("git")
+        .args(&args)
+        .current_dir(git_root)
+        .output()
+        .context("Failed to run git diff")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git diff failed: {}", stderr);
+    }
+
+    let stdout = String::from_utf8(output.stdout).context("git output is not valid UTF-8")?;
+    let mut files = parse_name_status(&stdout)?;
+
+    // Untracked files are new relative to any ref, but are never staged.
+    if let ChangeBase::Ref(_) = base {
+        files.extend(
+            git_untracked_files(git_root)?
+                .into_iter()
+                .map(|path| ChangedFile {
+                    path,
+                    kind: ChangeKind::Added,
+                }),
+        );
+    }
+
+    Ok(files)
+}
+
+/// Parse the NUL-delimited output of `git diff --name-status -z`.
+fn parse_name_status(output: &str) -> Result<Vec<ChangedFile>> {
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    let mut files = Vec::new();
+
+    while let Some(status) = fields.next() {
+        let mut next_path = || {
+            fields
+                .next()
+                .map(|path| path.replace('\\', "/"))
+                .with_context(|| format!("Missing path for git diff status '{status}'"))
+        };
+
+        let kind = match status.chars().next() {
+            Some('A') => ChangeKind::Added,
+            Some('M') => ChangeKind::Modified,
+            Some('D') => ChangeKind::Deleted,
+            Some('T') => ChangeKind::TypeChanged,
+            Some('R') => ChangeKind::Renamed { from: next_path()? },
+            Some('C') => ChangeKind::Copied { from: next_path()? },
+            // Unmerged (U) and unknown (X) entries still exist on disk.
+            _ => ChangeKind::Modified,
+        };
+
+        files.push(ChangedFile {
+            path: next_path()?,
+            kind,
+        });
+    }
+
+    Ok(files)
+}
+
+/// Find the merge base of `reference` and `HEAD`.
+fn git_merge_base(git_root: &str, reference: &str) -> Result<String> {
+    let output = Command::new("git")
+        .args(["merge-base", reference, "HEAD"])
+        .current_dir(git_root)
+        .output()
+        .context("Failed to run git merge-base")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Could not find a merge base with '{reference}': {}", stderr);
+    }
+
+    Ok(String::from_utf8(output.stdout)
+        .context("git output is not valid UTF-8")?
+        .trim()
+        .to_string())
+}
+
+/// Enumerate untracked, non-ignored files.
+fn git_untracked_files(git_root: &str) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(["ls-files", "-o", "--exclude-standard", "--full-name", "-z"])
+        .current_dir(git_root)
+        .output()
+        .context("Failed to run git ls-files")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git ls-files failed: {}", stderr);
+    }
+
+    let stdout = String::from_utf8(output.stdout).context("git output is not valid UTF-8")?;
+
+    Ok(stdout
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| path.replace('\\', "/"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangeKind, parse_name_status};
+
+    #[test]
+    fn parses_name_status_records() {
+        let output = "R100\0old\\a.txt\0new/a.txt\0C075\0b.txt\0c.txt\0D\0d.txt\0U\0e.txt\0";
+        let files: Vec<(String, ChangeKind)> = parse_name_status(output)
+            .unwrap()
+            .into_iter()
+            .map(|file| (file.path, file.kind))
+            .collect();
+        assert_eq!(
+            files,
+            [
+                (
+                    "new/a.txt".to_string(),
+                    ChangeKind::Renamed {
+                        from: "old/a.txt".to_string()
+                    }
+                ),
+                (
+                    "c.txt".to_string(),
+                    ChangeKind::Copied {
+                        from: "b.txt".to_string()
+                    }
+                ),
+                ("d.txt".to_string(), ChangeKind::Deleted),
+                ("e.txt".to_string(), ChangeKind::Modified),
+            ]
+        );
+
+        let error = parse_name_status("M\0a.txt\0R100\0b.txt\0").unwrap_err();
+        assert_eq!(error.to_string(), "Missing path for git diff status 'R100'");
+    }
+}
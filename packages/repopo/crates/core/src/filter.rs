@@ -0,0 +1,64 @@
+//! Selection of the policies a check runs.
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{LoadConfigResponse, PolicyMeta};
+
+/// Which of the configured policies to run. The default runs all of them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyFilter {
+    /// Run only these policies, by name, plus any selected by `tags`.
+    #[serde(default)]
+    pub policies: Vec<String>,
+
+    /// Run only policies with any of these tags, plus any named in
+    /// `policies`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Never run these policies, even if selected otherwise.
+    #[serde(default)]
+    pub skip: Vec<String>,
+}
+
+impl PolicyFilter {
+    /// Returns true if the filter runs the given policy.
+    pub fn selects(&self, policy: &PolicyMeta) -> bool {
+        let included = (self.policies.is_empty() && self.tags.is_empty())
+            || self.policies.contains(&policy.name)
+            || policy.tags.iter().any(|tag| self.tags.contains(tag));
+        included && !self.skip.contains(&policy.name)
+    }
+
+    /// Check that every policy name and tag in the filter exists in the
+    /// config, so that a typo does not silently run nothing.
+    pub fn validate(&self, config: &LoadConfigResponse) -> Result<()> {
+        for name in self.policies.iter().chain(&self.skip) {
+            if !config.policies.iter().any(|p| &p.name == name) {
+                bail!(
+                    "Unknown policy '{name}'. Run `repopo-core list` to see the configured policies."
+                );
+            }
+        }
+
+        for tag in &self.tags {
+            if !config.policies.iter().any(|p| p.tags.contains(tag)) {
+                let mut known: Vec<&str> = config
+                    .policies
+                    .iter()
+                    .flat_map(|p| p.tags.iter().map(String::as_str))
+                    .collect();
+                known.sort_unstable();
+                known.dedup();
+                if known.is_empty() {
+                    bail!("Unknown tag '{tag}'. No configured policy has tags.");
+                }
+                bail!("Unknown tag '{tag}'. Known tags: {}", known.join(", "));
+            }
+        }
+
+        Ok(())
+    }
+}
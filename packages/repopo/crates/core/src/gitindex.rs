@@ -0,0 +1,184 @@
+//! File sizes from the git index and object database.
+//!
+//! The index records the blob of each staged file, and `git diff-files`
+//! lists the files that changed since they were staged. Every other staged
+//! file has the size of its blob, which one `git cat-file` reads for all of
+//! them, so native policies that only need sizes skip a stat per file. Blob
+//! sizes are exact, unlike the sizes in the index, which keep only the low
+//! 32 bits.
+
+use anyhow::{Context, Result, bail};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::thread;
+
+/// The tag `git ls-files -v` gives an entry that git keeps current, as
+/// opposed to skip-worktree and assume-unchanged entries.
+const CACHED: &str = "H";
+
+/// The modes of regular files in the index.
+const REGULAR_FILE_MODES: &[&str] = &["100644", "100755"];
+
+/// The sizes of the files in a repo's git index, read on first use.
+pub struct GitIndex<'a> {
+    git_root: &'a str,
+    sizes: OnceLock<Option<HashMap<String, u64>>>,
+}
+
+impl<'a> GitIndex<'a> {
+    pub fn new(git_root: &'a str) -> Self {
+        Self {
+            git_root,
+            sizes: OnceLock::new(),
+        }
+    }
+
+    /// The size of a repo-relative file, if it is staged and unchanged
+    /// since. Returns `None` for any other file, and for every file if git
+    /// cannot be read, so callers fall back to the file system.
+    pub fn size(&self, file: &str) -> Option<u64> {
+        let sizes = self.sizes.get_or_init(|| load(self.git_root).ok());
+        sizes.as_ref()?.get(file).copied()
+    }
+}
+
+/// Run git in the repo root with `input` on stdin, and return its output.
+fn git(git_root: &str, args: &[&str], input: Vec<u8>) -> Result<Vec<u8>> {
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(git_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run git {}", args[0]))?;
+
+    // Write on another thread, since git blocks on a full stdout pipe
+    // before it has read all of its input.
+    let mut stdin = child.stdin.take().context("git has no stdin")?;
+    let writer = thread::spawn(move || stdin.write_all(&input));
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to run git {}", args[0]))?;
+    writer
+        .join()
+        .map_err(|_| anyhow::anyhow!("Failed to write to git {}", args[0]))?
+        .with_context(|| format!("Failed to write to git {}", args[0]))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("git {} failed: {}", args[0], stderr);
+    }
+    Ok(output.stdout)
+}
+
+/// Read the sizes of the staged files that have not changed since they
+/// were staged.
+fn load(git_root: &str) -> Result<HashMap<String, u64>> {
+    let changed = git(git_root, &["diff-files", "--name-only", "-z"], Vec::new())?;
+    let changed: HashSet<&[u8]> = changed.split(|&b| b == 0).collect();
+
+    let staged = git(git_root, &["ls-files", "--stage", "-v", "-z"], Vec::new())?;
+    let mut blobs: Vec<(String, &str)> = Vec::new();
+    for entry in staged.split(|&b| b == 0).filter(|e| !e.is_empty()) {
+        let (info, path) = parse_entry(entry)?;
+        if changed.contains(path) {
+            continue;
+        }
+        if let (Some(blob), Ok(path)) = (info, std::str::from_utf8(path)) {
+            blobs.push((path.to_string(), blob));
+        }
+    }
+
+    let mut input = Vec::new();
+    for (_, blob) in &blobs {
+        input.extend_from_slice(blob.as_bytes());
+        input.push(b'\n');
+    }
+    let output = git(
+        git_root,
+        &["cat-file", "--batch-check=%(objectsize)"],
+        input,
+    )?;
+    let output = String::from_utf8(output).context("git output is not valid UTF-8")?;
+
+    // `cat-file` answers each blob on a line, in order. Blobs missing from
+    // the object database are left out.
+    let mut sizes = HashMap::with_capacity(blobs.len());
+    let mut lines = output.lines();
+    for (path, _) in blobs {
+        let line = lines.next().context("Truncated git cat-file output")?;
+        if let Ok(size) = line.parse() {
+            sizes.insert(path, size);
+        }
+    }
+    Ok(sizes)
+}
+
+/// Split an entry of `git ls-files --stage -v` into its blob and path. The
+/// blob is `None` for entries whose size git does not keep current:
+/// anything but an unconflicted regular file that is checked out and not
+/// assumed unchanged.
+fn parse_entry(entry: &[u8]) -> Result<(Option<&str>, &[u8])> {
+    let tab = entry
+        .iter()
+        .position(|&b| b == b'\t')
+        .context("Unexpected git ls-files output")?;
+    let info = std::str::from_utf8(&entry[..tab]).context("git output is not valid UTF-8")?;
+    let path = &entry[tab + 1..];
+
+    let fields: Vec<&str> = info.split(' ').collect();
+    let [tag, mode, blob, stage] = fields[..] else {
+        bail!("Unexpected git ls-files output: {info}");
+    };
+    let current = tag == CACHED && REGULAR_FILE_MODES.contains(&mode) && stage == "0";
+    Ok((current.then_some(blob), path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GitIndex;
+    use std::fs;
+    use std::process::Command;
+
+    #[test]
+    fn reads_sizes_of_unchanged_staged_files() {
+        let root = std::env::temp_dir().join(format!("repopo-gitindex-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("dir")).unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(args)
+                .current_dir(&root)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?}");
+        };
+
+        git(&["init", "-q"]);
+        fs::write(root.join("a.txt"), "a").unwrap();
+        fs::write(root.join("dir/b.txt"), "bb").unwrap();
+        fs::write(root.join("dir/c.txt"), "ccc").unwrap();
+        fs::write(root.join("dir/d.txt"), "dddd").unwrap();
+        fs::write(root.join("dir/e.txt"), "eeeee").unwrap();
+        fs::write(root.join("dir/f.txt"), "ffffff").unwrap();
+        git(&["add", "a.txt", "dir/b.txt", "dir/c.txt", "dir/e.txt"]);
+        git(&["add", "--intent-to-add", "dir/f.txt"]);
+        git(&["update-index", "--skip-worktree", "dir/c.txt"]);
+        git(&["update-index", "--assume-unchanged", "dir/e.txt"]);
+        fs::write(root.join("a.txt"), "changed").unwrap();
+
+        let index = GitIndex::new(root.to_str().unwrap());
+        assert_eq!(index.size("dir/b.txt"), Some(2));
+        assert_eq!(index.size("a.txt"), None);
+        assert_eq!(index.size("dir/c.txt"), None);
+        assert_eq!(index.size("dir/d.txt"), None);
+        assert_eq!(index.size("dir/e.txt"), None);
+        assert_eq!(index.size("dir/f.txt"), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,544 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::engine::CheckOptions;
+use crate::files::{self, ChangeBase};
+use crate::report::{CheckReport, Outcome};
+
+/// Marker line identifying hooks written by `repopo-core hook install`.
+const HOOK_MARKER: &str = "# Installed by repopo-core.";
+
+/// Name of the git hook that runs policies.
+const HOOK_NAME: &str = "pre-commit";
+
+/// Resolve the directory git runs hooks from, honoring `core.hooksPath`.
+fn hooks_dir(git_root: &str) -> Result<PathBuf> {
+    files::git_path(git_root, "hooks")
+}
+
+/// Quote a string for a POSIX shell.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Install the pre-commit hook.
+///
+/// The hook runs this binary by absolute path. An existing hook that was not
+/// written by repopo-core is only replaced when `force` is set.
+pub fn install(git_root: &str, fix: bool, force: bool) -> Result<PathBuf> {
+    let dir = hooks_dir(git_root)?;
+    let path = dir.join(HOOK_NAME);
+
+    if let Ok(existing) = fs::read_to_string(&path)
+        && !existing.contains(HOOK_MARKER)
+        && !force
+    {
+        anyhow::bail!(
+            "A {HOOK_NAME} hook already exists at {}. Pass --force to replace it.",
+            path.display()
+        );
+    }
+
+    let exe = env::current_exe().context("Failed to locate the repopo-core binary")?;
+    let fix_arg = if fix { " --fix" } else { "" };
+    let script = format!(
+        "#!/bin/sh\n\
+         {HOOK_MARKER} Remove with `repopo-core hook uninstall`.\n\
+         exec {} hook run{fix_arg}\n",
+        shell_quote(&exe.to_string_lossy())
+    );
+
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create hooks directory {}", dir.display()))?;
+    fs::write(&path, script).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to make {} executable", path.display()))?;
+    }
+
+    Ok(path)
+}
+
+/// Remove the pre-commit hook if it was written by repopo-core.
+pub fn uninstall(git_root: &str) -> Result<PathBuf> {
+    let path = hooks_dir(git_root)?.join(HOOK_NAME);
+
+    let existing = fs::read_to_string(&path)
+        .with_context(|| format!("No {HOOK_NAME} hook found at {}", path.display()))?;
+
+    if !existing.contains(HOOK_MARKER) {
+        anyhow::bail!(
+            "The {HOOK_NAME} hook at {} was not installed by repopo-core; leaving it in place.",
+            path.display()
+        );
+    }
+
+    fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    Ok(path)
+}
+
+/// Directory under `.git` that holds the working-tree copies of partially
+/// staged files while their staged content is checked.
+const BACKUP_DIR: &str = "repopo-hook-backup";
+
+/// Directory in the backup with a copy of each file, at its repo path.
+const BACKUP_FILES: &str = "files";
+
+/// Directory in the backup with an empty marker, at its repo path, for each
+/// file that was staged but deleted from the working tree.
+const BACKUP_ABSENT: &str = "absent";
+
+/// Working-tree copies of partially staged files, swapped out for their
+/// staged content while policies run.
+///
+/// Each original is written to a backup under `.git` before its file is
+/// overwritten, and restored when this is dropped. A file deleted from the
+/// working tree is written for the check and deleted again. If the process
+/// dies before then, the next run restores the backup, so unstaged work is
+/// never lost. Symlinks are left as they are, so their targets are never
+/// written.
+struct StagedSnapshot {
+    git_root: PathBuf,
+    backup: PathBuf,
+    /// Files copied to the backup.
+    files: Vec<String>,
+    /// Files that were not in the working tree.
+    absent: Vec<String>,
+}
+
+impl StagedSnapshot {
+    /// Replace each file's working-tree content with its staged blob.
+    fn checkout(git_root: &str, files: &[String]) -> Result<Self> {
+        let mut snapshot = Self {
+            git_root: PathBuf::from(git_root),
+            backup: files::git_path(git_root, BACKUP_DIR)?,
+            files: Vec::with_capacity(files.len()),
+            absent: Vec::new(),
+        };
+
+        for file in files {
+            let path = snapshot.git_root.join(file);
+            let exists = match fs::symlink_metadata(&path) {
+                Ok(metadata) if !metadata.is_file() => continue,
+                Ok(_) => true,
+                Err(err) if err.kind() == ErrorKind::NotFound => false,
+                Err(err) => {
+                    return Err(err).with_context(|| format!("Failed to read {}", path.display()));
+                }
+            };
+            let staged = staged_blob(git_root, file)?;
+
+            let dir = if exists { BACKUP_FILES } else { BACKUP_ABSENT };
+            let backup = snapshot.backup.join(dir).join(file);
+            create_parent(&backup)?;
+            if exists {
+                fs::copy(&path, &backup)
+                    .and_then(|_| fs::File::open(&backup)?.sync_all())
+                    .with_context(|| format!("Failed to back up {}", path.display()))?;
+                snapshot.files.push(file.clone());
+            } else {
+                fs::File::create(&backup)
+                    .and_then(|marker| marker.sync_all())
+                    .with_context(|| format!("Failed to back up {}", path.display()))?;
+                snapshot.absent.push(file.clone());
+                create_parent(&path)?;
+            }
+
+            fs::write(&path, staged)
+                .with_context(|| format!("Failed to write staged content to {}", path.display()))?;
+        }
+
+        Ok(snapshot)
+    }
+
+    fn contains(&self, file: &str) -> bool {
+        self.files.iter().chain(&self.absent).any(|f| f == file)
+    }
+}
+
+impl Drop for StagedSnapshot {
+    fn drop(&mut self) {
+        let mut restored = true;
+        for file in &self.files {
+            let path = self.git_root.join(file);
+            if let Err(err) = fs::copy(self.backup.join(BACKUP_FILES).join(file), &path) {
+                eprintln!("Failed to restore {}: {err}", path.display());
+                restored = false;
+            }
+        }
+        for file in &self.absent {
+            let path = self.git_root.join(file);
+            if let Err(err) = remove_if_exists(&path) {
+                eprintln!("Failed to delete {} again: {err}", path.display());
+                restored = false;
+            }
+        }
+        if !restored {
+            eprintln!(
+                "The unstaged content of these files is kept in {}.",
+                self.backup.display()
+            );
+        } else if self.backup.exists()
+            && let Err(err) = fs::remove_dir_all(&self.backup)
+        {
+            eprintln!("Failed to remove {}: {err}", self.backup.display());
+        }
+    }
+}
+
+/// Restore working-tree files left in the backup by a run that did not
+/// finish. Returns the restored files.
+///
+/// A file is only restored if it still has the staged content the run
+/// wrote. If it was edited since, both copies are kept and this fails, so
+/// neither edit is lost.
+fn restore_backup(git_root: &str) -> Result<Vec<String>> {
+    let backup = files::git_path(git_root, BACKUP_DIR)?;
+    if !backup.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut restored = Vec::new();
+    let mut changed = Vec::new();
+    for (dir, exists) in [(BACKUP_FILES, true), (BACKUP_ABSENT, false)] {
+        let root = backup.join(dir);
+        for (file, entry) in backup_entries(&root)? {
+            let target = Path::new(git_root).join(&file);
+            let current = match fs::read(&target) {
+                Ok(current) => Some(current),
+                Err(err) if err.kind() == ErrorKind::NotFound => None,
+                Err(err) => {
+                    return Err(err)
+                        .with_context(|| format!("Failed to read {}", target.display()));
+                }
+            };
+            // A file that was deleted and is still gone needs no restoring.
+            let unchanged = match &current {
+                Some(current) => staged_blob(git_root, &file).is_ok_and(|s| &s == current),
+                None => !exists,
+            };
+            if !unchanged {
+                changed.push((file, entry));
+                continue;
+            }
+
+            if exists {
+                fs::copy(&entry, &target)
+                    .with_context(|| format!("Failed to restore {}", target.display()))?;
+            } else {
+                remove_if_exists(&target)
+                    .with_context(|| format!("Failed to delete {}", target.display()))?;
+            }
+            fs::remove_file(&entry)
+                .with_context(|| format!("Failed to remove {}", entry.display()))?;
+            restored.push(file);
+        }
+    }
+
+    if !changed.is_empty() {
+        let files: Vec<String> = changed
+            .iter()
+            .map(|(file, entry)| format!("  {file} (backup: {})", entry.display()))
+            .collect();
+        anyhow::bail!(
+            "These files were edited after an interrupted hook run replaced them with their \
+             staged content, so their unstaged changes were not restored:\n{}\n\
+             Restore each from its backup, or delete the backup to keep the file as it is, \
+             then commit again. A backup under {BACKUP_ABSENT} means the file had been deleted.",
+            files.join("\n")
+        );
+    }
+
+    fs::remove_dir_all(&backup)
+        .with_context(|| format!("Failed to remove {}", backup.display()))?;
+    restored.sort_unstable();
+    Ok(restored)
+}
+
+/// The repo paths and locations of the entries in a backup directory.
+fn backup_entries(root: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut entries = Vec::new();
+    if !root.exists() {
+        return Ok(entries);
+    }
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let children =
+            fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+        for child in children {
+            let path = child?.path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            let file = path
+                .strip_prefix(root)
+                .expect("backup entries are under the backup directory")
+                .to_string_lossy()
+                .replace('\\', "/");
+            entries.push((file, path));
+        }
+    }
+    Ok(entries)
+}
+
+/// Create the parent directory of a path.
+fn create_parent(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    Ok(())
+}
+
+/// Delete a file, if it exists.
+fn remove_if_exists(path: &Path) -> std::io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Read the staged blob for a file from the index.
+fn staged_blob(git_root: &str, file: &str) -> Result<Vec<u8>> {
+    let output = Command::new("git")
+        .args(["cat-file", "blob", &format!(":{file}")])
+        .current_dir(git_root)
+        .output()
+        .context("Failed to run git cat-file")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to read staged content of {file}: {}", stderr);
+    }
+
+    Ok(output.stdout)
+}
+
+/// List files whose working-tree content differs from the index.
+fn unstaged_files(git_root: &str) -> Result<HashSet<String>> {
+    let output = Command::new("git")
+        .args(["diff", "--name-only", "-z", "--no-ext-diff"])
+        .current_dir(git_root)
+        .output()
+        .context("Failed to run git diff")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git diff failed: {}", stderr);
+    }
+
+    let stdout = String::from_utf8(output.stdout).context("git output is not valid UTF-8")?;
+    Ok(stdout
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| path.replace('\\', "/"))
+        .collect())
+}
+
+/// Add files to the index.
+fn git_add(git_root: &str, files: &[&str]) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let status = Command::new("git")
+        .args(["add", "--"])
+        .args(files)
+        .current_dir(git_root)
+        .status()
+        .context("Failed to run git add")?;
+
+    if !status.success() {
+        anyhow::bail!("git add failed for resolved files");
+    }
+
+    Ok(())
+}
+
+/// Run policies against the staged content of staged files.
+///
+/// Files with unstaged changes have their staged blob swapped into the
+/// working tree for the duration of the check, with the working-tree copies
+/// backed up under `.git` until they are restored. With `--fix`, files that a
+/// policy resolved are re-staged. For partially staged files only the
+/// staged version receives the fix; the working-tree copy is restored as it
+/// was.
+///
+/// `check` runs the policies on the staged file list, either locally or
+/// through a daemon.
+pub fn run(
+    git_root: &str,
+    options: &CheckOptions,
+    check: impl FnOnce(Vec<String>) -> Result<CheckReport>,
+) -> Result<CheckReport> {
+    for file in restore_backup(git_root)? {
+        eprintln!("Restored unstaged changes to {file} from an interrupted hook run.");
+    }
+
+    let staged: Vec<String> = files::git_changed_files(git_root, ChangeBase::Staged)?
+        .into_iter()
+        .filter(|f| f.exists())
+        .map(|f| f.path)
+        .collect();
+
+    if options.verbose {
+        eprintln!("{} staged files to check.", staged.len());
+    }
+
+    let unstaged = unstaged_files(git_root)?;
+    let partial: Vec<String> = staged
+        .iter()
+        .filter(|f| unstaged.contains(*f))
+        .cloned()
+        .collect();
+
+    if options.verbose && !partial.is_empty() {
+        eprintln!(
+            "Checking staged content of {} partially staged files.",
+            partial.len()
+        );
+    }
+
+    let snapshot = StagedSnapshot::checkout(git_root, &partial)?;
+    let report = check(staged)?;
+
+    if options.fix {
+        let mut resolved: Vec<&str> = report
+            .policies
+            .iter()
+            .flat_map(|p| &p.results)
+            .filter(|r| r.outcome == Outcome::Fixed)
+            .map(|r| r.file.as_str())
+            .collect();
+        resolved.sort_unstable();
+        resolved.dedup();
+
+        git_add(git_root, &resolved)?;
+
+        if !options.quiet {
+            for file in resolved.iter().filter(|f| snapshot.contains(f)) {
+                eprintln!(
+                    "Resolved staged content of {file}; its unstaged changes were not modified."
+                );
+            }
+        }
+    }
+
+    drop(snapshot);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StagedSnapshot, restore_backup};
+    use std::fs;
+    use std::process::Command;
+
+    fn git(root: &std::path::Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(root)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?}");
+    }
+
+    #[test]
+    fn keeps_unstaged_changes_through_an_interrupted_run() {
+        let root = std::env::temp_dir().join(format!("repopo-hook-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        let root_str = root.to_str().unwrap();
+        git(&root, &["init", "-q"]);
+        fs::write(root.join("src/a.txt"), "staged\n").unwrap();
+        git(&root, &["add", "src/a.txt"]);
+        fs::write(root.join("src/a.txt"), "unstaged\n").unwrap();
+
+        let files = vec!["src/a.txt".to_string()];
+        let snapshot = StagedSnapshot::checkout(root_str, &files).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("src/a.txt")).unwrap(),
+            "staged\n"
+        );
+        drop(snapshot);
+        assert_eq!(
+            fs::read_to_string(root.join("src/a.txt")).unwrap(),
+            "unstaged\n"
+        );
+        assert!(!root.join(".git").join(super::BACKUP_DIR).exists());
+
+        // A run that dies without dropping the snapshot is restored by the
+        // next run.
+        std::mem::forget(StagedSnapshot::checkout(root_str, &files).unwrap());
+        assert_eq!(
+            fs::read_to_string(root.join("src/a.txt")).unwrap(),
+            "staged\n"
+        );
+        assert_eq!(restore_backup(root_str).unwrap(), files);
+        assert_eq!(
+            fs::read_to_string(root.join("src/a.txt")).unwrap(),
+            "unstaged\n"
+        );
+        assert!(restore_backup(root_str).unwrap().is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn checks_files_deleted_from_the_working_tree_and_keeps_later_edits() {
+        let root = std::env::temp_dir().join(format!("repopo-hook-edits-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        let root_str = root.to_str().unwrap();
+        git(&root, &["init", "-q"]);
+        fs::write(root.join("src/a.txt"), "staged\n").unwrap();
+        fs::write(root.join("src/b.txt"), "staged\n").unwrap();
+        git(&root, &["add", "src"]);
+        fs::remove_file(root.join("src/a.txt")).unwrap();
+        fs::write(root.join("src/b.txt"), "unstaged\n").unwrap();
+
+        let files = vec!["src/a.txt".to_string(), "src/b.txt".to_string()];
+        let snapshot = StagedSnapshot::checkout(root_str, &files).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("src/a.txt")).unwrap(),
+            "staged\n"
+        );
+        drop(snapshot);
+        assert!(!root.join("src/a.txt").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("src/b.txt")).unwrap(),
+            "unstaged\n"
+        );
+
+        // Edits made after a run died are not overwritten by the backup.
+        std::mem::forget(StagedSnapshot::checkout(root_str, &files).unwrap());
+        fs::write(root.join("src/b.txt"), "edited\n").unwrap();
+        assert!(restore_backup(root_str).is_err());
+        assert!(!root.join("src/a.txt").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("src/b.txt")).unwrap(),
+            "edited\n"
+        );
+        let backup = root.join(".git").join(super::BACKUP_DIR);
+        assert_eq!(
+            fs::read_to_string(backup.join(super::BACKUP_FILES).join("src/b.txt")).unwrap(),
+            "unstaged\n"
+        );
+
+        // Once the backup is dealt with, runs go ahead.
+        fs::remove_dir_all(&backup).unwrap();
+        assert!(restore_backup(root_str).unwrap().is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use std::env;
 use std::io::{BufRead, BufReader, Write};
 use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
 
@@ -82,13 +83,22 @@ impl Sidecar {
     }
 
     /// Ask the sidecar to load the repopo configuration and return policy metadata.
+    ///
+    /// The sidecar runs in the git root, so a relative `config_path` is
+    /// resolved against this process's working directory first.
     pub fn load_config(
         &mut self,
         config_path: Option<&str>,
         git_root: &str,
     ) -> Result<LoadConfigResponse> {
+        let config_path = config_path
+            .map(|path| -> Result<String> {
+                let cwd = env::current_dir().context("Failed to get current directory")?;
+                Ok(cwd.join(path).to_string_lossy().into_owned())
+            })
+            .transpose()?;
         let req = IpcRequest::LoadConfig(LoadConfigParams {
-            config_path: config_path.map(String::from),
+            config_path,
             git_root: git_root.to_string(),
         });
 
@@ -117,18 +127,7 @@ impl Sidecar {
 
         let response = self.request(&req)?;
         let data = response.data.context("No data in run_handler response")?;
-
-        // The data can be `true` (pass) or an error object
-        if data.is_boolean() {
-            if data.as_bool() == Some(true) {
-                return Ok(HandlerResult::Pass(true));
-            }
-            anyhow::bail!("Handler returned false (unexpected)");
-        }
-
-        let result: crate::types::PolicyErrorResult =
-            serde_json::from_value(data).context("Failed to parse handler result")?;
-        Ok(HandlerResult::Failure(result))
+        Self::parse_handler_data(data)
     }
 
     /// Ask the sidecar to run a policy resolver on a file.
@@ -148,17 +147,7 @@ impl Sidecar {
         let data = response
             .data
             .context("No data in run_resolver response")?;
-
-        if data.is_boolean() {
-            if data.as_bool() == Some(true) {
-                return Ok(HandlerResult::Pass(true));
-            }
-            anyhow::bail!("Resolver returned false (unexpected)");
-        }
-
-        let result: crate::types::PolicyErrorResult =
-            serde_json::from_value(data).context("Failed to parse resolver result")?;
-        Ok(HandlerResult::Failure(result))
+        Self::parse_handler_data(data)
     }
 
     /// Ask the sidecar to run a policy handler on a batch of files.
@@ -243,6 +232,8 @@ impl Sidecar {
                     fixable: item.fixable,
                     fixed: item.fixed,
                     manual_fix: item.manual_fix,
+                    severity: item.severity,
+                    line: item.line,
                 }),
             ));
         }
@@ -250,6 +241,13 @@ impl Sidecar {
         results
     }
 
+    /// Stop a sidecar whose IPC stream can no longer be trusted, without
+    /// waiting for it to answer.
+    pub fn kill(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+
     /// Tell the sidecar to shut down gracefully.
     pub fn shutdown(&mut self) -> Result<()> {
         let req = IpcRequest::Shutdown;
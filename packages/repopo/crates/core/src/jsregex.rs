@@ -0,0 +1,910 @@
+//! Translation of JavaScript `RegExp` syntax into Rust regex syntax.
+//!
+//! Policy patterns are written as JS regular expressions and arrive from
+//! the sidecar as a `source` string and a `flags` string. The two dialects
+//! overlap heavily but differ in ways that matter for path matching: JS
+//! `\d`, `\w`, `\s`, and `\b` are ASCII-based, `.` excludes `\r` and the
+//! Unicode line separators, `[` is literal inside a class, and non-unicode
+//! patterns treat unknown escapes such as `\a` or `\z` as the letter itself.
+//!
+//! Patterns are translated for the `regex` crate when possible. Patterns
+//! that use lookaround or backreferences are translated for `fancy-regex`
+//! instead, which backtracks.
+//!
+//! Known differences from JS:
+//! - Without the `u` flag, JS matches UTF-16 code units, so `.` matches
+//!   half of an astral character. Here `.` always matches a whole character.
+//! - With the `m` flag, `^` and `$` only treat `\n` as a line terminator.
+//! - Backreferences to groups that did not participate in the match fail
+//!   instead of matching the empty string.
+//! - Lookbehind must have a bounded length.
+//! - The `v` flag and escapes for lone surrogates are not supported.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+
+/// A JavaScript regular expression compiled for matching in Rust.
+#[derive(Debug)]
+pub struct JsRegex {
+    engine: Engine,
+}
+
+#[derive(Debug)]
+enum Engine {
+    /// Linear-time matching with the `regex` crate.
+    Fast(Regex),
+    /// Backtracking matching for lookaround and backreferences.
+    Backtracking(fancy_regex::Regex),
+}
+
+impl JsRegex {
+    /// Compile a JS regex `source` with JS `flags` (e.g. `"iu"`).
+    pub fn new(source: &str, flags: &str) -> Result<Self> {
+        Self::compile(source, flags)
+            .with_context(|| format!("Failed to compile regex pattern: {source} (flags: {flags})"))
+    }
+
+    fn compile(source: &str, flags: &str) -> Result<Self> {
+        let flags = Flags::parse(flags)?;
+
+        let fast = Translator::new(source, flags, Target::Fast).translate()?;
+        if !fast.needs_backtracking {
+            return Ok(Self {
+                engine: Engine::Fast(Regex::new(&fast.pattern)?),
+            });
+        }
+
+        let translated = Translator::new(source, flags, Target::Backtracking).translate()?;
+        Ok(Self {
+            engine: Engine::Backtracking(fancy_regex::Regex::new(&translated.pattern)?),
+        })
+    }
+
+    /// Returns true if the pattern matches anywhere in `text`, like JS
+    /// `RegExp.prototype.test` with `lastIndex` reset to 0.
+    ///
+    /// A backtracking pattern that exceeds the backtracking limit is
+    /// treated as not matching.
+    pub fn is_match(&self, text: &str) -> bool {
+        match &self.engine {
+            Engine::Fast(re) => re.is_match(text),
+            Engine::Backtracking(re) => re.is_match(text).unwrap_or(false),
+        }
+    }
+
+    /// The translated pattern, if it runs on the `regex` crate and can be
+    /// combined into a [`regex::RegexSet`].
+    pub fn fast_pattern(&self) -> Option<&str> {
+        match &self.engine {
+            Engine::Fast(re) => Some(re.as_str()),
+            Engine::Backtracking(_) => None,
+        }
+    }
+}
+
+/// The JS flags that affect matching.
+#[derive(Debug, Clone, Copy, Default)]
+struct Flags {
+    ignore_case: bool,
+    multiline: bool,
+    dot_all: bool,
+    unicode: bool,
+    sticky: bool,
+}
+
+impl Flags {
+    fn parse(flags: &str) -> Result<Self> {
+        let mut parsed = Self::default();
+        for flag in flags.chars() {
+            match flag {
+                'i' => parsed.ignore_case = true,
+                'm' => parsed.multiline = true,
+                's' => parsed.dot_all = true,
+                'u' => parsed.unicode = true,
+                'y' => parsed.sticky = true,
+                // Global and match indices do not affect whether a test matches.
+                'g' | 'd' => {}
+                'v' => anyhow::bail!("The 'v' flag is not supported"),
+                other => anyhow::bail!("Invalid regex flag '{other}'"),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// Which engine a translation targets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Fast,
+    Backtracking,
+}
+
+/// The result of translating a pattern.
+struct Translation {
+    pattern: String,
+
+    /// The pattern uses features only the backtracking engine supports. For
+    /// a [`Target::Fast`] translation, the pattern is then incomplete.
+    needs_backtracking: bool,
+}
+
+/// The characters JS `\d` matches.
+const DIGIT: &[(char, char)] = &[('0', '9')];
+
+/// The characters JS `\w` matches.
+const WORD: &[(char, char)] = &[('0', '9'), ('A', 'Z'), ('_', '_'), ('a', 'z')];
+
+/// The characters JS `\s` matches: WhiteSpace and LineTerminator.
+const SPACE: &[(char, char)] = &[
+    ('\t', '\r'),
+    (' ', ' '),
+    ('\u{a0}', '\u{a0}'),
+    ('\u{1680}', '\u{1680}'),
+    ('\u{2000}', '\u{200a}'),
+    ('\u{2028}', '\u{2029}'),
+    ('\u{202f}', '\u{202f}'),
+    ('\u{205f}', '\u{205f}'),
+    ('\u{3000}', '\u{3000}'),
+    ('\u{feff}', '\u{feff}'),
+];
+
+/// The characters JS `.` matches without the `s` flag.
+const NOT_LINE_TERMINATOR: &str = r"[^\n\r\x{2028}\x{2029}]";
+
+/// A class that matches nothing, e.g. JS `[]`.
+const NOTHING: &str = r"[^\x00-\x{10FFFF}]";
+
+/// A class that matches any character, e.g. JS `[^]`.
+const ANYTHING: &str = r"(?s:.)";
+
+/// An ASCII word boundary, built from lookaround for the backtracking
+/// engine, which does not support disabling Unicode.
+const WORD_BOUNDARY: &str =
+    r"(?:(?<=[0-9A-Za-z_])(?![0-9A-Za-z_])|(?<![0-9A-Za-z_])(?=[0-9A-Za-z_]))";
+const NOT_WORD_BOUNDARY: &str =
+    r"(?:(?<=[0-9A-Za-z_])(?=[0-9A-Za-z_])|(?<![0-9A-Za-z_])(?![0-9A-Za-z_]))";
+
+/// One item in a character class.
+enum ClassItem {
+    /// Inclusive character ranges.
+    Ranges(Vec<(char, char)>),
+    /// Rust class syntax with no range form, e.g. `\p{L}`.
+    Raw(String),
+}
+
+/// A single class atom, before range detection.
+enum ClassAtom {
+    Char(char),
+    Item(ClassItem),
+}
+
+/// Flag state that modifier groups such as `(?i:...)` can change.
+#[derive(Debug, Clone, Copy)]
+struct Scope {
+    ignore_case: bool,
+    dot_all: bool,
+}
+
+struct Translator {
+    chars: Vec<char>,
+    pos: usize,
+    flags: Flags,
+    target: Target,
+    out: String,
+    needs_backtracking: bool,
+
+    /// Names of the capturing groups, in order. Unnamed groups are `None`.
+    groups: Vec<Option<String>>,
+
+    scope: Scope,
+    scopes: Vec<Scope>,
+}
+
+impl Translator {
+    fn new(source: &str, flags: Flags, target: Target) -> Self {
+        let chars: Vec<char> = source.chars().collect();
+        let groups = scan_groups(&chars);
+        Self {
+            chars,
+            pos: 0,
+            flags,
+            target,
+            out: String::new(),
+            needs_backtracking: false,
+            groups,
+            scope: Scope {
+                ignore_case: flags.ignore_case,
+                dot_all: flags.dot_all,
+            },
+            scopes: Vec::new(),
+        }
+    }
+
+    fn translate(mut self) -> Result<Translation> {
+        if self.flags.ignore_case {
+            self.out.push_str("(?i)");
+        }
+        if self.flags.multiline {
+            self.out.push_str("(?m)");
+        }
+        // `test` with a sticky regex only matches at `lastIndex`, which is 0.
+        if self.flags.sticky {
+            self.out.push_str(r"\A(?:");
+        }
+
+        while let Some(c) = self.next() {
+            match c {
+                '\\' => self.escape()?,
+                '[' => self.class()?,
+                '(' => self.group()?,
+                ')' => {
+                    self.scope = self.scopes.pop().context("Unmatched ')'")?;
+                    self.out.push(')');
+                }
+                '.' => {
+                    let dot = if self.scope.dot_all {
+                        ANYTHING
+                    } else {
+                        NOT_LINE_TERMINATOR
+                    };
+                    self.out.push_str(dot);
+                }
+                '{' => match self.quantifier_len() {
+                    Some(len) => {
+                        self.out.push('{');
+                        self.out.extend(&self.chars[self.pos..self.pos + len]);
+                        self.pos += len;
+                    }
+                    // Not a valid quantifier, so JS treats the brace literally.
+                    None => self.literal('{'),
+                },
+                '^' | '$' | '|' | '*' | '+' | '?' => self.out.push(c),
+                c => self.literal(c),
+            }
+        }
+
+        if !self.scopes.is_empty() {
+            anyhow::bail!("Unterminated group");
+        }
+        if self.flags.sticky {
+            self.out.push(')');
+        }
+
+        Ok(Translation {
+            pattern: self.out,
+            needs_backtracking: self.needs_backtracking,
+        })
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.get(self.pos).copied();
+        if c.is_some() {
+            self.pos += 1;
+        }
+        c
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the length of the `{n}`, `{n,}`, or `{n,m}` quantifier body
+    /// (including the closing brace) starting at the current position.
+    fn quantifier_len(&self) -> Option<usize> {
+        let rest = &self.chars[self.pos..];
+        let close = rest.iter().position(|&c| c == '}')?;
+        let body: String = rest[..close].iter().collect();
+        let (min, max) = match body.split_once(',') {
+            Some((min, max)) => (min, Some(max)),
+            None => (body.as_str(), None),
+        };
+        let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+        let valid = is_number(min) && max.is_none_or(|max| max.is_empty() || is_number(max));
+        valid.then_some(close + 1)
+    }
+
+    /// Emit a literal character.
+    fn literal(&mut self, c: char) {
+        // Without the `u` flag, JS case folding never maps a non-ASCII
+        // character to an ASCII one, so /s/i does not match 'ſ' and /k/i
+        // does not match the Kelvin sign. Rust's Unicode folding does.
+        if self.scope.ignore_case && !self.flags.unicode && matches!(c, 's' | 'S' | 'k' | 'K') {
+            let lower = c.to_ascii_lowercase();
+            let upper = c.to_ascii_uppercase();
+            self.out.push_str(&format!("(?-i:[{lower}{upper}])"));
+            return;
+        }
+        push_literal(&mut self.out, c);
+    }
+
+    fn backreference(&mut self, group: usize) {
+        self.needs_backtracking = true;
+        self.out.push_str(&format!(r"\{group}"));
+    }
+
+    fn word_boundary(&mut self, negated: bool) {
+        let boundary = match (self.target, negated) {
+            (Target::Fast, false) => r"(?-u:\b)",
+            (Target::Fast, true) => r"(?-u:\B)",
+            (Target::Backtracking, false) => WORD_BOUNDARY,
+            (Target::Backtracking, true) => NOT_WORD_BOUNDARY,
+        };
+        self.out.push_str(boundary);
+    }
+
+    /// Translate an escape outside a character class. The backslash has
+    /// been consumed.
+    fn escape(&mut self) -> Result<()> {
+        let c = self.next().context(r"Pattern ends with '\'")?;
+        match c {
+            'd' | 'D' | 'w' | 'W' | 's' | 'S' => {
+                let item = self.class_escape_item(c);
+                self.emit_class(false, vec![item]);
+            }
+            'b' => self.word_boundary(false),
+            'B' => self.word_boundary(true),
+            '1'..='9' => {
+                self.pos -= 1;
+                let start = self.pos;
+                let number = self.decimal();
+                if number >= 1 && number <= self.groups.len() {
+                    self.backreference(number);
+                } else if self.flags.unicode {
+                    anyhow::bail!(r"Invalid escape '\{number}'");
+                } else {
+                    self.pos = start;
+                    let c = self.legacy_octal_or_digit();
+                    self.literal(c);
+                }
+            }
+            'k' if self.flags.unicode || self.groups.iter().any(Option::is_some) => {
+                let name = self.group_name()?;
+                let group = self
+                    .groups
+                    .iter()
+                    .position(|g| g.as_deref() == Some(name.as_str()))
+                    .with_context(|| format!("Unknown group name '{name}'"))?;
+                self.backreference(group + 1);
+            }
+            'p' | 'P' if self.flags.unicode => {
+                let property = self.property(c)?;
+                self.out.push_str(&property);
+            }
+            _ => {
+                let c = self.character_escape(c, false)?;
+                self.literal(c);
+            }
+        }
+        Ok(())
+    }
+
+    /// Translate an escape that stands for a single character. Handles
+    /// identity escapes such as `\z`, which JS treats as the letter itself
+    /// without the `u` flag.
+    fn character_escape(&mut self, c: char, in_class: bool) -> Result<char> {
+        Ok(match c {
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\u{b}',
+            'b' if in_class => '\u{8}',
+            'c' => match self.peek() {
+                Some(letter) if letter.is_ascii_alphabetic() => {
+                    self.pos += 1;
+                    char::from(letter as u8 % 32)
+                }
+                _ if self.flags.unicode => anyhow::bail!(r"Invalid escape '\c'"),
+                // JS reads a lone `\c` as a backslash followed by `c`.
+                _ => {
+                    self.pos -= 1;
+                    '\\'
+                }
+            },
+            '0' if !self.peek().is_some_and(|d| d.is_ascii_digit()) => '\0',
+            '0'..='9' if in_class || c == '0' => {
+                if self.flags.unicode {
+                    anyhow::bail!(r"Invalid escape '\{c}'");
+                }
+                self.pos -= 1;
+                self.legacy_octal_or_digit()
+            }
+            'x' => match self.hex_digits(2) {
+                Some(value) => char::from_u32(value).context("Invalid \\x escape")?,
+                None if self.flags.unicode => anyhow::bail!(r"Invalid escape '\x'"),
+                None => 'x',
+            },
+            'u' => self.unicode_escape()?,
+            c if self.flags.unicode && !is_syntax_character(c) && c != '/' && c != '-' => {
+                anyhow::bail!(r"Invalid escape '\{c}'")
+            }
+            c => c,
+        })
+    }
+
+    /// Parse a run of decimal digits.
+    fn decimal(&mut self) -> usize {
+        let mut number = 0usize;
+        while let Some(d) = self.peek().and_then(|c| c.to_digit(10)) {
+            number = number.saturating_mul(10).saturating_add(d as usize);
+            self.pos += 1;
+        }
+        number
+    }
+
+    /// Parse a legacy octal escape such as `\101`, or the literal digit
+    /// for `\8` and `\9`.
+    fn legacy_octal_or_digit(&mut self) -> char {
+        let first = self.next().unwrap_or('0');
+        if first == '8' || first == '9' {
+            return first;
+        }
+
+        let mut value = first.to_digit(8).unwrap_or(0);
+        // Up to three digits, as long as the value stays below 0o400.
+        let max_len = if first <= '3' { 3 } else { 2 };
+        for _ in 1..max_len {
+            match self.peek().and_then(|c| c.to_digit(8)) {
+                Some(d) => {
+                    value = value * 8 + d;
+                    self.pos += 1;
+                }
+                None => break,
+            }
+        }
+        char::from_u32(value).unwrap_or('\0')
+    }
+
+    /// Parse exactly `count` hex digits.
+    fn hex_digits(&mut self, count: usize) -> Option<u32> {
+        let digits: String = self.chars.get(self.pos..self.pos + count)?.iter().collect();
+        let value = u32::from_str_radix(&digits, 16).ok()?;
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        self.pos += count;
+        Some(value)
+    }
+
+    /// Parse the rest of a `\u` escape: `\uXXXX`, a surrogate pair of them,
+    /// or `\u{X...}` with the `u` flag.
+    fn unicode_escape(&mut self) -> Result<char> {
+        if self.flags.unicode && self.eat('{') {
+            let close = self.chars[self.pos..]
+                .iter()
+                .position(|&c| c == '}')
+                .context(r"Unterminated '\u{' escape")?;
+            let digits: String = self.chars[self.pos..self.pos + close].iter().collect();
+            self.pos += close + 1;
+            let value = u32::from_str_radix(&digits, 16).context(r"Invalid '\u{' escape")?;
+            return char::from_u32(value).context(r"Invalid '\u{' escape");
+        }
+
+        let Some(unit) = self.hex_digits(4) else {
+            if self.flags.unicode {
+                anyhow::bail!(r"Invalid escape '\u'");
+            }
+            return Ok('u');
+        };
+
+        if (0xd800..0xdc00).contains(&unit)
+            && self.peek() == Some('\\')
+            && self.peek_at(1) == Some('u')
+        {
+            let start = self.pos;
+            self.pos += 2;
+            match self.hex_digits(4) {
+                Some(low) if (0xdc00..0xe000).contains(&low) => {
+                    let value = 0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00);
+                    return char::from_u32(value).context("Invalid surrogate pair");
+                }
+                _ => self.pos = start,
+            }
+        }
+
+        // A lone surrogate can never match valid UTF-8 text.
+        char::from_u32(unit).context("Lone surrogates are not supported")
+    }
+
+    /// Parse a `\p{...}` property escape; the `p` or `P` has been consumed.
+    fn property(&mut self, kind: char) -> Result<String> {
+        if !self.eat('{') {
+            anyhow::bail!(r"Invalid escape '\{kind}'");
+        }
+        let close = self.chars[self.pos..]
+            .iter()
+            .position(|&c| c == '}')
+            .context("Unterminated property escape")?;
+        let name: String = self.chars[self.pos..self.pos + close].iter().collect();
+        self.pos += close + 1;
+        Ok(format!(r"\{kind}{{{name}}}"))
+    }
+
+    /// Parse `<name>` after `\k` or `(?`.
+    fn group_name(&mut self) -> Result<String> {
+        if !self.eat('<') {
+            anyhow::bail!("Expected '<' to start a group name");
+        }
+        let close = self.chars[self.pos..]
+            .iter()
+            .position(|&c| c == '>')
+            .context("Unterminated group name")?;
+        let name: String = self.chars[self.pos..self.pos + close].iter().collect();
+        self.pos += close + 1;
+        Ok(name)
+    }
+
+    /// Translate a group opening; the `(` has been consumed.
+    fn group(&mut self) -> Result<()> {
+        self.scopes.push(self.scope);
+
+        if !self.eat('?') {
+            self.out.push('(');
+            return Ok(());
+        }
+
+        match (self.peek(), self.peek_at(1)) {
+            (Some(':'), _) => {
+                self.pos += 1;
+                self.out.push_str("(?:");
+            }
+            (Some(c @ ('=' | '!')), _) => {
+                self.pos += 1;
+                self.needs_backtracking = true;
+                self.out.push_str(&format!("(?{c}"));
+            }
+            (Some('<'), Some(c @ ('=' | '!'))) => {
+                self.pos += 2;
+                self.needs_backtracking = true;
+                self.out.push_str(&format!("(?<{c}"));
+            }
+            (Some('<'), _) => {
+                // Names only matter for `\k` backreferences, which are
+                // translated to numbered ones, so the group is unnamed.
+                self.group_name()?;
+                self.out.push('(');
+            }
+            _ => self.modifiers()?,
+        }
+        Ok(())
+    }
+
+    /// Translate a modifier group such as `(?i:...)` or `(?-s:...)`.
+    fn modifiers(&mut self) -> Result<()> {
+        let mut enable = true;
+        let mut text = String::new();
+        loop {
+            let c = self.next().context("Unterminated group")?;
+            match c {
+                ':' => break,
+                '-' if enable => enable = false,
+                'i' => self.scope.ignore_case = enable,
+                's' => self.scope.dot_all = enable,
+                'm' => {}
+                c => anyhow::bail!("Invalid group '(?{text}{c}'"),
+            }
+            text.push(c);
+        }
+        self.out.push_str(&format!("(?{text}:"));
+        Ok(())
+    }
+
+    /// The class item for `\d`, `\w`, `\s`, or their negations.
+    fn class_escape_item(&self, c: char) -> ClassItem {
+        let ranges = match c.to_ascii_lowercase() {
+            'd' => DIGIT,
+            'w' => WORD,
+            _ => SPACE,
+        };
+        if c.is_ascii_uppercase() {
+            ClassItem::Ranges(complement(ranges))
+        } else {
+            ClassItem::Ranges(ranges.to_vec())
+        }
+    }
+
+    /// Parse one class atom; returns `None` at the closing `]`.
+    fn class_atom(&mut self) -> Result<Option<ClassAtom>> {
+        let c = self.next().context("Unterminated character class")?;
+        match c {
+            ']' => Ok(None),
+            '\\' => {
+                let c = self.next().context("Unterminated character class")?;
+                Ok(Some(match c {
+                    'd' | 'D' | 'w' | 'W' | 's' | 'S' => ClassAtom::Item(self.class_escape_item(c)),
+                    'p' | 'P' if self.flags.unicode => {
+                        ClassAtom::Item(ClassItem::Raw(self.property(c)?))
+                    }
+                    '-' => ClassAtom::Char('-'),
+                    c => ClassAtom::Char(self.character_escape(c, true)?),
+                }))
+            }
+            c => Ok(Some(ClassAtom::Char(c))),
+        }
+    }
+
+    /// Translate a character class; the `[` has been consumed.
+    fn class(&mut self) -> Result<()> {
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+
+        while let Some(atom) = self.class_atom()? {
+            let ClassAtom::Char(start) = atom else {
+                if let ClassAtom::Item(item) = atom {
+                    items.push(item);
+                }
+                continue;
+            };
+
+            // A '-' between two characters is a range; elsewhere it is literal.
+            if self.peek() == Some('-') && self.peek_at(1).is_some_and(|c| c != ']') {
+                self.pos += 1;
+                match self.class_atom()? {
+                    Some(ClassAtom::Char(end)) => {
+                        if start > end {
+                            anyhow::bail!("Range out of order in character class");
+                        }
+                        items.push(ClassItem::Ranges(vec![(start, end)]));
+                    }
+                    Some(ClassAtom::Item(item)) => {
+                        if self.flags.unicode {
+                            anyhow::bail!("Invalid character class range");
+                        }
+                        items.push(ClassItem::Ranges(vec![(start, start), ('-', '-')]));
+                        items.push(item);
+                    }
+                    None => unreachable!("checked that the range has an end"),
+                }
+                continue;
+            }
+
+            items.push(ClassItem::Ranges(vec![(start, start)]));
+        }
+
+        self.emit_class(negated, items);
+        Ok(())
+    }
+
+    fn emit_class(&mut self, negated: bool, items: Vec<ClassItem>) {
+        let mut ranges = Vec::new();
+        let mut raw = String::new();
+        for item in items {
+            match item {
+                ClassItem::Ranges(r) => ranges.extend(r),
+                ClassItem::Raw(r) => raw.push_str(&r),
+            }
+        }
+
+        // Mirror JS case folding for ASCII-only classes without the `u`
+        // flag; see `literal`.
+        let ascii_fold = self.scope.ignore_case
+            && !self.flags.unicode
+            && raw.is_empty()
+            && ranges.iter().all(|&(_, end)| end.is_ascii());
+        if ascii_fold {
+            ranges = ascii_case_closure(&ranges);
+        }
+
+        let ranges = normalize(ranges);
+        if ranges.is_empty() && raw.is_empty() {
+            self.out.push_str(if negated { ANYTHING } else { NOTHING });
+            return;
+        }
+
+        let mut class = String::from("[");
+        if negated {
+            class.push('^');
+        }
+        for (start, end) in ranges {
+            push_class_char(&mut class, start);
+            if end != start {
+                class.push('-');
+                push_class_char(&mut class, end);
+            }
+        }
+        class.push_str(&raw);
+        class.push(']');
+
+        if ascii_fold {
+            self.out.push_str(&format!("(?-i:{class})"));
+        } else {
+            self.out.push_str(&class);
+        }
+    }
+}
+
+/// Record the names of the capturing groups in a pattern, in order.
+fn scan_groups(chars: &[char]) -> Vec<Option<String>> {
+    let mut groups = Vec::new();
+    let mut in_class = false;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '(' if !in_class => {
+                if chars.get(i + 1) != Some(&'?') {
+                    groups.push(None);
+                } else if chars.get(i + 2) == Some(&'<')
+                    && !matches!(chars.get(i + 3), Some('=' | '!'))
+                {
+                    let name: String = chars[i + 3..].iter().take_while(|&&c| c != '>').collect();
+                    groups.push(Some(name));
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    groups
+}
+
+/// JS SyntaxCharacter, plus the characters that are always escapable.
+fn is_syntax_character(c: char) -> bool {
+    matches!(
+        c,
+        '^' | '$' | '\\' | '.' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|'
+    )
+}
+
+/// Push a literal character outside a class, escaped for Rust.
+fn push_literal(out: &mut String, c: char) {
+    if matches!(
+        c,
+        '\\' | '.'
+            | '+'
+            | '*'
+            | '?'
+            | '('
+            | ')'
+            | '|'
+            | '['
+            | ']'
+            | '{'
+            | '}'
+            | '^'
+            | '$'
+            | '#'
+            | '&'
+            | '-'
+            | '~'
+    ) {
+        out.push('\\');
+        out.push(c);
+    } else if c.is_control() || c.is_whitespace() {
+        out.push_str(&format!(r"\x{{{:X}}}", c as u32));
+    } else {
+        out.push(c);
+    }
+}
+
+/// Push a character inside a class, escaped for Rust.
+fn push_class_char(out: &mut String, c: char) {
+    if matches!(c, '\\' | ']' | '[' | '^' | '-' | '&' | '~') {
+        out.push('\\');
+        out.push(c);
+    } else if c.is_control() || c.is_whitespace() {
+        out.push_str(&format!(r"\x{{{:X}}}", c as u32));
+    } else {
+        out.push(c);
+    }
+}
+
+/// Sort and merge overlapping or adjacent ranges.
+fn normalize(mut ranges: Vec<(char, char)>) -> Vec<(char, char)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(char, char)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        if let Some(last) = merged.last_mut()
+            && (start as u32) <= (last.1 as u32).saturating_add(1)
+        {
+            last.1 = last.1.max(end);
+            continue;
+        }
+        merged.push((start, end));
+    }
+    merged
+}
+
+/// The ranges of every character not in `ranges`.
+fn complement(ranges: &[(char, char)]) -> Vec<(char, char)> {
+    let mut result = Vec::new();
+    let mut next = 0u32;
+    for &(start, end) in &normalize(ranges.to_vec()) {
+        if (start as u32) > next {
+            push_range(&mut result, next, start as u32 - 1);
+        }
+        next = end as u32 + 1;
+    }
+    if next <= 0x10ffff {
+        push_range(&mut result, next, 0x10ffff);
+    }
+    result
+}
+
+/// Push a range of code points, skipping the surrogate block.
+fn push_range(ranges: &mut Vec<(char, char)>, start: u32, end: u32) {
+    let pieces = [(start, end.min(0xd7ff)), (start.max(0xe000), end)];
+    for (start, end) in pieces {
+        if start <= end
+            && let (Some(start), Some(end)) = (char::from_u32(start), char::from_u32(end))
+        {
+            ranges.push((start, end));
+        }
+    }
+}
+
+/// Add the other ASCII case of every letter in ASCII-only `ranges`.
+fn ascii_case_closure(ranges: &[(char, char)]) -> Vec<(char, char)> {
+    let mut closed = ranges.to_vec();
+    for &(start, end) in ranges {
+        for c in start..=end {
+            if c.is_ascii_lowercase() {
+                closed.push((c.to_ascii_uppercase(), c.to_ascii_uppercase()));
+            } else if c.is_ascii_uppercase() {
+                closed.push((c.to_ascii_lowercase(), c.to_ascii_lowercase()));
+            }
+        }
+    }
+    closed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsRegex;
+    use serde::Deserialize;
+
+    /// A pattern and the inputs it is checked against.
+    #[derive(Deserialize)]
+    struct ConformanceCase {
+        pattern: String,
+        flags: String,
+        /// Pairs of input and whether JS `matches()` matches it.
+        cases: Vec<(String, bool)>,
+    }
+
+    /// The same fixture is checked against the TypeScript `matches()`
+    /// function by `test/regex-conformance.test.ts`.
+    const CONFORMANCE: &str = include_str!("../../../test/data/regex-conformance.json");
+
+    #[test]
+    fn matches_javascript_semantics() {
+        let suite: Vec<ConformanceCase> =
+            serde_json::from_str(CONFORMANCE).expect("conformance fixture is valid JSON");
+
+        let mut failures = Vec::new();
+        for case in &suite {
+            let re = match JsRegex::new(&case.pattern, &case.flags) {
+                Ok(re) => re,
+                Err(err) => {
+                    failures.push(format!("/{}/{}: {err:#}", case.pattern, case.flags));
+                    continue;
+                }
+            };
+            for (input, expected) in &case.cases {
+                if re.is_match(input) != *expected {
+                    failures.push(format!(
+                        "/{}/{} on {input:?}: expected {expected}",
+                        case.pattern, case.flags
+                    ));
+                }
+            }
+        }
+
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use std::io::Write;
+
+use crate::report::{CheckReport, FileResult, Outcome};
+use crate::types::Severity;
+
+/// Write a check report as JUnit XML.
+///
+/// Each policy becomes a `<testsuite>` and each file it ran on becomes a
+/// `<testcase>`. Suite timings come from the handler and resolver times in
+/// the run's performance stats; individual files are not timed. Only
+/// errors are reported as failures; warnings and info results pass with
+/// their message in `<system-out>`.
+pub fn write_junit(report: &CheckReport, out: &mut impl Write) -> Result<()> {
+    let mut xml = String::new();
+
+    let total_tests: usize = report.policies.iter().map(|p| p.results.len()).sum();
+    let total_failures = report
+        .policies
+        .iter()
+        .flat_map(|p| &p.results)
+        .filter(|r| is_junit_failure(r))
+        .count();
+    let total_time: f64 = report
+        .stats
+        .handler_times
+        .values()
+        .chain(report.stats.resolver_times.values())
+        .map(|d| d.as_secs_f64())
+        .sum();
+
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"repopo\" tests=\"{total_tests}\" failures=\"{total_failures}\" errors=\"0\" time=\"{total_time:.3}\">\n"
+    ));
+
+    for policy in &report.policies {
+        let failures = policy
+            .results
+            .iter()
+            .filter(|r| is_junit_failure(r))
+            .count();
+        let time: f64 = [
+            report.stats.handler_times.get(&policy.name),
+            report.stats.resolver_times.get(&policy.name),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|d| d.as_secs_f64())
+        .sum();
+
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" errors=\"0\" skipped=\"0\" time=\"{time:.3}\">\n",
+            escape(&policy.name),
+            policy.results.len(),
+        ));
+
+        if !policy.description.is_empty() {
+            xml.push_str(&format!(
+                "    <properties>\n      <property name=\"description\" value=\"{}\"/>\n    </properties>\n",
+                escape(&policy.description)
+            ));
+        }
+
+        for r in &policy.results {
+            let open = format!(
+                "    <testcase name=\"{}\" classname=\"{}\"",
+                escape(&r.file),
+                escape(&policy.name)
+            );
+
+            match r.outcome {
+                Outcome::Pass | Outcome::Baselined | Outcome::Suppressed => {
+                    xml.push_str(&open);
+                    xml.push_str("/>\n");
+                }
+                Outcome::Fixed => {
+                    xml.push_str(&open);
+                    xml.push_str(">\n");
+                    xml.push_str(&format!(
+                        "      <system-out>Resolved {} policy failure</system-out>\n",
+                        escape(&policy.name)
+                    ));
+                    xml.push_str("    </testcase>\n");
+                }
+                Outcome::Fail | Outcome::FixFailed if r.severity != Severity::Error => {
+                    let message = r
+                        .result
+                        .error_message()
+                        .unwrap_or_else(|| "Policy failure".to_string());
+                    xml.push_str(&open);
+                    xml.push_str(">\n");
+                    xml.push_str(&format!(
+                        "      <system-out>{}: {}</system-out>\n",
+                        r.severity,
+                        escape(&message)
+                    ));
+                    xml.push_str("    </testcase>\n");
+                }
+                Outcome::Fail | Outcome::FixFailed => {
+                    let (kind, fallback) = if r.outcome == Outcome::FixFailed {
+                        ("FixFailed", "Error fixing policy failure")
+                    } else {
+                        ("PolicyFailure", "Policy failure")
+                    };
+                    let message = r
+                        .result
+                        .error_message()
+                        .unwrap_or_else(|| fallback.to_string());
+
+                    let mut body = message.clone();
+                    if let Some(manual_fix) = r.result.manual_fix() {
+                        body.push_str("\n\nManual fix: ");
+                        body.push_str(manual_fix);
+                    }
+
+                    xml.push_str(&open);
+                    xml.push_str(">\n");
+                    xml.push_str(&format!(
+                        "      <failure message=\"{}\" type=\"{kind}\">{}</failure>\n",
+                        escape(&message),
+                        escape(&body)
+                    ));
+                    xml.push_str("    </testcase>\n");
+                }
+            }
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+
+    out.write_all(xml.as_bytes())
+        .context("Failed to write JUnit report")?;
+    Ok(())
+}
+
+/// Returns true if a result is reported as a JUnit failure.
+fn is_junit_failure(result: &FileResult) -> bool {
+    result.outcome.is_failure() && result.severity == Severity::Error
+}
+
+/// Escape text for use in XML attributes and character data.
+///
+/// Characters that are not allowed anywhere in XML 1.0 are dropped.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,767 @@
+use anyhow::{Context, Result};
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics, ShowMessage,
+};
+use lsp_types::request::{
+    ApplyWorkspaceEdit, CodeActionRequest, ExecuteCommand, HoverRequest, Request as _,
+};
+use lsp_types::{
+    ApplyWorkspaceEditParams, CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, Command, Diagnostic, DiagnosticSeverity, ExecuteCommandOptions,
+    ExecuteCommandParams, Hover, HoverContents, HoverParams, HoverProviderCapability,
+    InitializeParams, MarkupContent, MarkupKind, MessageType, NumberOrString, Position,
+    PublishDiagnosticsParams, Range, ServerCapabilities, ShowMessageParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, TextEdit, Url, WorkspaceEdit,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::engine::PolicySet;
+use crate::files;
+use crate::ipc::Sidecar;
+use crate::suppress::Suppressions;
+use crate::types::{HandlerResult, Severity};
+
+/// Command that runs a policy's resolver on a document.
+const FIX_COMMAND: &str = "repopo.fix";
+
+/// Source name shown next to diagnostics.
+const SOURCE: &str = "repopo";
+
+/// How long a document must go without changes before it is checked.
+/// Editors send a change for every keystroke.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Data attached to each diagnostic so code actions can find its policy.
+#[derive(Debug, Serialize, Deserialize)]
+struct DiagnosticData {
+    policy: String,
+}
+
+/// A policy failure on an open document.
+struct Failure {
+    policy: String,
+    description: String,
+    has_resolver: bool,
+    /// Whether the policy reads only the document, so it can check and fix
+    /// unsaved text.
+    cacheable: bool,
+    severity: Severity,
+    result: HandlerResult,
+    /// The range the diagnostic covers.
+    range: Range,
+}
+
+impl Failure {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let mut message = self
+            .result
+            .error_message()
+            .unwrap_or_else(|| format!("'{}' policy failure", self.policy));
+        if let Some(manual_fix) = self.result.manual_fix() {
+            message.push_str("\nManual fix: ");
+            message.push_str(manual_fix);
+        }
+
+        Diagnostic {
+            range: self.range,
+            severity: Some(match self.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+                Severity::Info => DiagnosticSeverity::INFORMATION,
+            }),
+            code: Some(NumberOrString::String(self.policy.clone())),
+            source: Some(SOURCE.to_string()),
+            message,
+            data: serde_json::to_value(DiagnosticData {
+                policy: self.policy.clone(),
+            })
+            .ok(),
+            ..Diagnostic::default()
+        }
+    }
+}
+
+/// An open document.
+struct Document {
+    /// The text in the editor, which may not be saved.
+    text: String,
+
+    /// Failures from the last check.
+    failures: Vec<Failure>,
+
+    /// When the document is due to be checked after unsaved changes.
+    pending: Option<Instant>,
+}
+
+/// Server state shared across requests.
+struct Server<'a, F> {
+    connection: &'a Connection,
+    git_root: String,
+    config_path: Option<&'a str>,
+    spawn: F,
+    sidecar: Sidecar,
+    policies: PolicySet,
+
+    /// A directory that mirrors the repo with the text of open documents,
+    /// so policies can run on unsaved text.
+    scratch: PathBuf,
+
+    /// Id of the next request sent to the client.
+    next_request_id: i32,
+
+    /// Open documents.
+    open: HashMap<Url, Document>,
+
+    /// Whether each document is ignored by git, kept until a `.gitignore`
+    /// is saved.
+    ignored: HashMap<Url, bool>,
+}
+
+impl<'a, F> Server<'a, F>
+where
+    F: Fn(&str) -> Result<Sidecar>,
+{
+    fn start(
+        connection: &'a Connection,
+        git_root: String,
+        config_path: Option<&'a str>,
+        spawn: F,
+    ) -> Result<Self> {
+        let mut sidecar = spawn(&git_root)?;
+        let config = sidecar.load_config(config_path, &git_root)?;
+        let policies = PolicySet::new(config)?;
+
+        Ok(Self {
+            connection,
+            git_root,
+            config_path,
+            spawn,
+            sidecar,
+            policies,
+            scratch: create_scratch()?,
+            next_request_id: 0,
+            open: HashMap::new(),
+            ignored: HashMap::new(),
+        })
+    }
+
+    /// Replace the sidecar and reload policies. The JS runtime caches
+    /// imported config modules, so a fresh process is needed to pick up
+    /// config edits.
+    fn reload(&mut self) -> Result<()> {
+        let mut sidecar = (self.spawn)(&self.git_root)?;
+        let config = sidecar.load_config(self.config_path, &self.git_root)?;
+        self.policies = PolicySet::new(config)?;
+
+        let mut old = std::mem::replace(&mut self.sidecar, sidecar);
+        old.shutdown()?;
+        Ok(())
+    }
+
+    /// Convert a document URI to a repo-relative path, if it is a file in
+    /// the repository.
+    fn repo_path(&self, uri: &Url) -> Option<String> {
+        let path = uri.to_file_path().ok()?;
+        let rel = path.strip_prefix(&self.git_root).ok()?;
+        Some(rel.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// Whether a document is ignored by git. The answer is cached until a
+    /// `.gitignore` is saved.
+    fn is_ignored(&mut self, uri: &Url, file: &str) -> Result<bool> {
+        if let Some(&ignored) = self.ignored.get(uri) {
+            return Ok(ignored);
+        }
+        let ignored = files::git_is_ignored(&self.git_root, file)?;
+        self.ignored.insert(uri.clone(), ignored);
+        Ok(ignored)
+    }
+
+    fn is_config_file(&self, uri: &Url) -> bool {
+        let config_files = self.policies.config.config_files();
+        uri.to_file_path()
+            .is_ok_and(|path| config_files.iter().any(|f| path == Path::new(f)))
+    }
+
+    /// Write the text of a document to its copy in the scratch directory,
+    /// which is the root policies run on to see unsaved text.
+    fn write_scratch(&self, file: &str, text: &str) -> Result<String> {
+        let path = self.scratch.join(file);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(self.scratch.to_string_lossy().into_owned())
+    }
+
+    /// Run the matching policies on the text of a document.
+    ///
+    /// Policies that read only the document run on its text. Policies that
+    /// read other files run on the saved file, and only when `saved` is
+    /// set; until then their last failures are kept.
+    fn check(&mut self, uri: &Url, saved: bool) -> Result<Vec<Failure>> {
+        let Some(file) = self.repo_path(uri) else {
+            return Ok(Vec::new());
+        };
+        if !self.open.contains_key(uri) || self.is_ignored(uri, &file)? {
+            return Ok(Vec::new());
+        }
+        let Some(document) = self.open.get_mut(uri) else {
+            return Ok(Vec::new());
+        };
+
+        let text = document.text.clone();
+        let mut failures = std::mem::take(&mut document.failures);
+        failures.retain(|f| !saved && !f.cacheable);
+
+        let scratch_root = self.write_scratch(&file, &text)?;
+        let suppressions = Suppressions::scan_text(&self.git_root, &file, &text)?;
+        for (policy_id, policy) in self.policies.policies_for(&file) {
+            if suppressions.get(&file, &policy.name).is_some() {
+                continue;
+            }
+            let root = if policy.cacheable {
+                &scratch_root
+            } else if saved {
+                &self.git_root
+            } else {
+                continue;
+            };
+            let result = self
+                .policies
+                .run_one(&mut self.sidecar, policy_id, &file, root, false)?;
+            if !result.is_pass() {
+                failures.push(Failure {
+                    policy: policy.name.clone(),
+                    description: policy.description.clone(),
+                    has_resolver: policy.has_resolver,
+                    cacheable: policy.cacheable,
+                    severity: result.severity(policy.severity),
+                    range: line_range(&text, result.line()),
+                    result,
+                });
+            }
+        }
+
+        Ok(failures)
+    }
+
+    /// Check a document and publish its diagnostics. `saved` is set when
+    /// the document's text matches the file on disk.
+    fn refresh(&mut self, uri: Url, saved: bool) -> Result<()> {
+        if let Some(document) = self.open.get_mut(&uri) {
+            document.pending = None;
+        }
+        let failures = self.check(&uri, saved).unwrap_or_else(|err| {
+            eprintln!("Failed to check {uri}: {err:#}");
+            Vec::new()
+        });
+        let diagnostics = failures.iter().map(Failure::to_diagnostic).collect();
+        if let Some(document) = self.open.get_mut(&uri) {
+            document.failures = failures;
+        }
+        self.publish(uri, diagnostics)
+    }
+
+    /// When the next document with unsaved changes is due to be checked.
+    fn next_due(&self) -> Option<Instant> {
+        self.open.values().filter_map(|d| d.pending).min()
+    }
+
+    /// Check the documents whose changes have settled.
+    fn refresh_due(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let due: Vec<Url> = self
+            .open
+            .iter()
+            .filter(|(_, d)| d.pending.is_some_and(|at| at <= now))
+            .map(|(uri, _)| uri.clone())
+            .collect();
+        for uri in due {
+            self.refresh(uri, false)?;
+        }
+        Ok(())
+    }
+
+    fn publish(&self, uri: Url, diagnostics: Vec<Diagnostic>) -> Result<()> {
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        };
+        self.notify(PublishDiagnostics::METHOD, params)
+    }
+
+    fn notify(&self, method: &str, params: impl Serialize) -> Result<()> {
+        self.connection
+            .sender
+            .send(Message::Notification(Notification::new(
+                method.to_string(),
+                params,
+            )))
+            .context("Failed to send notification")
+    }
+
+    fn request(&mut self, method: &str, params: impl Serialize) -> Result<()> {
+        let id = RequestId::from(self.next_request_id);
+        self.next_request_id += 1;
+        self.connection
+            .sender
+            .send(Message::Request(Request::new(
+                id,
+                method.to_string(),
+                params,
+            )))
+            .context("Failed to send request")
+    }
+
+    fn respond(&self, id: RequestId, result: impl Serialize) -> Result<()> {
+        self.connection
+            .sender
+            .send(Message::Response(Response::new_ok(id, result)))
+            .context("Failed to send response")
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let document = Document {
+                    text: params.text_document.text,
+                    failures: Vec::new(),
+                    pending: None,
+                };
+                self.open.insert(params.text_document.uri.clone(), document);
+                self.refresh(params.text_document.uri, true)?;
+            }
+
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                // Documents are synced in full, so the last change holds
+                // the whole text. The check waits until the changes settle.
+                let uri = params.text_document.uri;
+                if let (Some(document), Some(change)) = (
+                    self.open.get_mut(&uri),
+                    params.content_changes.into_iter().last(),
+                ) {
+                    document.text = change.text;
+                    document.pending = Some(Instant::now() + DEBOUNCE);
+                }
+            }
+
+            DidSaveTextDocument::METHOD => {
+                let params: lsp_types::DidSaveTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                if is_gitignore(&params.text_document.uri) {
+                    self.ignored.clear();
+                }
+                if self.is_config_file(&params.text_document.uri) {
+                    self.reload()?;
+                    let uris: Vec<Url> = self.open.keys().cloned().collect();
+                    for uri in uris {
+                        self.refresh(uri, true)?;
+                    }
+                } else {
+                    self.refresh(params.text_document.uri, true)?;
+                }
+            }
+
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                self.open.remove(&params.text_document.uri);
+                self.ignored.remove(&params.text_document.uri);
+                if let Some(file) = self.repo_path(&params.text_document.uri) {
+                    let _ = fs::remove_file(self.scratch.join(file));
+                }
+                self.publish(params.text_document.uri, Vec::new())?;
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn handle_request(&mut self, request: Request) -> Result<()> {
+        match request.method.as_str() {
+            HoverRequest::METHOD => {
+                let params: HoverParams = serde_json::from_value(request.params)?;
+                let hover = self.hover(&params);
+                self.respond(request.id, hover)
+            }
+
+            CodeActionRequest::METHOD => {
+                let params: CodeActionParams = serde_json::from_value(request.params)?;
+                let actions = self.code_actions(&params);
+                self.respond(request.id, actions)
+            }
+
+            ExecuteCommand::METHOD => {
+                let params: ExecuteCommandParams = serde_json::from_value(request.params)?;
+                self.execute_command(params)?;
+                self.respond(request.id, serde_json::Value::Null)
+            }
+
+            _ => self
+                .connection
+                .sender
+                .send(Message::Response(Response::new_err(
+                    request.id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    format!("Unhandled method: {}", request.method),
+                )))
+                .context("Failed to send response"),
+        }
+    }
+
+    /// Describe the failing policies when hovering over their diagnostics.
+    fn hover(&self, params: &HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params.position;
+        let failures: Vec<&Failure> = self
+            .open
+            .get(&params.text_document_position_params.text_document.uri)?
+            .failures
+            .iter()
+            .filter(|f| f.range.start.line == position.line)
+            .collect();
+        let range = failures.first()?.range;
+
+        let value = failures
+            .iter()
+            .map(|f| format!("**{}**: {}", f.policy, f.description))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(range),
+        })
+    }
+
+    /// Offer a quick fix for each diagnostic whose policy has a resolver
+    /// and reads only the document. Fixes of policies that read other files
+    /// may change those files, so they are left to `check --fix`.
+    fn code_actions(&self, params: &CodeActionParams) -> Vec<CodeActionOrCommand> {
+        let uri = &params.text_document.uri;
+        let Some(document) = self.open.get(uri) else {
+            return Vec::new();
+        };
+        let failures = &document.failures;
+
+        params
+            .context
+            .diagnostics
+            .iter()
+            .filter(|d| d.source.as_deref() == Some(SOURCE))
+            .filter_map(|d| {
+                let data: DiagnosticData = serde_json::from_value(d.data.clone()?).ok()?;
+                failures
+                    .iter()
+                    .find(|f| f.policy == data.policy && f.has_resolver && f.cacheable)?;
+
+                let title = format!("Fix with {}", data.policy);
+                Some(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: title.clone(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![d.clone()]),
+                    command: Some(Command {
+                        title,
+                        command: FIX_COMMAND.to_string(),
+                        arguments: Some(vec![
+                            serde_json::to_value(uri).ok()?,
+                            serde_json::Value::String(data.policy),
+                        ]),
+                    }),
+                    ..CodeAction::default()
+                }))
+            })
+            .collect()
+    }
+
+    /// Run a policy's resolver on the text of a document, and ask the
+    /// editor to apply the fix with `workspace/applyEdit`. The resolver
+    /// runs on the document's scratch copy, so the file on disk and any
+    /// unsaved text are left to the editor. Applying the edit changes the
+    /// document, which checks it again.
+    fn execute_command(&mut self, params: ExecuteCommandParams) -> Result<()> {
+        if params.command != FIX_COMMAND {
+            anyhow::bail!("Unknown command: {}", params.command);
+        }
+
+        let mut args = params.arguments.into_iter();
+        let (Some(uri), Some(policy)) = (args.next(), args.next()) else {
+            anyhow::bail!("{FIX_COMMAND} expects a document URI and a policy name");
+        };
+        let uri: Url = serde_json::from_value(uri)?;
+        let policy: String = serde_json::from_value(policy)?;
+        let file = self
+            .repo_path(&uri)
+            .with_context(|| format!("{uri} is not in the repository"))?;
+
+        let text = self
+            .open
+            .get(&uri)
+            .with_context(|| format!("{uri} is not open"))?
+            .text
+            .clone();
+
+        let (policy_id, meta) = self
+            .policies
+            .policies_for(&file)
+            .into_iter()
+            .find(|(_, meta)| meta.name == policy)
+            .with_context(|| format!("Policy '{policy}' does not apply to {file}"))?;
+        if !meta.cacheable {
+            anyhow::bail!("Policy '{policy}' reads other files; fix it with `check --fix`");
+        }
+
+        let scratch_root = self.write_scratch(&file, &text)?;
+        let result =
+            self.policies
+                .run_one(&mut self.sidecar, policy_id, &file, &scratch_root, true)?;
+        if !result.is_pass() && !result.is_fixed() {
+            let message = result
+                .error_message()
+                .unwrap_or_else(|| format!("Error fixing {policy} policy failure"));
+            return self.notify(
+                ShowMessage::METHOD,
+                ShowMessageParams {
+                    typ: MessageType::WARNING,
+                    message,
+                },
+            );
+        }
+
+        let path = self.scratch.join(&file);
+        let fixed = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if fixed == text {
+            return Ok(());
+        }
+        let edit = WorkspaceEdit {
+            changes: Some(HashMap::from([(uri, vec![text_edit(&text, &fixed)])])),
+            ..WorkspaceEdit::default()
+        };
+        self.request(
+            ApplyWorkspaceEdit::METHOD,
+            ApplyWorkspaceEditParams {
+                label: Some(format!("Fix with {policy}")),
+                edit,
+            },
+        )
+    }
+}
+
+/// Create a scratch directory that only the current user can read, with a
+/// name no other process has taken.
+fn create_scratch() -> Result<PathBuf> {
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+
+    for attempt in 0..100u32 {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        let dir = std::env::temp_dir().join(format!(
+            "repopo-lsp-{}-{nanos:08x}{attempt}",
+            std::process::id()
+        ));
+        // Creating the directory fails if anything, including a symlink,
+        // already has its name.
+        match builder.create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to create {}", dir.display()));
+            }
+        }
+    }
+    anyhow::bail!("Failed to create a scratch directory")
+}
+
+/// Whether a document is a `.gitignore`, which changes which files are
+/// ignored.
+fn is_gitignore(uri: &Url) -> bool {
+    uri.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .is_some_and(|name| name == ".gitignore")
+}
+
+/// The range of a diagnostic: the text of the line a policy reported, or
+/// of the first line, since most policies check the file as a whole.
+fn line_range(text: &str, line: Option<usize>) -> Range {
+    let (index, content) = line
+        .and_then(|line| line.checked_sub(1))
+        .and_then(|index| Some((index, text.lines().nth(index)?)))
+        .unwrap_or((0, text.lines().next().unwrap_or_default()));
+    let indent = content.len() - content.trim_start().len();
+    let character = |s: &str| s.encode_utf16().count() as u32;
+    Range {
+        start: Position {
+            line: index as u32,
+            character: character(&content[..indent]),
+        },
+        end: Position {
+            line: index as u32,
+            character: character(content),
+        },
+    }
+}
+
+/// The position of a byte offset in `text`, in the UTF-16 code units LSP
+/// counts characters in.
+fn position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    Position {
+        line: before.matches('\n').count() as u32,
+        character: before[line_start..].encode_utf16().count() as u32,
+    }
+}
+
+/// The smallest edit that turns `old` into `new`: the span between their
+/// common prefix and suffix.
+fn text_edit(old: &str, new: &str) -> TextEdit {
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(old.len().min(new.len()), |((offset, _), _)| offset);
+    let suffix: usize = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum();
+
+    TextEdit {
+        range: Range {
+            start: position(old, prefix),
+            end: position(old, old.len() - suffix),
+        },
+        new_text: new[prefix..new.len() - suffix].to_string(),
+    }
+}
+
+/// Run the language server over stdio until the client shuts it down.
+///
+/// Diagnostics are published when a document is opened, changed, or saved.
+/// Changes are checked once the document has gone [`DEBOUNCE`] without
+/// another.
+/// Policies that read only the document run on the editor's text, through
+/// a copy in a scratch directory; policies that read other files run on the
+/// saved file when it is opened or saved. `spawn` starts a sidecar rooted at
+/// the given git root; the root is taken from the client's workspace.
+pub fn run<F>(config_path: Option<&str>, spawn: F) -> Result<()>
+where
+    F: Fn(&str) -> Result<Sidecar>,
+{
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                ..TextDocumentSyncOptions::default()
+            },
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: vec![FIX_COMMAND.to_string()],
+            ..ExecuteCommandOptions::default()
+        }),
+        ..ServerCapabilities::default()
+    };
+
+    let params = connection
+        .initialize(serde_json::to_value(capabilities)?)
+        .context("LSP initialization failed")?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+
+    #[allow(deprecated)]
+    let root = params
+        .workspace_folders
+        .as_ref()
+        .and_then(|folders| folders.first())
+        .map(|folder| folder.uri.clone())
+        .or(params.root_uri)
+        .and_then(|uri| uri.to_file_path().ok())
+        .map(Ok)
+        .unwrap_or_else(std::env::current_dir)
+        .context("Failed to determine the workspace root")?;
+    let git_root = files::find_git_root(&root.to_string_lossy())?;
+
+    let mut server = Server::start(&connection, git_root, config_path, spawn)?;
+
+    loop {
+        let message = match server.next_due() {
+            Some(due) => {
+                match connection
+                    .receiver
+                    .recv_timeout(due.saturating_duration_since(Instant::now()))
+                {
+                    Ok(message) => message,
+                    Err(e) if e.is_timeout() => {
+                        if let Err(err) = server.refresh_due() {
+                            eprintln!("{err:#}");
+                        }
+                        continue;
+                    }
+                    Err(_) => break,
+                }
+            }
+            None => match connection.receiver.recv() {
+                Ok(message) => message,
+                Err(_) => break,
+            },
+        };
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let id = request.id.clone();
+                if let Err(err) = server.handle_request(request) {
+                    connection.sender.send(Message::Response(Response::new_err(
+                        id,
+                        lsp_server::ErrorCode::InternalError as i32,
+                        format!("{err:#}"),
+                    )))?;
+                }
+            }
+            Message::Notification(notification) => {
+                if let Err(err) = server.handle_notification(notification) {
+                    eprintln!("{err:#}");
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    server.sidecar.shutdown()?;
+    let _ = fs::remove_dir_all(&server.scratch);
+    drop(server);
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
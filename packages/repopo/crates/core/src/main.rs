@@ -1,12 +1,37 @@
+mod baseline;
+mod cache;
+#[cfg(unix)]
+mod daemon;
 mod engine;
+mod explain;
 mod files;
+mod filter;
+mod gitindex;
+mod hook;
 mod ipc;
+mod jsregex;
+mod junit;
+mod lsp;
+mod native;
+mod pattern;
+mod pool;
+mod report;
+mod routing;
+mod sarif;
+mod suppress;
 mod types;
+mod watch;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 use std::env;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
+use std::thread;
+
+use crate::baseline::Baseline;
+use crate::filter::PolicyFilter;
+use crate::report::{CheckReport, OutputFormat};
 
 #[derive(Parser)]
 #[command(
@@ -28,9 +53,18 @@ enum Commands {
         fix: bool,
 
         /// Read list of files from stdin instead of git.
-        #[arg(long)]
+        #[arg(long, conflicts_with_all = ["changed_since", "staged"])]
         stdin: bool,
 
+        /// Only check files changed since the merge base of this ref and HEAD,
+        /// including uncommitted and untracked files.
+        #[arg(long, value_name = "REF", conflicts_with = "staged")]
+        changed_since: Option<String>,
+
+        /// Only check files staged in the git index.
+        #[arg(long)]
+        staged: bool,
+
         /// Show verbose output including per-policy timing.
         #[arg(short, long)]
         verbose: bool,
@@ -50,6 +84,142 @@ enum Commands {
         /// JS runtime to use for the sidecar process.
         #[arg(long, value_enum, env = "REPOPO_RUNTIME", default_value_t = Runtime::Auto)]
         runtime: Runtime,
+
+        /// Maximum number of sidecar processes to run in parallel.
+        /// Defaults to the number of available CPUs.
+        #[arg(short, long, env = "REPOPO_JOBS")]
+        jobs: Option<usize>,
+
+        /// Output format for results. Structured formats are written to stdout.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Skip files that passed on a previous run and have not changed since.
+        /// Policies that read files other than the one being checked opt out
+        /// with `cacheable: false` and always run. Editing the config or a
+        /// local module it imports discards the cache.
+        #[arg(long)]
+        cache: bool,
+
+        /// Directory for the check cache, relative to the git root.
+        #[arg(long, default_value = cache::DEFAULT_CACHE_DIR, requires = "cache")]
+        cache_location: PathBuf,
+
+        /// Only run this policy. Can be repeated; combines with --tag.
+        #[arg(long = "policy", value_name = "NAME")]
+        policies: Vec<String>,
+
+        /// Only run policies with this tag, such as `cargo` or `package`.
+        /// Can be repeated; combines with --policy.
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Do not run this policy. Can be repeated.
+        #[arg(long = "skip-policy", value_name = "NAME")]
+        skip_policies: Vec<String>,
+
+        /// Fail if policies report more than this many warnings.
+        #[arg(long, value_name = "N")]
+        max_warnings: Option<usize>,
+
+        /// Fail if policies report any warnings. Same as `--max-warnings 0`.
+        #[arg(long, conflicts_with = "max_warnings")]
+        deny_warnings: bool,
+
+        /// Baseline file of known violations to suppress. Defaults to
+        /// `.repopo-baseline.json` in the git root, if it exists.
+        #[arg(long, value_name = "PATH", conflicts_with = "no_baseline")]
+        baseline: Option<PathBuf>,
+
+        /// Report every violation, even those in the baseline.
+        #[arg(long)]
+        no_baseline: bool,
+
+        /// Run in this process even if a daemon is running.
+        #[arg(long, env = "REPOPO_NO_DAEMON")]
+        no_daemon: bool,
+    },
+
+    /// Manage and run the git pre-commit hook.
+    Hook {
+        #[command(subcommand)]
+        command: HookCommand,
+    },
+
+    /// Watch the working tree and re-run affected policies on changes.
+    Watch {
+        /// Show verbose output.
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Path to the config file.
+        #[arg(short, long)]
+        config: Option<String>,
+
+        /// Path to the Node.js sidecar script.
+        #[arg(long, env = "REPOPO_SIDECAR_PATH")]
+        sidecar_path: Option<String>,
+
+        /// JS runtime to use for the sidecar process.
+        #[arg(long, value_enum, env = "REPOPO_RUNTIME", default_value_t = Runtime::Auto)]
+        runtime: Runtime,
+
+        /// Maximum number of sidecar processes to run in parallel.
+        /// Defaults to the number of available CPUs.
+        #[arg(short, long, env = "REPOPO_JOBS")]
+        jobs: Option<usize>,
+    },
+
+    /// Manage the baseline of known violations that `check` suppresses.
+    Baseline {
+        #[command(subcommand)]
+        command: BaselineCommand,
+    },
+
+    /// Keep sidecars and compiled policies warm for `check` and `list`.
+    #[cfg(unix)]
+    Daemon {
+        #[command(subcommand)]
+        command: DaemonCommand,
+    },
+
+    /// Run a language server on stdio that reports policy failures as
+    /// diagnostics.
+    Lsp {
+        /// Path to the config file.
+        #[arg(short, long)]
+        config: Option<String>,
+
+        /// Path to the Node.js sidecar script.
+        #[arg(long, env = "REPOPO_SIDECAR_PATH")]
+        sidecar_path: Option<String>,
+
+        /// JS runtime to use for the sidecar process.
+        #[arg(long, value_enum, env = "REPOPO_RUNTIME", default_value_t = Runtime::Auto)]
+        runtime: Runtime,
+    },
+
+    /// Show which patterns decide whether each policy applies to files.
+    Explain {
+        /// Paths to explain, relative to the current directory.
+        #[arg(required = true)]
+        files: Vec<String>,
+
+        /// Path to the config file.
+        #[arg(short, long)]
+        config: Option<String>,
+
+        /// Path to the Node.js sidecar script.
+        #[arg(long, env = "REPOPO_SIDECAR_PATH")]
+        sidecar_path: Option<String>,
+
+        /// JS runtime to use for the sidecar process.
+        #[arg(long, value_enum, env = "REPOPO_RUNTIME", default_value_t = Runtime::Auto)]
+        runtime: Runtime,
+
+        /// Run in this process even if a daemon is running.
+        #[arg(long, env = "REPOPO_NO_DAEMON")]
+        no_daemon: bool,
     },
 
     /// List all configured policies.
@@ -73,9 +243,137 @@ enum Commands {
         /// JS runtime to use for the sidecar process.
         #[arg(long, value_enum, env = "REPOPO_RUNTIME", default_value_t = Runtime::Auto)]
         runtime: Runtime,
+        /// Run in this process even if a daemon is running.
+        #[arg(long, env = "REPOPO_NO_DAEMON")]
+        no_daemon: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum HookCommand {
+    /// Install a pre-commit hook that runs `hook run`.
+    /// Respects `core.hooksPath`.
+    Install {
+        /// Fix policy violations in the hook and re-stage resolved files.
+        #[arg(short, long, alias = "resolve")]
+        fix: bool,
+
+        /// Replace an existing pre-commit hook not installed by repopo-core.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Remove the pre-commit hook installed by `hook install`.
+    Uninstall,
+
+    /// Check the staged content of staged files.
+    Run {
+        /// Fix policy violations if possible and re-stage resolved files.
+        #[arg(short, long, alias = "resolve")]
+        fix: bool,
+
+        /// Show verbose output including per-policy timing.
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Suppress all output except errors.
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Path to the config file.
+        #[arg(short, long)]
+        config: Option<String>,
+
+        /// Path to the Node.js sidecar script.
+        #[arg(long, env = "REPOPO_SIDECAR_PATH")]
+        sidecar_path: Option<String>,
+
+        /// JS runtime to use for the sidecar process.
+        #[arg(long, value_enum, env = "REPOPO_RUNTIME", default_value_t = Runtime::Auto)]
+        runtime: Runtime,
+
+        /// Maximum number of sidecar processes to run in parallel.
+        /// Defaults to the number of available CPUs.
+        #[arg(short, long, env = "REPOPO_JOBS")]
+        jobs: Option<usize>,
+        /// Run in this process even if a daemon is running.
+        #[arg(long, env = "REPOPO_NO_DAEMON")]
+        no_daemon: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum BaselineCommand {
+    /// Check every file and record the current violations as the baseline,
+    /// replacing any existing one.
+    Create {
+        /// Path to write the baseline to. Defaults to
+        /// `.repopo-baseline.json` in the git root.
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+
+        /// Show verbose output.
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Path to the config file.
+        #[arg(short, long)]
+        config: Option<String>,
+
+        /// Path to the Node.js sidecar script.
+        #[arg(long, env = "REPOPO_SIDECAR_PATH")]
+        sidecar_path: Option<String>,
+
+        /// JS runtime to use for the sidecar process.
+        #[arg(long, value_enum, env = "REPOPO_RUNTIME", default_value_t = Runtime::Auto)]
+        runtime: Runtime,
+
+        /// Maximum number of sidecar processes to run in parallel.
+        /// Defaults to the number of available CPUs.
+        #[arg(short, long, env = "REPOPO_JOBS")]
+        jobs: Option<usize>,
+
+        /// Run in this process even if a daemon is running.
+        #[arg(long, env = "REPOPO_NO_DAEMON")]
+        no_daemon: bool,
     },
 }
 
+#[cfg(unix)]
+#[derive(Subcommand)]
+enum DaemonCommand {
+    /// Run the daemon in the foreground. `check`, `list`, and `hook run`
+    /// use it automatically while it is running.
+    Start {
+        /// Show verbose output.
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Path to the config file.
+        #[arg(short, long)]
+        config: Option<String>,
+
+        /// Path to the Node.js sidecar script.
+        #[arg(long, env = "REPOPO_SIDECAR_PATH")]
+        sidecar_path: Option<String>,
+
+        /// JS runtime to use for the sidecar process.
+        #[arg(long, value_enum, env = "REPOPO_RUNTIME", default_value_t = Runtime::Auto)]
+        runtime: Runtime,
+
+        /// Maximum number of sidecar processes to run in parallel.
+        /// Defaults to the number of available CPUs.
+        #[arg(short, long, env = "REPOPO_JOBS")]
+        jobs: Option<usize>,
+    },
+
+    /// Stop the running daemon.
+    Stop,
+
+    /// Show whether a daemon is running for this repository.
+    Status,
+}
+
 #[derive(Clone, Debug, ValueEnum)]
 enum Runtime {
     /// Auto-detect: use bun if available, otherwise node.
@@ -108,6 +406,15 @@ fn resolve_runtime(runtime: &Runtime) -> String {
     }
 }
 
+/// Resolve how many sidecar processes to run. Defaults to the CPU count.
+fn resolve_jobs(jobs: Option<usize>) -> usize {
+    jobs.filter(|&n| n > 0).unwrap_or_else(|| {
+        thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    })
+}
+
 /// Resolve the sidecar path. Looks for it relative to the binary location
 /// or uses the provided/env-var path.
 fn resolve_sidecar_path(explicit: Option<&str>) -> Result<String> {
@@ -160,6 +467,94 @@ fn resolve_sidecar_path(explicit: Option<&str>) -> Result<String> {
     )
 }
 
+/// Report deletions and renames found when listing changed files.
+fn log_changed_files(changed: &[files::ChangedFile]) {
+    for file in changed {
+        match file.kind {
+            files::ChangeKind::Deleted => eprintln!("Deleted (skipped): {}", file.path),
+            files::ChangeKind::Renamed { ref from } => {
+                eprintln!("Renamed: {from} -> {}", file.path)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// How to start sidecars when no daemon serves the request.
+struct SidecarArgs {
+    sidecar_path: Option<String>,
+    runtime: Runtime,
+    jobs: Option<usize>,
+}
+
+impl SidecarArgs {
+    /// Spawn a sidecar pool with its working directory set to `git_root`
+    /// so relative file paths work.
+    fn spawn_pool(&self, git_root: &str, verbose: bool) -> Result<pool::SidecarPool> {
+        let sidecar_script = resolve_sidecar_path(self.sidecar_path.as_deref())?;
+        let runtime_bin = resolve_runtime(&self.runtime);
+        let jobs = resolve_jobs(self.jobs);
+
+        if verbose {
+            eprintln!("Using sidecar: {sidecar_script} (runtime: {runtime_bin}, jobs: {jobs})");
+        }
+
+        pool::SidecarPool::spawn(&runtime_bin, &sidecar_script, git_root, jobs)
+    }
+}
+
+/// Check files through the repository's daemon if one is running and
+/// accepts the options, otherwise through a freshly spawned sidecar pool.
+fn run_check(
+    files: Vec<String>,
+    git_root: &str,
+    options: &engine::CheckOptions,
+    sidecar: &SidecarArgs,
+    use_daemon: bool,
+) -> Result<CheckReport> {
+    #[cfg(unix)]
+    if use_daemon && let Some(client) = daemon::connect(git_root)? {
+        if let Some(report) = client.check(&files, options)? {
+            if options.verbose {
+                eprintln!("Checked by daemon.");
+            }
+            if options.fix && !options.quiet {
+                eprintln!("Resolving errors if possible.");
+            }
+            return Ok(report);
+        }
+        if options.verbose {
+            eprintln!("Daemon uses a different config; running locally.");
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = use_daemon;
+
+    let mut pool = sidecar.spawn_pool(git_root, options.verbose)?;
+    let report = engine::run_check(&mut pool, files, git_root, options)?;
+    pool.shutdown()?;
+    Ok(report)
+}
+
+/// Suppress the violations recorded in a baseline. Without an explicit
+/// path, the default baseline is applied only if it exists.
+fn apply_baseline(report: &mut CheckReport, git_root: &str, path: Option<&Path>) -> Result<()> {
+    let baseline = match path {
+        Some(path) => Some(
+            Baseline::load(git_root, path)?
+                .with_context(|| format!("Baseline {} does not exist", path.display()))?,
+        ),
+        None => Baseline::load(
+            git_root,
+            &Path::new(git_root).join(baseline::DEFAULT_BASELINE_FILE),
+        )?,
+    };
+    if let Some(baseline) = baseline {
+        baseline.apply(report);
+    }
+    Ok(())
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -167,19 +562,26 @@ fn main() -> Result<()> {
         Commands::Check {
             fix,
             stdin,
+            changed_since,
+            staged,
             verbose,
             quiet,
             config,
             sidecar_path,
             runtime,
+            jobs,
+            format,
+            cache,
+            cache_location,
+            policies,
+            tags,
+            skip_policies,
+            max_warnings,
+            deny_warnings,
+            baseline,
+            no_baseline,
+            no_daemon,
         } => {
-            let sidecar_script = resolve_sidecar_path(sidecar_path.as_deref())?;
-            let runtime_bin = resolve_runtime(&runtime);
-
-            if verbose {
-                eprintln!("Using sidecar: {sidecar_script} (runtime: {runtime_bin})");
-            }
-
             let cwd = env::current_dir()
                 .context("Failed to get current directory")?
                 .to_string_lossy()
@@ -192,8 +594,24 @@ fn main() -> Result<()> {
             }
 
             // Enumerate files
+            let change_base = match changed_since.as_deref() {
+                Some(reference) => Some(files::ChangeBase::Ref(reference)),
+                None if staged => Some(files::ChangeBase::Staged),
+                None => None,
+            };
+
             let file_list = if stdin {
                 files::read_stdin_files()?
+            } else if let Some(base) = change_base {
+                let changed = files::git_changed_files(&git_root, base)?;
+                if verbose {
+                    log_changed_files(&changed);
+                }
+                changed
+                    .into_iter()
+                    .filter(|f| f.exists())
+                    .map(|f| f.path)
+                    .collect()
             } else {
                 files::git_ls_files(&git_root)?
             };
@@ -202,35 +620,108 @@ fn main() -> Result<()> {
                 eprintln!("{} files to check.", file_list.len());
             }
 
-            // Spawn sidecar with cwd set to git root so relative file paths work
-            let mut sidecar = ipc::Sidecar::spawn(&runtime_bin, &sidecar_script, &git_root)?;
-
-            let success = engine::run_check(
-                &mut sidecar,
-                file_list,
-                &git_root,
-                config.as_deref(),
+            // Human-oriented notices are suppressed when stdout carries a report
+            let quiet = quiet || format != OutputFormat::Text;
+            let filter = PolicyFilter {
+                policies,
+                tags,
+                skip: skip_policies,
+            };
+            let options = engine::CheckOptions {
+                config_path: config.as_deref(),
                 fix,
                 verbose,
                 quiet,
-            )?;
+                cache_dir: cache.then_some(cache_location.as_path()),
+                filter: &filter,
+            };
+            let sidecar = SidecarArgs {
+                sidecar_path,
+                runtime,
+                jobs,
+            };
+            let mut report = run_check(file_list, &git_root, &options, &sidecar, !no_daemon)?;
+            if !no_baseline {
+                apply_baseline(&mut report, &git_root, baseline.as_deref())?;
+            }
 
-            sidecar.shutdown()?;
+            let max_warnings = if deny_warnings { Some(0) } else { max_warnings };
+            report.write(format, quiet, max_warnings)?;
+            report.stats.log(verbose);
 
-            if !success {
+            if !report.success(max_warnings) {
                 process::exit(1);
             }
         }
 
-        Commands::List {
+        Commands::Hook { command } => {
+            let cwd = env::current_dir()
+                .context("Failed to get current directory")?
+                .to_string_lossy()
+                .to_string();
+
+            let git_root = files::find_git_root(&cwd)?;
+
+            match command {
+                HookCommand::Install { fix, force } => {
+                    let path = hook::install(&git_root, fix, force)?;
+                    eprintln!("Installed pre-commit hook at {}", path.display());
+                }
+
+                HookCommand::Uninstall => {
+                    let path = hook::uninstall(&git_root)?;
+                    eprintln!("Removed pre-commit hook at {}", path.display());
+                }
+
+                HookCommand::Run {
+                    fix,
+                    verbose,
+                    quiet,
+                    config,
+                    sidecar_path,
+                    runtime,
+                    jobs,
+                    no_daemon,
+                } => {
+                    let sidecar = SidecarArgs {
+                        sidecar_path,
+                        runtime,
+                        jobs,
+                    };
+
+                    let options = engine::CheckOptions {
+                        config_path: config.as_deref(),
+                        fix,
+                        verbose,
+                        quiet,
+                        cache_dir: None,
+                        filter: &PolicyFilter::default(),
+                    };
+                    let mut report = hook::run(&git_root, &options, |files| {
+                        run_check(files, &git_root, &options, &sidecar, !no_daemon)
+                    })?;
+                    apply_baseline(&mut report, &git_root, None)?;
+
+                    report.write(OutputFormat::Text, quiet, None)?;
+                    report.stats.log(verbose);
+
+                    if !report.success(None) {
+                        process::exit(1);
+                    }
+                }
+            }
+        }
+
+        Commands::Watch {
             verbose,
-            quiet: _,
             config,
             sidecar_path,
             runtime,
+            jobs,
         } => {
             let sidecar_script = resolve_sidecar_path(sidecar_path.as_deref())?;
             let runtime_bin = resolve_runtime(&runtime);
+            let jobs = resolve_jobs(jobs);
 
             let cwd = env::current_dir()
                 .context("Failed to get current directory")?
@@ -239,6 +730,208 @@ fn main() -> Result<()> {
 
             let git_root = files::find_git_root(&cwd)?;
 
+            let mut pool =
+                pool::SidecarPool::spawn(&runtime_bin, &sidecar_script, &git_root, jobs)?;
+
+            let options = engine::CheckOptions {
+                config_path: config.as_deref(),
+                fix: false,
+                verbose,
+                quiet: true,
+                cache_dir: None,
+                filter: &PolicyFilter::default(),
+            };
+            watch::run(&mut pool, &git_root, &options)?;
+        }
+
+        Commands::Baseline { command } => {
+            let cwd = env::current_dir()
+                .context("Failed to get current directory")?
+                .to_string_lossy()
+                .to_string();
+
+            let git_root = files::find_git_root(&cwd)?;
+
+            match command {
+                BaselineCommand::Create {
+                    output,
+                    verbose,
+                    config,
+                    sidecar_path,
+                    runtime,
+                    jobs,
+                    no_daemon,
+                } => {
+                    let options = engine::CheckOptions {
+                        config_path: config.as_deref(),
+                        fix: false,
+                        verbose,
+                        quiet: true,
+                        cache_dir: None,
+                        filter: &PolicyFilter::default(),
+                    };
+                    let sidecar = SidecarArgs {
+                        sidecar_path,
+                        runtime,
+                        jobs,
+                    };
+                    let file_list = files::git_ls_files(&git_root)?;
+                    let report = run_check(file_list, &git_root, &options, &sidecar, !no_daemon)?;
+
+                    let path = output.unwrap_or_else(|| {
+                        Path::new(&git_root).join(baseline::DEFAULT_BASELINE_FILE)
+                    });
+                    let count = Baseline::create(&path, &report)?;
+                    eprintln!("Recorded {count} violations in {}", path.display());
+                }
+            }
+        }
+
+        #[cfg(unix)]
+        Commands::Daemon { command } => {
+            let cwd = env::current_dir()
+                .context("Failed to get current directory")?
+                .to_string_lossy()
+                .to_string();
+
+            let git_root = files::find_git_root(&cwd)?;
+
+            match command {
+                DaemonCommand::Start {
+                    verbose,
+                    config,
+                    sidecar_path,
+                    runtime,
+                    jobs,
+                } => {
+                    let sidecar = SidecarArgs {
+                        sidecar_path,
+                        runtime,
+                        jobs,
+                    };
+                    daemon::serve(&git_root, config.as_deref(), verbose, || {
+                        sidecar.spawn_pool(&git_root, verbose)
+                    })?;
+                }
+
+                DaemonCommand::Stop => {
+                    let client = daemon::connect(&git_root)?.context("No daemon is running")?;
+                    client.shutdown()?;
+                    eprintln!("Daemon stopped.");
+                }
+
+                DaemonCommand::Status => match daemon::connect(&git_root)? {
+                    Some(client) => {
+                        let status = client.status()?;
+                        println!(
+                            "Daemon running (pid {}) at {}",
+                            status.pid,
+                            daemon::socket_path(&git_root)?.display()
+                        );
+                        println!(
+                            "  config: {}",
+                            status.config_file.as_deref().unwrap_or("(default)")
+                        );
+                        println!("  policies: {}", status.policies);
+                        println!("  requests served: {}", status.requests);
+                    }
+                    None => {
+                        println!("No daemon is running.");
+                        process::exit(1);
+                    }
+                },
+            }
+        }
+
+        Commands::Lsp {
+            config,
+            sidecar_path,
+            runtime,
+        } => {
+            let sidecar_script = resolve_sidecar_path(sidecar_path.as_deref())?;
+            let runtime_bin = resolve_runtime(&runtime);
+
+            lsp::run(config.as_deref(), |git_root| {
+                ipc::Sidecar::spawn(&runtime_bin, &sidecar_script, git_root)
+            })?;
+        }
+
+        Commands::Explain {
+            files: paths,
+            config,
+            sidecar_path,
+            runtime,
+            no_daemon,
+        } => {
+            let cwd = env::current_dir().context("Failed to get current directory")?;
+            let git_root = files::find_git_root(&cwd.to_string_lossy())?;
+
+            let file_list = paths
+                .iter()
+                .map(|path| explain::repo_path(&git_root, &cwd, path))
+                .collect::<Result<Vec<_>>>()?;
+
+            #[cfg(unix)]
+            let from_daemon = if no_daemon {
+                None
+            } else {
+                match daemon::connect(&git_root)? {
+                    Some(client) => client.list(config.as_deref())?,
+                    None => None,
+                }
+            };
+            #[cfg(not(unix))]
+            let from_daemon = {
+                let _ = no_daemon;
+                None
+            };
+
+            let loaded = match from_daemon {
+                Some(loaded) => loaded,
+                None => {
+                    let sidecar_script = resolve_sidecar_path(sidecar_path.as_deref())?;
+                    let runtime_bin = resolve_runtime(&runtime);
+                    let mut sidecar =
+                        ipc::Sidecar::spawn(&runtime_bin, &sidecar_script, &git_root)?;
+                    let loaded = sidecar.load_config(config.as_deref(), &git_root)?;
+                    sidecar.shutdown()?;
+                    loaded
+                }
+            };
+
+            let policies = engine::PolicySet::new(loaded)?;
+            explain::print(&policies, &git_root, &file_list)?;
+        }
+
+        Commands::List {
+            verbose,
+            quiet: _,
+            config,
+            sidecar_path,
+            runtime,
+            no_daemon,
+        } => {
+            let cwd = env::current_dir()
+                .context("Failed to get current directory")?
+                .to_string_lossy()
+                .to_string();
+
+            let git_root = files::find_git_root(&cwd)?;
+
+            #[cfg(unix)]
+            if !no_daemon
+                && let Some(client) = daemon::connect(&git_root)?
+                && let Some(loaded) = client.list(config.as_deref())?
+            {
+                engine::print_list(&loaded, verbose);
+                return Ok(());
+            }
+            #[cfg(not(unix))]
+            let _ = no_daemon;
+
+            let sidecar_script = resolve_sidecar_path(sidecar_path.as_deref())?;
+            let runtime_bin = resolve_runtime(&runtime);
+
             let mut sidecar = ipc::Sidecar::spawn(&runtime_bin, &sidecar_script, &git_root)?;
             engine::run_list(&mut sidecar, &git_root, config.as_deref(), verbose)?;
             sidecar.shutdown()?;
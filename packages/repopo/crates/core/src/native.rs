@@ -0,0 +1,434 @@
+//! Policies built into repopo-core.
+//!
+//! A native policy checks a file's path and contents in-process, without a
+//! round trip to the sidecar. A config enables native policies by name in
+//! `nativePolicies`. Once loaded they are routed, filtered, cached, and
+//! reported like sidecar policies; only the engine's dispatch differs.
+
+use anyhow::{Context, Result};
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::gitindex::GitIndex;
+use crate::types::{FilePattern, HandlerResult, LoadConfigResponse, PolicyErrorResult, PolicyMeta};
+
+mod cargo_licence_validated;
+mod cargo_lints_configured;
+mod file_header;
+mod file_headers;
+mod gleam_licence_configured;
+mod no_large_binary_files;
+mod no_target_artifacts;
+mod package_esm_type;
+mod package_json;
+mod package_json_properties;
+mod package_json_sorted;
+mod package_license;
+mod package_private_field;
+mod spdx;
+mod toml;
+mod toml_sorted;
+mod workspace_inheritance;
+
+/// A policy implemented in Rust.
+pub trait NativePolicy: Send + Sync {
+    /// A description of what the policy checks.
+    fn description(&self) -> &str;
+
+    /// The pattern that determines which files the policy applies to.
+    fn match_pattern(&self) -> FilePattern;
+
+    /// Tags that group related policies, such as `cargo` or `package`.
+    fn tags(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Check a file. A failure's `fixable` defaults to
+    /// [`NativePolicy::has_resolver`].
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult>;
+
+    /// Whether [`NativePolicy::resolve`] can fix the failures the policy
+    /// reports.
+    fn has_resolver(&self) -> bool {
+        false
+    }
+
+    /// Whether a pass depends only on the checked file, so it can be cached
+    /// by the file's content. Policies that read other files return false.
+    fn cacheable(&self) -> bool {
+        true
+    }
+
+    /// Fix a file that failed the check. Returns the fixed contents, or
+    /// `None` if this failure cannot be fixed. A policy that fixes other
+    /// files returns the file's contents unchanged, which are not written.
+    fn resolve(&self, _file: &NativeFile) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+/// A file a native policy runs on. The contents are read on first use, so
+/// policies that only look at the path never read the file.
+pub struct NativeFile<'a> {
+    /// Absolute path to the repo root.
+    pub root: &'a str,
+
+    /// Repo-relative path to the file.
+    pub path: &'a str,
+
+    /// The git index of the repo, shared by the files of a run.
+    index: Option<&'a GitIndex<'a>>,
+
+    contents: OnceCell<Vec<u8>>,
+}
+
+impl<'a> NativeFile<'a> {
+    pub fn new(root: &'a str, path: &'a str, index: Option<&'a GitIndex<'a>>) -> Self {
+        Self {
+            root,
+            path,
+            index,
+            contents: OnceCell::new(),
+        }
+    }
+
+    /// Absolute path to the file.
+    pub fn absolute_path(&self) -> PathBuf {
+        Path::new(self.root).join(self.path)
+    }
+
+    /// The file's size in bytes, from the git index if it has the file
+    /// unchanged. Returns `None` if the file does not exist or is not a
+    /// file.
+    pub fn size(&self) -> Option<u64> {
+        if let Some(size) = self.index.and_then(|index| index.size(self.path)) {
+            return Some(size);
+        }
+        let metadata = fs::metadata(self.absolute_path()).ok()?;
+        metadata.is_file().then_some(metadata.len())
+    }
+
+    /// The file's contents.
+    pub fn contents(&self) -> Result<&[u8]> {
+        if let Some(contents) = self.contents.get() {
+            return Ok(contents);
+        }
+        let contents = fs::read(self.absolute_path())
+            .with_context(|| format!("Failed to read {}", self.path))?;
+        Ok(self.contents.get_or_init(|| contents))
+    }
+}
+
+/// Creates a native policy from its settings in the config.
+type Constructor = fn(&serde_json::Value) -> Result<Box<dyn NativePolicy>>;
+
+/// Every native policy, by name.
+const REGISTRY: &[(&str, Constructor)] = &[
+    ("CargoLicenceValidated", cargo_licence_validated::create),
+    ("CargoLintsConfigured", cargo_lints_configured::create),
+    ("CargoTomlSorted", toml_sorted::create_cargo),
+    ("GleamLicenceConfigured", gleam_licence_configured::create),
+    ("GleamTomlSorted", toml_sorted::create_gleam),
+    ("HtmlFileHeaders", file_headers::create_html),
+    ("JsTsFileHeaders", file_headers::create_js_ts),
+    ("NoLargeBinaryFiles", no_large_binary_files::create),
+    ("NoTargetArtifacts", no_target_artifacts::create),
+    ("PackageEsmType", package_esm_type::create),
+    ("PackageJsonProperties", package_json_properties::create),
+    ("PackageJsonSorted", package_json_sorted::create),
+    ("PackageLicense", package_license::create),
+    ("PackagePrivateField", package_private_field::create),
+    ("WorkspaceInheritance", workspace_inheritance::create),
+];
+
+/// The native policies of a loaded config, by policy index.
+pub type NativePolicies = HashMap<usize, Box<dyn NativePolicy>>;
+
+/// Create a native policy by name.
+fn create(name: &str, config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    let Some((_, constructor)) = REGISTRY.iter().find(|(known, _)| *known == name) else {
+        let known: Vec<&str> = REGISTRY.iter().map(|(known, _)| *known).collect();
+        anyhow::bail!(
+            "Unknown native policy '{name}'. Known native policies: {}",
+            known.join(", ")
+        );
+    };
+    constructor(config).with_context(|| format!("Invalid config for native policy '{name}'"))
+}
+
+/// Create the native policies a config enables and move them into its
+/// policies, after the sidecar policies, so that the sidecar's policy
+/// indexes stay valid.
+pub fn load(config: &mut LoadConfigResponse) -> Result<NativePolicies> {
+    let offset = config.policies.len();
+    let mut policies = NativePolicies::new();
+
+    let enabled = std::mem::take(&mut config.native_policies);
+    for (index, native) in enabled.into_iter().enumerate() {
+        let policy = create(&native.name, &native.config)?;
+        config.policies.push(PolicyMeta {
+            description: policy.description().to_string(),
+            tags: policy.tags().iter().map(|tag| tag.to_string()).collect(),
+            match_pattern: policy.match_pattern(),
+            severity: native.severity,
+            has_resolver: policy.has_resolver(),
+            exclude_files: native.exclude_files,
+            config_hash: Some(format!(
+                "{:016x}",
+                xxh3_64(native.config.to_string().as_bytes())
+            )),
+            cacheable: policy.cacheable(),
+            scope: native.scope,
+            native: true,
+            name: native.name,
+        });
+        policies.insert(offset + index, policy);
+    }
+
+    for scope in &mut config.scopes {
+        let native = std::mem::take(&mut scope.native_policies);
+        scope
+            .policies
+            .extend(native.into_iter().map(|index| offset + index));
+    }
+
+    Ok(policies)
+}
+
+/// Run a native policy on a file, fixing it if `resolve` is set and the
+/// policy can. Errors are reported as failures, like handler errors in the
+/// sidecar. Runs on the files of a check share an `index`.
+pub fn run(
+    policy: &dyn NativePolicy,
+    root: &str,
+    path: &str,
+    index: Option<&GitIndex>,
+    resolve: bool,
+) -> HandlerResult {
+    let file = NativeFile::new(root, path, index);
+    let mut failure = match policy.check(&file) {
+        Ok(HandlerResult::Failure(failure)) => failure,
+        Ok(pass) => return pass,
+        Err(err) => {
+            return HandlerResult::Failure(PolicyErrorResult {
+                error: Some(format!("Handler error: {err:#}")),
+                fixable: Some(false),
+                ..PolicyErrorResult::default()
+            });
+        }
+    };
+    failure.fixable.get_or_insert(policy.has_resolver());
+
+    if resolve && policy.has_resolver() {
+        match write_fix(policy, &file) {
+            // Check the fixed file again, so a partial fix reports what is
+            // left as a failed fix.
+            Ok(true) => match policy.check(&NativeFile::new(root, path, index)) {
+                Ok(HandlerResult::Failure(mut remaining)) => {
+                    remaining.fixable.get_or_insert(policy.has_resolver());
+                    remaining.fixed = Some(false);
+                    return HandlerResult::Failure(remaining);
+                }
+                Ok(_) => failure.fixed = Some(true),
+                Err(err) => {
+                    failure.error = Some(format!("Handler error: {err:#}"));
+                    failure.fixed = Some(false);
+                }
+            },
+            Ok(false) => failure.fixed = Some(false),
+            Err(err) => {
+                failure.error = Some(format!("Resolver error: {err:#}"));
+                failure.fixed = Some(false);
+            }
+        }
+    }
+
+    HandlerResult::Failure(failure)
+}
+
+/// Write a policy's fix for a file. Returns false if the policy could not
+/// fix it.
+fn write_fix(policy: &dyn NativePolicy, file: &NativeFile) -> Result<bool> {
+    let Some(contents) = policy.resolve(file)? else {
+        return Ok(false);
+    };
+    if file
+        .contents()
+        .is_ok_and(|current| current == contents.as_slice())
+    {
+        return Ok(true);
+    }
+    fs::write(file.absolute_path(), contents)
+        .with_context(|| format!("Failed to write {}", file.path))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NativeFile, NativePolicy, create, run};
+    use crate::gitindex::GitIndex;
+    use crate::pattern::Matcher;
+    use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+    use anyhow::Result;
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::process::Command;
+
+    /// Fails files that do not end with this many newlines, and adds one.
+    struct FinalNewlines(usize);
+
+    impl NativePolicy for FinalNewlines {
+        fn description(&self) -> &str {
+            "Files end with a newline"
+        }
+
+        fn match_pattern(&self) -> FilePattern {
+            FilePattern::Glob {
+                glob: "**".to_string(),
+                ignore_case: false,
+            }
+        }
+
+        fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+            if file.contents()?.ends_with(&b"\n".repeat(self.0)) {
+                return Ok(HandlerResult::Pass(true));
+            }
+            Ok(HandlerResult::Failure(PolicyErrorResult {
+                error: Some("No final newline".to_string()),
+                ..PolicyErrorResult::default()
+            }))
+        }
+
+        fn has_resolver(&self) -> bool {
+            true
+        }
+
+        fn resolve(&self, file: &NativeFile) -> Result<Option<Vec<u8>>> {
+            let mut contents = file.contents()?.to_vec();
+            contents.push(b'\n');
+            Ok(Some(contents))
+        }
+    }
+
+    #[test]
+    fn checks_and_resolves_in_process() {
+        let root = std::env::temp_dir().join(format!("repopo-native-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("a.txt"), "a").unwrap();
+        let root_str = root.to_str().unwrap();
+
+        let result = run(&FinalNewlines(1), root_str, "a.txt", None, false);
+        assert!(!result.is_pass() && result.is_fixable() && !result.is_fixed());
+
+        let result = run(&FinalNewlines(1), root_str, "a.txt", None, true);
+        assert!(result.is_fixed());
+        assert_eq!(fs::read_to_string(root.join("a.txt")).unwrap(), "a\n");
+        assert!(run(&FinalNewlines(1), root_str, "a.txt", None, false).is_pass());
+
+        // A fix that leaves the file failing is reported as failed.
+        let result = run(&FinalNewlines(3), root_str, "a.txt", None, true);
+        assert!(result.is_fix_failed());
+        assert_eq!(fs::read_to_string(root.join("a.txt")).unwrap(), "a\n\n");
+
+        let result = run(&FinalNewlines(1), root_str, "missing.txt", None, true);
+        assert!(
+            result
+                .error_message()
+                .unwrap()
+                .starts_with("Handler error:")
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Native ports of TS policies are checked against the same fixture as
+    /// the TS policies by `test/native-policy-conformance.test.ts`.
+    const CONFORMANCE: &str = include_str!("../../../test/data/native-policy-conformance.json");
+
+    #[derive(Deserialize)]
+    struct ConformanceCase {
+        policy: String,
+        #[serde(default)]
+        config: serde_json::Value,
+        files: BTreeMap<String, ConformanceFile>,
+        failures: BTreeMap<String, ConformanceFailure>,
+    }
+
+    /// A file to create, of a size in zero bytes or with given contents.
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ConformanceFile {
+        Size(usize),
+        Contents(String),
+    }
+
+    /// A failure with a single `error` lists it in `error_messages`.
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ConformanceFailure {
+        error_messages: Vec<String>,
+        manual_fix: Option<String>,
+    }
+
+    #[test]
+    fn matches_the_ts_policies() {
+        let suite: Vec<ConformanceCase> =
+            serde_json::from_str(CONFORMANCE).expect("conformance fixture is valid JSON");
+        let root = std::env::temp_dir().join(format!("repopo-conformance-{}", std::process::id()));
+        let root_str = root.to_str().unwrap();
+
+        for (index, case) in suite.iter().enumerate() {
+            let _ = fs::remove_dir_all(&root);
+            fs::create_dir_all(&root).unwrap();
+            for (file, contents) in &case.files {
+                let path = root.join(file);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                match contents {
+                    ConformanceFile::Size(size) => fs::write(path, vec![0u8; *size]),
+                    ConformanceFile::Contents(contents) => fs::write(path, contents),
+                }
+                .unwrap();
+            }
+
+            // Stage every other file, so sizes come from both the git index
+            // and the file system.
+            let staged: Vec<&str> = case.files.keys().step_by(2).map(String::as_str).collect();
+            for args in [&["init", "-q"][..], &[&["add", "--"][..], &staged].concat()] {
+                let output = Command::new("git")
+                    .args(args)
+                    .current_dir(&root)
+                    .output()
+                    .unwrap();
+                assert!(output.status.success(), "git {args:?}");
+            }
+
+            let policy = create(&case.policy, &case.config).unwrap();
+            let matcher = Matcher::new(&policy.match_pattern()).unwrap();
+            let index_sizes = GitIndex::new(root_str);
+            let mut failures = BTreeMap::new();
+            for file in case.files.keys().filter(|file| matcher.is_match(file)) {
+                if let HandlerResult::Failure(failure) =
+                    run(policy.as_ref(), root_str, file, Some(&index_sizes), false)
+                {
+                    failures.insert(
+                        file.clone(),
+                        ConformanceFailure {
+                            error_messages: failure
+                                .error_messages
+                                .unwrap_or_else(|| failure.error.into_iter().collect()),
+                            manual_fix: failure.manual_fix,
+                        },
+                    );
+                }
+            }
+            assert_eq!(failures, case.failures, "{} #{index}", case.policy);
+        }
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+//! Native port of `src/policies/CargoLicenceValidated.ts`.
+//!
+//! Licences are validated with the embedded SPDX list in [`super::spdx`]
+//! rather than `spdx-correct`, and a `license.workspace = true` inherited
+//! from the workspace passes.
+
+use anyhow::Result;
+use serde::Deserialize;
+use toml_edit::{DocumentMut, Item, Value};
+
+use super::{NativeFile, NativePolicy, spdx, toml};
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Policy settings, as for the TS policy.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    /// Allowed SPDX licence identifiers. If set, only these are accepted.
+    allowed_licences: Option<Vec<String>>,
+
+    /// Validate the licence against the SPDX licence list. Defaults to true.
+    validate_spdx: Option<bool>,
+
+    /// Skip Cargo.toml files without a `[package]` section. Defaults to true.
+    skip_workspace: Option<bool>,
+}
+
+const MANUAL_FIX: &str = "Use a valid SPDX licence identifier (e.g., MIT, Apache-2.0, MPL-2.0).";
+
+/// Validates the `license` field in Cargo.toml against the SPDX licence list.
+struct CargoLicenceValidated {
+    allowed_licences: Option<Vec<String>>,
+    validate_spdx: bool,
+    skip_workspace: bool,
+}
+
+pub fn create(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    let settings: Settings =
+        serde_json::from_value::<Option<Settings>>(config.clone())?.unwrap_or_default();
+    Ok(Box::new(CargoLicenceValidated {
+        allowed_licences: settings.allowed_licences,
+        validate_spdx: settings.validate_spdx.unwrap_or(true),
+        skip_workspace: settings.skip_workspace.unwrap_or(true),
+    }))
+}
+
+/// The `license` value in `[package]`.
+fn licence_mut(doc: &mut DocumentMut) -> Option<&mut Value> {
+    doc.get_mut("package")?
+        .as_table_like_mut()?
+        .get_mut("license")?
+        .as_value_mut()
+}
+
+impl CargoLicenceValidated {
+    fn validate(&self, licence: &str) -> Option<spdx::Invalid> {
+        spdx::validate(
+            &[licence],
+            self.allowed_licences.as_deref(),
+            self.validate_spdx,
+        )
+    }
+}
+
+impl NativePolicy for CargoLicenceValidated {
+    fn description(&self) -> &str {
+        "Validates that Cargo.toml has a valid SPDX licence identifier."
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: r"(^|\/)Cargo\.toml$".to_string(),
+            flags: String::new(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["cargo"]
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        let doc = toml::parse(file)?;
+        let package = doc.get("package").and_then(Item::as_table_like);
+        if package.is_none() && self.skip_workspace {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        let licence = package.and_then(|package| package.get("license"));
+        if licence
+            .and_then(Item::as_table_like)
+            .and_then(|licence| licence.get("workspace"))
+            .and_then(Item::as_bool)
+            == Some(true)
+        {
+            return Ok(HandlerResult::Pass(true));
+        }
+        let licence = licence
+            .and_then(Item::as_str)
+            .filter(|licence| !licence.trim().is_empty());
+        let Some(licence) = licence else {
+            return Ok(HandlerResult::Failure(PolicyErrorResult {
+                error: Some("Missing or empty license field in [package] section.".to_string()),
+                manual_fix: Some(
+                    "Add license = \"MIT\" (or appropriate SPDX identifier) to [package] in Cargo.toml."
+                        .to_string(),
+                ),
+                fixable: Some(false),
+                ..PolicyErrorResult::default()
+            }));
+        };
+
+        Ok(match self.validate(licence) {
+            Some(invalid) => invalid.failure(MANUAL_FIX),
+            None => HandlerResult::Pass(true),
+        })
+    }
+
+    fn has_resolver(&self) -> bool {
+        true
+    }
+
+    fn resolve(&self, file: &NativeFile) -> Result<Option<Vec<u8>>> {
+        let mut doc = toml::parse(file)?;
+        let Some(value) = licence_mut(&mut doc) else {
+            return Ok(None);
+        };
+        let Some(invalid) = value.as_str().and_then(|licence| self.validate(licence)) else {
+            return Ok(None);
+        };
+        let Some((_, corrected)) = invalid.corrections.first() else {
+            return Ok(None);
+        };
+        toml::replace_str(value, corrected);
+        Ok(Some(doc.to_string().into_bytes()))
+    }
+}
@@ -0,0 +1,155 @@
+//! Native port of `src/policies/CargoLintsConfigured.ts`.
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::Map;
+use toml_edit::{Item, TableLike, Value};
+
+use super::{NativeFile, NativePolicy, toml};
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Policy settings, as for the TS policy.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    /// Required clippy lint levels, by lint name without the `clippy::`
+    /// prefix. Missing and mismatched lints are reported in this order.
+    required: Option<Map<String, serde_json::Value>>,
+
+    /// If true, require that a `[lints.clippy]` section exists. Defaults to
+    /// true.
+    require_section: Option<bool>,
+}
+
+const ADD_SECTION: &str =
+    "Add a [lints.clippy] section, e.g.:\n[lints.clippy]\npedantic = \"warn\"";
+
+/// Ensures Cargo.toml has a `[lints.clippy]` section configured.
+struct CargoLintsConfigured {
+    required: Vec<(String, String)>,
+    require_section: bool,
+}
+
+pub fn create(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    let settings: Settings =
+        serde_json::from_value::<Option<Settings>>(config.clone())?.unwrap_or_default();
+    let required = settings
+        .required
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(lint, level)| Ok((lint, serde_json::from_value(level)?)))
+        .collect::<Result<_>>()?;
+    Ok(Box::new(CargoLintsConfigured {
+        required,
+        require_section: settings.require_section.unwrap_or(true),
+    }))
+}
+
+/// A failure with a message and instructions to fix it by hand.
+fn failure(error: String, manual_fix: &str) -> HandlerResult {
+    HandlerResult::Failure(PolicyErrorResult {
+        error: Some(error),
+        manual_fix: Some(manual_fix.to_string()),
+        ..PolicyErrorResult::default()
+    })
+}
+
+/// The level a lint is set to. A lint can be set to a level, or to a table
+/// with a `level` and a `priority`.
+fn lint_level(item: &Item) -> Option<&str> {
+    item.as_str().or_else(|| {
+        item.as_table_like()
+            .and_then(|table| table.get("level"))
+            .and_then(Item::as_str)
+    })
+}
+
+impl CargoLintsConfigured {
+    fn check_clippy_lints(&self, clippy: &dyn TableLike) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (lint, expected_level) in &self.required {
+            match clippy.get(lint) {
+                None => errors.push(format!(
+                    "Missing clippy lint: {lint} (expected level: \"{expected_level}\")"
+                )),
+                Some(actual) => match lint_level(actual) {
+                    None => errors.push(format!(
+                        "Clippy lint {lint} has no level, expected \"{expected_level}\""
+                    )),
+                    Some(level) if level != expected_level => errors.push(format!(
+                        "Clippy lint {lint} = \"{level}\", expected \"{expected_level}\""
+                    )),
+                    Some(_) => {}
+                },
+            }
+        }
+        errors
+    }
+}
+
+impl NativePolicy for CargoLintsConfigured {
+    fn description(&self) -> &str {
+        "Ensures Cargo.toml has clippy lints configured in the [lints] section."
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: r"(^|\/)Cargo\.toml$".to_string(),
+            flags: String::new(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["cargo"]
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        let doc = toml::parse(file)?;
+
+        // Skip workspace root Cargo.toml files
+        if doc.contains_key("workspace") && !doc.contains_key("package") {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        let Some(lints) = doc.get("lints").and_then(Item::as_table_like) else {
+            if self.require_section {
+                return Ok(failure(
+                    "Missing [lints] section in Cargo.toml.".to_string(),
+                    ADD_SECTION,
+                ));
+            }
+            return Ok(HandlerResult::Pass(true));
+        };
+
+        // A crate that inherits the workspace's lints is configured there.
+        if lints
+            .get("workspace")
+            .and_then(|item| item.as_value())
+            .and_then(Value::as_bool)
+            == Some(true)
+        {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        let clippy = lints.get("clippy").and_then(Item::as_table_like);
+        let Some(clippy) = clippy else {
+            if self.require_section {
+                return Ok(failure(
+                    "Missing [lints.clippy] section in Cargo.toml.".to_string(),
+                    ADD_SECTION,
+                ));
+            }
+            return Ok(HandlerResult::Pass(true));
+        };
+
+        let errors = self.check_clippy_lints(clippy);
+        if !errors.is_empty() {
+            return Ok(failure(
+                errors.join("; "),
+                "Update the [lints.clippy] section with the required lint levels.",
+            ));
+        }
+
+        Ok(HandlerResult::Pass(true))
+    }
+}
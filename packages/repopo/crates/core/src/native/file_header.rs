@@ -0,0 +1,344 @@
+//! Checking and inserting file headers for native policies.
+//!
+//! A header is a comment at the top of a file, after any byte order mark
+//! and `#!` line. Its text is a template: `{year}` matches a year or a
+//! range such as `2019-2025`, and `{license}` matches a valid SPDX licence
+//! expression unless a licence is given. Trailing whitespace on header
+//! lines is ignored, and CRLF files get CRLF headers.
+
+use regex::Regex;
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::spdx;
+
+const BOM: &str = "\u{feff}";
+
+/// How each line of a header is written as a comment.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentStyle {
+    /// `/*!` and ` */` around lines that begin with ` * `, followed by a
+    /// blank line.
+    Block,
+
+    /// Lines that begin with `// `.
+    Line,
+
+    /// Lines wrapped in `<!-- ` and ` -->`.
+    Html,
+
+    /// Lines that begin with `# `.
+    Hash,
+}
+
+/// The parts of a comment style.
+struct Syntax {
+    /// A line before the header.
+    start: Option<&'static str>,
+    line_start: &'static str,
+    line_end: &'static str,
+    /// A line after the header.
+    end: Option<&'static str>,
+    /// Whether a blank line must follow the header.
+    blank_line_after: bool,
+}
+
+impl CommentStyle {
+    fn syntax(self) -> Syntax {
+        let (start, line_start, line_end, end) = match self {
+            CommentStyle::Block => (Some("/*!"), " * ", "", Some(" */")),
+            CommentStyle::Line => (None, "// ", "", None),
+            CommentStyle::Html => (None, "<!-- ", " -->", None),
+            CommentStyle::Hash => (None, "# ", "", None),
+        };
+        Syntax {
+            start,
+            line_start,
+            line_end,
+            end,
+            blank_line_after: self == CommentStyle::Block,
+        }
+    }
+}
+
+/// A piece of a header line.
+#[derive(Debug, Clone)]
+enum Part {
+    Text(String),
+    Year,
+    License,
+}
+
+/// A header line as a template, and the pattern that matches it.
+struct Line {
+    parts: Vec<Part>,
+    pattern: Regex,
+}
+
+impl Line {
+    fn new(style: &Syntax, text: &str, license: Option<&str>) -> Self {
+        let mut parts = vec![Part::Text(style.line_start.to_string())];
+        let mut rest = text;
+        while let Some(open) = rest.find('{') {
+            let (placeholder, len) = if rest[open..].starts_with("{year}") {
+                (Part::Year, "{year}".len())
+            } else if rest[open..].starts_with("{license}") {
+                let part = license.map_or(Part::License, |license| Part::Text(license.to_string()));
+                (part, "{license}".len())
+            } else {
+                parts.push(Part::Text(rest[..=open].to_string()));
+                rest = &rest[open + 1..];
+                continue;
+            };
+            parts.push(Part::Text(rest[..open].to_string()));
+            parts.push(placeholder);
+            rest = &rest[open + len..];
+        }
+        parts.push(Part::Text(format!("{rest}{}", style.line_end)));
+
+        // Merge adjacent text, and ignore trailing whitespace.
+        let mut merged: Vec<Part> = Vec::new();
+        for part in parts {
+            match (merged.last_mut(), part) {
+                (Some(Part::Text(last)), Part::Text(text)) => last.push_str(&text),
+                (_, part) => merged.push(part),
+            }
+        }
+        if let Some(Part::Text(last)) = merged.last_mut() {
+            last.truncate(last.trim_end().len());
+        }
+
+        let pattern: String = merged
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => regex::escape(text),
+                Part::Year => r"\d{4}(?:\s*-\s*\d{4})?".to_string(),
+                Part::License => "(.+?)".to_string(),
+            })
+            .collect();
+        Self {
+            parts: merged,
+            pattern: Regex::new(&format!("^{pattern}$"))
+                .expect("escaped header is a valid pattern"),
+        }
+    }
+
+    /// Whether a line of a file, without its line ending, matches.
+    fn matches(&self, line: &str) -> bool {
+        self.pattern
+            .captures(line.trim_end())
+            .is_some_and(|captures| {
+                captures
+                    .iter()
+                    .skip(1)
+                    .flatten()
+                    .all(|license| spdx::parse(license.as_str()).is_ok())
+            })
+    }
+
+    /// The line with its placeholders filled in, or `None` if it needs a
+    /// licence that was not given.
+    fn render(&self, year: i64) -> Option<String> {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => Some(text.clone()),
+                Part::Year => Some(year.to_string()),
+                Part::License => None,
+            })
+            .collect()
+    }
+}
+
+/// A header to check files for, in a comment style.
+pub struct Header {
+    style: Syntax,
+    text: Vec<Line>,
+    auto_gen: Vec<Line>,
+}
+
+impl Header {
+    /// A header of `text` lines, optionally followed by `auto_gen` lines.
+    /// `license` fills in `{license}` placeholders.
+    pub fn new(
+        style: CommentStyle,
+        text: &str,
+        auto_gen: Option<&str>,
+        license: Option<&str>,
+    ) -> Self {
+        let style = style.syntax();
+        let lines = |text: &str| -> Vec<Line> {
+            text.lines()
+                .map(|line| Line::new(&style, line, license))
+                .collect()
+        };
+        let text = lines(text);
+        let auto_gen = auto_gen.map(lines).unwrap_or_default();
+        Self {
+            style,
+            text,
+            auto_gen,
+        }
+    }
+
+    /// Whether the header can be inserted, which it cannot if it needs a
+    /// licence that was not given.
+    pub fn can_insert(&self) -> bool {
+        self.text
+            .iter()
+            .chain(&self.auto_gen)
+            .flat_map(|line| &line.parts)
+            .all(|part| !matches!(part, Part::License))
+    }
+
+    /// Whether a file starts with the header.
+    pub fn is_present(&self, contents: &str) -> bool {
+        let (_, rest) = split_prelude(contents);
+        let mut lines = rest.lines().map(str::trim_end);
+
+        if let Some(start) = self.style.start
+            && lines.next() != Some(start)
+        {
+            return false;
+        }
+        let matches_all = |lines: &mut dyn Iterator<Item = &str>, template: &[Line]| {
+            template
+                .iter()
+                .all(|line| lines.next().is_some_and(|actual| line.matches(actual)))
+        };
+        if !matches_all(&mut lines, &self.text) {
+            return false;
+        }
+        // The auto-generated text is optional, but must be complete.
+        let before_auto_gen = lines.clone();
+        if !matches_all(&mut lines, &self.auto_gen) {
+            lines = before_auto_gen;
+        }
+        if let Some(end) = self.style.end
+            && lines.next() != Some(end)
+        {
+            return false;
+        }
+        !self.style.blank_line_after || lines.next() == Some("")
+    }
+
+    /// The contents with the header inserted after any byte order mark and
+    /// `#!` line, separated from the rest by a blank line. Returns `None` if
+    /// the header needs a licence that was not given.
+    pub fn insert(&self, contents: &str, year: i64) -> Option<String> {
+        let newline = if contents.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let (prelude, rest) = split_prelude(contents);
+
+        let mut lines: Vec<String> = Vec::new();
+        lines.extend(self.style.start.map(str::to_string));
+        for line in self.text.iter().chain(&self.auto_gen) {
+            lines.push(line.render(year)?);
+        }
+        lines.extend(self.style.end.map(str::to_string));
+
+        let mut result = prelude.to_string();
+        if !prelude.is_empty() && prelude != BOM && !prelude.ends_with('\n') {
+            result.push_str(newline);
+        }
+        for line in lines {
+            result.push_str(&line);
+            result.push_str(newline);
+        }
+        if !rest.starts_with(['\r', '\n']) {
+            result.push_str(newline);
+        }
+        result.push_str(rest);
+        Some(result)
+    }
+}
+
+/// Split a file into its byte order mark and `#!` line, and the rest.
+fn split_prelude(contents: &str) -> (&str, &str) {
+    let mut len = if contents.starts_with(BOM) {
+        BOM.len()
+    } else {
+        0
+    };
+    if contents[len..].starts_with("#!") {
+        len = contents[len..]
+            .find('\n')
+            .map_or(contents.len(), |newline| len + newline + 1);
+    }
+    contents.split_at(len)
+}
+
+/// The current year in UTC.
+pub fn current_year() -> i64 {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() / 86_400) as i64;
+    // Howard Hinnant's civil_from_days, for the year alone.
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let year = year_of_era + era * 400;
+    if month >= 10 { year + 1 } else { year }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommentStyle, Header};
+
+    #[test]
+    fn checks_and_inserts_headers() {
+        let header = Header::new(
+            CommentStyle::Block,
+            "Copyright (c) {year} Example\nSPDX-License-Identifier: {license}",
+            Some("Generated by a tool"),
+            Some("MIT"),
+        );
+        let expected = "/*!\n * Copyright (c) 2025 Example\n * SPDX-License-Identifier: MIT\n * Generated by a tool\n */\n\nlet x = 1;\n";
+        assert_eq!(header.insert("let x = 1;\n", 2025).unwrap(), expected);
+        assert!(header.is_present(expected));
+        assert!(header.is_present(&expected.replace("2025", "2019 - 2025")));
+        assert!(header.is_present(&expected.replace(" * Generated by a tool\n", "")));
+        assert!(!header.is_present(&expected.replace("MIT", "ISC")));
+        assert!(!header.is_present(&expected.replace("\n\nlet", "\nlet")));
+
+        let with_shebang = "\u{feff}#!/usr/bin/env node\r\n\r\nrun();\r\n";
+        let fixed = header.insert(with_shebang, 2025).unwrap();
+        assert!(fixed.starts_with("\u{feff}#!/usr/bin/env node\r\n/*!\r\n * Copyright"));
+        assert!(fixed.ends_with(" */\r\n\r\nrun();\r\n"));
+        assert!(header.is_present(&fixed));
+    }
+
+    #[test]
+    fn matches_any_valid_license() {
+        let header = Header::new(
+            CommentStyle::Hash,
+            "SPDX-License-Identifier: {license}",
+            None,
+            None,
+        );
+        assert!(header.is_present("#!/bin/sh\n# SPDX-License-Identifier: MIT OR Apache-2.0\n"));
+        assert!(!header.is_present("# SPDX-License-Identifier: Proprietary\n"));
+        assert_eq!(header.insert("echo\n", 2025), None);
+
+        let html = Header::new(
+            CommentStyle::Html,
+            "Copyright {year}\n\nAll rights reserved.",
+            None,
+            None,
+        );
+        let fixed = html.insert("<p></p>\n", 2025).unwrap();
+        assert_eq!(
+            fixed,
+            "<!-- Copyright 2025 -->\n<!--  -->\n<!-- All rights reserved. -->\n\n<p></p>\n"
+        );
+        assert!(html.is_present(&fixed));
+    }
+}
@@ -0,0 +1,135 @@
+//! Native ports of `src/policies/JsTsFileHeaders.ts` and
+//! `src/policies/HtmlFileHeaders.ts`.
+//!
+//! Both check for the configured header text, with the placeholders of
+//! [`super::file_header`], and insert it after any `#!` line.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::file_header::{self, CommentStyle, Header};
+use super::{NativeFile, NativePolicy};
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Policy settings, as for the TS policies.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    /// The text to use as the header.
+    header_text: Option<String>,
+
+    /// Text that may follow the header text.
+    auto_gen_text: Option<String>,
+
+    /// How the header is written as a comment. Native only.
+    comment_style: Option<CommentStyle>,
+
+    /// The licence for `{license}` placeholders. Native only.
+    license: Option<String>,
+}
+
+/// Ensures files have the configured header comment.
+struct FileHeaders {
+    description: &'static str,
+    pattern: &'static str,
+    /// The TS policies skip every file without a config.
+    header: Option<Header>,
+}
+
+pub fn create_js_ts(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    create(
+        config,
+        "Ensures JavaScript and TypeScript source files have the configured header comment.",
+        r"(^|\/)[^/]+\.[mc]?[jt]sx?$",
+        CommentStyle::Block,
+    )
+}
+
+pub fn create_html(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    create(
+        config,
+        "Ensures HTML files have the configured header comment.",
+        r"(^|\/)[^/]+\.html?$",
+        CommentStyle::Html,
+    )
+}
+
+fn create(
+    config: &serde_json::Value,
+    description: &'static str,
+    pattern: &'static str,
+    default_style: CommentStyle,
+) -> Result<Box<dyn NativePolicy>> {
+    let settings: Option<Settings> = serde_json::from_value(config.clone())?;
+    let header = settings.map(|settings| {
+        Header::new(
+            settings.comment_style.unwrap_or(default_style),
+            settings.header_text.as_deref().unwrap_or_default(),
+            settings.auto_gen_text.as_deref(),
+            settings.license.as_deref(),
+        )
+    });
+    Ok(Box::new(FileHeaders {
+        description,
+        pattern,
+        header,
+    }))
+}
+
+/// The extension of a path, including the dot, as `extname` in Node.
+fn extname(path: &str) -> &str {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name.rfind('.') {
+        Some(dot) if dot > 0 => &name[dot..],
+        _ => "",
+    }
+}
+
+impl NativePolicy for FileHeaders {
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: self.pattern.to_string(),
+            flags: "i".to_string(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["headers"]
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        let Some(header) = &self.header else {
+            return Ok(HandlerResult::Pass(true));
+        };
+        if header.is_present(&String::from_utf8_lossy(file.contents()?)) {
+            return Ok(HandlerResult::Pass(true));
+        }
+        Ok(HandlerResult::Failure(PolicyErrorResult {
+            error_messages: Some(vec![format!("{} file missing header", extname(file.path))]),
+            fixable: Some(header.can_insert()),
+            ..PolicyErrorResult::default()
+        }))
+    }
+
+    fn has_resolver(&self) -> bool {
+        true
+    }
+
+    fn resolve(&self, file: &NativeFile) -> Result<Option<Vec<u8>>> {
+        let Some(header) = &self.header else {
+            return Ok(None);
+        };
+        let contents = std::str::from_utf8(file.contents()?)
+            .with_context(|| format!("{} is not UTF-8", file.path))?;
+        if header.is_present(contents) {
+            return Ok(None);
+        }
+        Ok(header
+            .insert(contents, file_header::current_year())
+            .map(String::into_bytes))
+    }
+}
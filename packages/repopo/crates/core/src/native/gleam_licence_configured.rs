@@ -0,0 +1,121 @@
+//! Native port of `src/policies/GleamLicenceConfigured.ts`.
+//!
+//! Licences are validated with the embedded SPDX list in [`super::spdx`]
+//! rather than `spdx-correct`.
+
+use anyhow::Result;
+use serde::Deserialize;
+use toml_edit::{Array, DocumentMut, Item, Value};
+
+use super::{NativeFile, NativePolicy, spdx, toml};
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Policy settings, as for the TS policy.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    /// Allowed SPDX licence identifiers. If set, only these are accepted.
+    allowed_licences: Option<Vec<String>>,
+
+    /// Validate licences against the SPDX licence list. Defaults to false.
+    validate_spdx: Option<bool>,
+}
+
+const MANUAL_FIX: &str = "Use valid SPDX licence identifiers (e.g., MIT, Apache-2.0, MPL-2.0).";
+
+/// Ensures licence metadata is properly configured in gleam.toml.
+struct GleamLicenceConfigured {
+    allowed_licences: Option<Vec<String>>,
+    validate_spdx: bool,
+}
+
+pub fn create(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    let settings: Settings =
+        serde_json::from_value::<Option<Settings>>(config.clone())?.unwrap_or_default();
+    Ok(Box::new(GleamLicenceConfigured {
+        allowed_licences: settings.allowed_licences,
+        validate_spdx: settings.validate_spdx.unwrap_or(false),
+    }))
+}
+
+/// The `licences` array, if it is a non-empty array.
+fn licences(doc: &DocumentMut) -> Option<&Array> {
+    doc.get("licences")
+        .and_then(Item::as_array)
+        .filter(|licences| !licences.is_empty())
+}
+
+impl GleamLicenceConfigured {
+    fn validate(&self, licences: &Array) -> Option<spdx::Invalid> {
+        let licences: Vec<&str> = licences.iter().filter_map(Value::as_str).collect();
+        spdx::validate(
+            &licences,
+            self.allowed_licences.as_deref(),
+            self.validate_spdx,
+        )
+    }
+}
+
+impl NativePolicy for GleamLicenceConfigured {
+    fn description(&self) -> &str {
+        "Ensures gleam.toml has valid SPDX licence identifiers configured."
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: r"(^|\/)gleam\.toml$".to_string(),
+            flags: String::new(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["gleam"]
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        let doc = toml::parse(file)?;
+        let Some(licences) = licences(&doc) else {
+            return Ok(HandlerResult::Failure(PolicyErrorResult {
+                error: Some("Missing or empty licences field in gleam.toml.".to_string()),
+                manual_fix: Some(
+                    "Add licences = [\"MIT\"] (or appropriate SPDX identifier) to gleam.toml."
+                        .to_string(),
+                ),
+                fixable: Some(false),
+                ..PolicyErrorResult::default()
+            }));
+        };
+
+        Ok(match self.validate(licences) {
+            Some(invalid) => invalid.failure(MANUAL_FIX),
+            None => HandlerResult::Pass(true),
+        })
+    }
+
+    fn has_resolver(&self) -> bool {
+        true
+    }
+
+    fn resolve(&self, file: &NativeFile) -> Result<Option<Vec<u8>>> {
+        let mut doc = toml::parse(file)?;
+        let Some(invalid) = licences(&doc).and_then(|licences| self.validate(licences)) else {
+            return Ok(None);
+        };
+        if invalid.corrections.is_empty() {
+            return Ok(None);
+        }
+        let Some(licences) = doc.get_mut("licences").and_then(Item::as_array_mut) else {
+            return Ok(None);
+        };
+        for value in licences.iter_mut() {
+            if let Some(corrected) = value
+                .as_str()
+                .and_then(|licence| invalid.correction(licence))
+            {
+                let corrected = corrected.to_string();
+                toml::replace_str(value, &corrected);
+            }
+        }
+        Ok(Some(doc.to_string().into_bytes()))
+    }
+}
@@ -0,0 +1,181 @@
+//! Native port of `src/policies/NoLargeBinaryFiles.ts`.
+//!
+//! Sizes of staged files that have not changed come from their blobs in
+//! git, so most files are checked without touching the file system. Only
+//! modified and untracked files are read with a stat.
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use super::{NativeFile, NativePolicy};
+use crate::jsregex::JsRegex;
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Policy settings, as for the TS policy.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    /// Maximum file size in bytes. Defaults to 10MB.
+    max_size_bytes: Option<u64>,
+
+    /// File extensions to exclude from size checking.
+    exclude_extensions: Option<Vec<String>>,
+
+    /// File patterns to exclude from size checking, where `*` matches
+    /// anything. A pattern without `*` matches paths that contain it.
+    exclude_patterns: Option<Vec<String>>,
+}
+
+/// Default maximum file size (10MB in bytes).
+const DEFAULT_MAX_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Default file extensions that are typically safe to exclude from size
+/// limits.
+const DEFAULT_EXCLUDE_EXTENSIONS: &[&str] = &[
+    ".md", ".txt", ".json", ".js", ".ts", ".jsx", ".tsx", ".css", ".scss", ".sass", ".less",
+    ".html", ".xml", ".yml", ".yaml", ".toml", ".ini", ".conf", ".config",
+];
+
+/// Prevents large binary files from being committed.
+struct NoLargeBinaryFiles {
+    max_size_bytes: u64,
+    exclude_extensions: Vec<String>,
+    exclude_patterns: Vec<ExcludePattern>,
+}
+
+enum ExcludePattern {
+    /// A pattern with `*`, compiled to the same regex as the TS policy.
+    Wildcard(JsRegex),
+
+    /// A pattern without `*`, matched as a substring.
+    Substring(String),
+}
+
+pub fn create(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    let settings: Settings =
+        serde_json::from_value::<Option<Settings>>(config.clone())?.unwrap_or_default();
+
+    let exclude_patterns = settings
+        .exclude_patterns
+        .unwrap_or_default()
+        .into_iter()
+        .map(|pattern| {
+            if !pattern.contains('*') {
+                return Ok(ExcludePattern::Substring(pattern));
+            }
+            let mut source = String::from("^");
+            for c in pattern.chars() {
+                match c {
+                    '*' => source.push_str(".*"),
+                    '.' | '+' | '?' | '^' | '$' | '{' | '}' | '(' | ')' | '|' | '[' | ']'
+                    | '\\' => {
+                        source.push('\\');
+                        source.push(c);
+                    }
+                    _ => source.push(c),
+                }
+            }
+            source.push('$');
+            Ok(ExcludePattern::Wildcard(JsRegex::new(&source, "")?))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(Box::new(NoLargeBinaryFiles {
+        max_size_bytes: settings.max_size_bytes.unwrap_or(DEFAULT_MAX_SIZE),
+        exclude_extensions: settings.exclude_extensions.unwrap_or_else(|| {
+            DEFAULT_EXCLUDE_EXTENSIONS
+                .iter()
+                .map(|ext| ext.to_string())
+                .collect()
+        }),
+        exclude_patterns,
+    }))
+}
+
+impl NoLargeBinaryFiles {
+    /// Check if a file matches any of the exclude patterns or extensions.
+    fn is_excluded(&self, file: &str) -> bool {
+        let extension = extname(file).to_lowercase();
+        if self.exclude_extensions.contains(&extension) {
+            return true;
+        }
+
+        self.exclude_patterns.iter().any(|pattern| match pattern {
+            ExcludePattern::Wildcard(regex) => regex.is_match(file),
+            ExcludePattern::Substring(substring) => file.contains(substring.as_str()),
+        })
+    }
+}
+
+impl NativePolicy for NoLargeBinaryFiles {
+    fn description(&self) -> &str {
+        "Prevents large binary files from being committed to avoid repository bloat; consider using Git LFS for large assets."
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: ".*".to_string(),
+            flags: String::new(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["repo"]
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        if self.is_excluded(file.path) {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        // Directories and files that can't be accessed pass, as in the TS
+        // policy.
+        let Some(size) = file.size() else {
+            return Ok(HandlerResult::Pass(true));
+        };
+        if size <= self.max_size_bytes {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        Ok(HandlerResult::Failure(PolicyErrorResult {
+            error_messages: Some(vec![format!(
+                "File is too large: {} (max: {}). Consider using Git LFS or removing the file.",
+                format_bytes(size),
+                format_bytes(self.max_size_bytes)
+            )]),
+            ..PolicyErrorResult::default()
+        }))
+    }
+}
+
+/// The extension of a path, as `extname` from `pathe` returns it. Unlike
+/// Node's, it includes the leading dot of a dotfile in a directory, so the
+/// extension of `a/.gitignore` is `.gitignore`.
+fn extname(path: &str) -> &str {
+    if path == ".." {
+        return "";
+    }
+    match path.rfind('.') {
+        Some(dot) if dot > 0 && !path[dot..].contains('/') => &path[dot..],
+        _ => "",
+    }
+}
+
+/// Format bytes in a human-readable format, exactly as the TS policy does.
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    if bytes == 0 {
+        return "0 B".to_string();
+    }
+
+    // Computed with floats like `Math.log`, so that the unit is the same
+    // as the TS policy's.
+    let unit = ((bytes as f64).ln() / 1024f64.ln()).floor() as usize;
+    let unit = unit.min(UNITS.len() - 1);
+
+    // `toFixed(1)` rounds halves up where Rust's formatting rounds them to
+    // even, so round in integers.
+    let divisor = 1024u128.pow(unit as u32);
+    let tenths = (u128::from(bytes) * 20 + divisor) / (2 * divisor);
+    format!("{}.{} {}", tenths / 10, tenths % 10, UNITS[unit])
+}
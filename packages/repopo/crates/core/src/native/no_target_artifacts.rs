@@ -0,0 +1,58 @@
+//! Native port of `src/policies/NoTargetArtifacts.ts`.
+
+use anyhow::Result;
+
+use super::{NativeFile, NativePolicy};
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Build artifact extensions that should not be committed to source control.
+const ARTIFACT_EXTENSIONS: &[&str] = &[
+    ".rlib", ".rmeta", ".d", ".so", ".dylib", ".dll", ".exe", ".pdb",
+];
+
+/// Prevents accidental commits of Rust build artifacts.
+struct NoTargetArtifacts;
+
+pub fn create(_config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    Ok(Box::new(NoTargetArtifacts))
+}
+
+impl NativePolicy for NoTargetArtifacts {
+    fn description(&self) -> &str {
+        "Prevents accidental commits of Rust build artifacts (.rlib, .rmeta, .so, .dylib, etc.)."
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        let extensions: Vec<String> = ARTIFACT_EXTENSIONS
+            .iter()
+            .map(|ext| ext.replacen('.', "\\.", 1))
+            .collect();
+        FilePattern::Regex {
+            source: format!("({})$", extensions.join("|")),
+            flags: "i".to_string(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["cargo"]
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        // Allow files inside test/fixture directories
+        if file.path.contains("test/") || file.path.contains("fixture") {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        Ok(HandlerResult::Failure(PolicyErrorResult {
+            error_messages: Some(vec![format!(
+                "Build artifact detected: {}. Rust build artifacts should not be committed to source control.",
+                file.path
+            )]),
+            manual_fix: Some(
+                "Remove the file from source control and ensure /target/ is in .gitignore."
+                    .to_string(),
+            ),
+            ..PolicyErrorResult::default()
+        }))
+    }
+}
@@ -0,0 +1,227 @@
+//! Native port of `src/policies/PackageEsmType.ts`.
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{NativeFile, NativePolicy, package_json};
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Policy settings, as for the TS policy.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    /// The module type every package must declare.
+    required_type: Option<ModuleType>,
+
+    /// Detect the expected type from `exports`, `main`, and `module`.
+    detect_from_exports: Option<bool>,
+
+    /// Package names, or `@scope`s, to skip.
+    exclude_packages: Option<Vec<String>>,
+
+    /// What to do when the type cannot be detected.
+    on_detection_failure: Option<OnDetectionFailure>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ModuleType {
+    Module,
+    CommonJs,
+}
+
+impl ModuleType {
+    fn as_str(self) -> &'static str {
+        match self {
+            ModuleType::Module => "module",
+            ModuleType::CommonJs => "commonjs",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OnDetectionFailure {
+    #[default]
+    Skip,
+    Warn,
+    Fail,
+}
+
+/// Ensures the type field in package.json indicates the module format.
+struct PackageEsmType {
+    /// The TS policy skips every package without a config.
+    enabled: bool,
+    settings: Settings,
+}
+
+pub fn create(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    let settings: Option<Settings> = serde_json::from_value(config.clone())?;
+    Ok(Box::new(PackageEsmType {
+        enabled: settings.is_some(),
+        settings: settings.unwrap_or_default(),
+    }))
+}
+
+/// Detect the module type of a package, with the reason.
+fn detect(json: &Value) -> (Option<ModuleType>, &'static str) {
+    if let Some(exports) = json.get("exports") {
+        let exports = exports.to_string();
+        let has_esm = exports.contains(".mjs") || exports.contains("\"import\"");
+        let has_cjs = exports.contains(".cjs") || exports.contains("\"require\"");
+        match (has_esm, has_cjs) {
+            (true, true) => {
+                return (
+                    Some(ModuleType::Module),
+                    "Package has both ESM and CommonJS exports (dual format). Defaulting to ESM.",
+                );
+            }
+            (true, false) => {
+                return (
+                    Some(ModuleType::Module),
+                    "Detected ESM from exports field (.mjs or import condition)",
+                );
+            }
+            (false, true) => {
+                return (
+                    Some(ModuleType::CommonJs),
+                    "Detected CommonJS from exports field (.cjs or require condition)",
+                );
+            }
+            (false, false) => {}
+        }
+    }
+
+    if let Some(main) = json.get("main").and_then(Value::as_str) {
+        if main.ends_with(".mjs") {
+            return (
+                Some(ModuleType::Module),
+                "Detected ESM from main field (.mjs extension)",
+            );
+        }
+        if main.ends_with(".cjs") {
+            return (
+                Some(ModuleType::CommonJs),
+                "Detected CommonJS from main field (.cjs extension)",
+            );
+        }
+    }
+
+    if json.get("module").is_some() {
+        return (
+            Some(ModuleType::Module),
+            "Detected ESM from module field presence",
+        );
+    }
+
+    (
+        None,
+        "Could not detect module type from exports, main, or module fields",
+    )
+}
+
+impl PackageEsmType {
+    /// Returns true if a package is excluded by name or scope.
+    fn is_excluded(&self, name: &str) -> bool {
+        self.settings
+            .exclude_packages
+            .iter()
+            .flatten()
+            .any(|pattern| {
+                (pattern.starts_with('@') && name.starts_with(&format!("{pattern}/")))
+                    || name == pattern
+            })
+    }
+
+    /// The type a package should have, with the reason if it was detected.
+    fn expected_type(&self, json: &Value) -> (Option<ModuleType>, Option<&'static str>) {
+        if let Some(required) = self.settings.required_type {
+            return (Some(required), None);
+        }
+        if self.settings.detect_from_exports == Some(true) {
+            let (detected, reason) = detect(json);
+            return (detected, Some(reason));
+        }
+        (None, None)
+    }
+}
+
+impl NativePolicy for PackageEsmType {
+    fn description(&self) -> &str {
+        "Ensures the type field in package.json correctly indicates ESM or CommonJS module format."
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: package_json::PATTERN.to_string(),
+            flags: "i".to_string(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        package_json::TAGS
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        if !self.enabled {
+            return Ok(HandlerResult::Pass(true));
+        }
+        let json = package_json::parse(file)?;
+        let Some(name) = json.get("name").and_then(Value::as_str) else {
+            return Ok(HandlerResult::Pass(true));
+        };
+        if name == "root" || self.is_excluded(name) {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        let (expected, reason) = self.expected_type(&json);
+        let Some(expected) = expected else {
+            if self.settings.on_detection_failure == Some(OnDetectionFailure::Fail) {
+                return Ok(HandlerResult::Failure(PolicyErrorResult {
+                    error_messages: Some(vec![format!(
+                        "Package \"{name}\": {}. Set \"type\" field explicitly or use \"requiredType\" config.",
+                        reason.unwrap_or("Could not detect module type")
+                    )]),
+                    fixable: Some(false),
+                    ..PolicyErrorResult::default()
+                }));
+            }
+            return Ok(HandlerResult::Pass(true));
+        };
+
+        let message = match json.get("type") {
+            Some(Value::String(current)) if current == expected.as_str() => {
+                return Ok(HandlerResult::Pass(true));
+            }
+            None => format!(
+                "Package \"{name}\" is missing the \"type\" field. Expected: \"type\": \"{}\"",
+                expected.as_str()
+            ),
+            Some(current) => format!(
+                "Package \"{name}\" has \"type\": \"{}\" but expected \"type\": \"{}\"",
+                current
+                    .as_str()
+                    .map_or_else(|| current.to_string(), str::to_string),
+                expected.as_str()
+            ),
+        };
+        Ok(HandlerResult::Failure(PolicyErrorResult {
+            error_messages: Some(vec![message]),
+            ..PolicyErrorResult::default()
+        }))
+    }
+
+    fn has_resolver(&self) -> bool {
+        true
+    }
+
+    fn resolve(&self, file: &NativeFile) -> Result<Option<Vec<u8>>> {
+        let mut json = package_json::parse(file)?;
+        let Some(expected) = self.expected_type(&json).0 else {
+            return Ok(None);
+        };
+        package_json::set(&mut json, "/type", Value::from(expected.as_str()));
+        Ok(Some(package_json::write(file, &json)?))
+    }
+}
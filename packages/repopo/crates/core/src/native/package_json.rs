@@ -0,0 +1,125 @@
+//! Reading and writing package.json files for native policies.
+//!
+//! Objects keep the order of their keys, so a fix writes every field it
+//! does not change back where it was. Files are written like `jsonfile`
+//! writes them in the TS policies, with the indentation detected as
+//! `src/utils/indentation.ts` detects it.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::ser::{PrettyFormatter, Serializer};
+
+use super::NativeFile;
+
+/// The pattern of package.json files, as `PackageJsonRegexMatch`.
+pub const PATTERN: &str = r"(^|\/)package\.json";
+
+/// The tags of package policies.
+pub const TAGS: &[&str] = &["package"];
+
+/// Parse a file as JSON, skipping a byte order mark as `jsonfile` does.
+pub fn parse(file: &NativeFile) -> Result<Value> {
+    let contents = std::str::from_utf8(file.contents()?)
+        .with_context(|| format!("{} is not UTF-8", file.path))?;
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+    serde_json::from_str(contents).with_context(|| format!("Failed to parse {}", file.path))
+}
+
+/// Serialize JSON with the indentation of a file's current contents, and a
+/// final newline.
+pub fn write(file: &NativeFile, json: &Value) -> Result<Vec<u8>> {
+    let contents = String::from_utf8_lossy(file.contents()?);
+    let indent = detect_indentation(&contents);
+    let mut out = Vec::new();
+    let mut serializer =
+        Serializer::with_formatter(&mut out, PrettyFormatter::with_indent(&indent));
+    json.serialize(&mut serializer)?;
+    out.push(b'\n');
+    Ok(out)
+}
+
+/// The indentation of the first indented line, as a tab or spaces. Like
+/// `JSON.stringify`, no more than 10 spaces are used. Defaults to a tab.
+pub fn detect_indentation(contents: &str) -> Vec<u8> {
+    // JS's `\s` also matches a byte order mark.
+    let is_space = |c: char| c.is_whitespace() || c == '\u{feff}';
+    for line in contents.split('\n') {
+        let indent = line.len() - line.trim_start_matches(is_space).len();
+        if indent == 0 {
+            continue;
+        }
+        if line.starts_with('\t') {
+            return b"\t".to_vec();
+        }
+        let spaces = line[..indent].chars().count().min(10);
+        return vec![b' '; spaces];
+    }
+    b"\t".to_vec()
+}
+
+/// Escape a key for use in a JSON pointer.
+pub fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Set the value at a JSON pointer, creating objects along the way and
+/// replacing anything else in their place.
+pub fn set(json: &mut Value, pointer: &str, value: Value) {
+    let mut target = json;
+    if !pointer.is_empty() {
+        for token in pointer.trim_start_matches('/').split('/') {
+            let key = token.replace("~1", "/").replace("~0", "~");
+            if !target.is_object() {
+                *target = Value::Object(serde_json::Map::new());
+            }
+            let Value::Object(map) = target else {
+                unreachable!();
+            };
+            target = map.entry(key).or_insert(Value::Null);
+        }
+    }
+    *target = value;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NativeFile, detect_indentation, set, write};
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn detects_indentation_like_the_ts_utility() {
+        assert_eq!(detect_indentation("{\n  \"a\": 1\n}"), b"  ");
+        assert_eq!(detect_indentation("{\n\t\"a\": 1\n}"), b"\t");
+        assert_eq!(detect_indentation("{\"a\": 1}"), b"\t");
+        assert_eq!(detect_indentation("{\n\n    \"a\": 1\n}"), b"    ");
+        assert_eq!(
+            detect_indentation(&format!("{{\n{}\"a\": 1\n}}", " ".repeat(12))),
+            b"          "
+        );
+    }
+
+    #[test]
+    fn writes_with_the_detected_indentation_in_key_order() {
+        let root = std::env::temp_dir().join(format!("repopo-package-json-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("package.json"),
+            "{\n  \"name\": \"a\",\n  \"b\": {}\n}",
+        )
+        .unwrap();
+        let file = NativeFile::new(root.to_str().unwrap(), "package.json", None);
+
+        let mut json = super::parse(&file).unwrap();
+        set(&mut json, "/scripts/build~1all", json!("tsc"));
+        set(&mut json, "/b/c", json!([1]));
+        let written = String::from_utf8(write(&file, &json).unwrap()).unwrap();
+        assert_eq!(
+            written,
+            "{\n  \"name\": \"a\",\n  \"b\": {\n    \"c\": [\n      1\n    ]\n  },\n  \"scripts\": {\n    \"build/all\": \"tsc\"\n  }\n}\n"
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,171 @@
+//! Native port of `src/policies/PackageJsonProperties.ts`.
+//!
+//! Each value in `verbatim` is asserted at its JSON pointer, so failures
+//! name the exact field, and a fix sets only the fields that differ. Arrays
+//! are compared whole rather than merged. `required` lists pointers that
+//! must be present with any value; those cannot be fixed.
+
+use anyhow::{Result, bail};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::{NativeFile, NativePolicy, package_json};
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Policy settings, as for the TS policy.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    /// Sets package.json properties to the values provided, used verbatim.
+    verbatim: Option<Map<String, Value>>,
+
+    /// JSON pointers to properties that must be present.
+    required: Option<Vec<String>>,
+}
+
+/// Ensures package.json files contain required properties with correct
+/// values.
+struct PackageJsonProperties {
+    /// The expected value at each pointer. An expected object only asserts
+    /// that there is an object, whose fields follow it in the list.
+    verbatim: Vec<(String, Value)>,
+    required: Vec<String>,
+}
+
+pub fn create(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    let settings: Settings =
+        serde_json::from_value::<Option<Settings>>(config.clone())?.unwrap_or_default();
+    let mut verbatim = Vec::new();
+    flatten("", &settings.verbatim.unwrap_or_default(), &mut verbatim);
+
+    // A pointer without a leading `/` never matches, so every package would
+    // fail for a field it has.
+    let required = settings.required.unwrap_or_default();
+    if let Some(pointer) = required
+        .iter()
+        .find(|pointer| !pointer.is_empty() && !pointer.starts_with('/'))
+    {
+        bail!("Invalid required field '{pointer}': expected a JSON pointer such as '/{pointer}'");
+    }
+
+    Ok(Box::new(PackageJsonProperties { verbatim, required }))
+}
+
+/// List the values of an object by JSON pointer, parents first. Nulls are
+/// skipped, as `defu` skips them.
+fn flatten(prefix: &str, object: &Map<String, Value>, out: &mut Vec<(String, Value)>) {
+    for (key, value) in object {
+        let pointer = format!("{prefix}/{}", package_json::escape(key));
+        match value {
+            Value::Null => {}
+            Value::Object(fields) => {
+                out.push((pointer.clone(), Value::Object(Map::new())));
+                flatten(&pointer, fields, out);
+            }
+            _ => out.push((pointer, value.clone())),
+        }
+    }
+}
+
+impl PackageJsonProperties {
+    /// The pointers of the verbatim values that a package.json does not
+    /// have, with the values.
+    fn incorrect<'a>(&'a self, json: &Value) -> Vec<&'a (String, Value)> {
+        self.verbatim
+            .iter()
+            .filter(
+                |(pointer, expected)| match (json.pointer(pointer), expected) {
+                    (Some(actual), Value::Object(_)) => !actual.is_object(),
+                    (Some(actual), _) => actual != expected,
+                    (None, _) => true,
+                },
+            )
+            .collect()
+    }
+}
+
+impl NativePolicy for PackageJsonProperties {
+    fn description(&self) -> &str {
+        "Ensures package.json files contain required properties with correct values."
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: package_json::PATTERN.to_string(),
+            flags: "i".to_string(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        package_json::TAGS
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        let json = package_json::parse(file)?;
+        let incorrect = self.incorrect(&json);
+        // Fields of a missing object are reported with the object.
+        let mut messages: Vec<String> = incorrect
+            .iter()
+            .filter(|(pointer, _)| {
+                !incorrect.iter().any(|(parent, _)| {
+                    pointer
+                        .strip_prefix(parent.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+                })
+            })
+            .map(|(pointer, _)| format!("Incorrect package.json field value for '{pointer}'."))
+            .collect();
+        messages.extend(
+            self.required
+                .iter()
+                .filter(|pointer| json.pointer(pointer).is_none())
+                .map(|pointer| format!("Missing required package.json field '{pointer}'.")),
+        );
+        if messages.is_empty() {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        Ok(HandlerResult::Failure(PolicyErrorResult {
+            error_messages: Some(messages),
+            fixable: Some(!incorrect.is_empty()),
+            ..PolicyErrorResult::default()
+        }))
+    }
+
+    fn has_resolver(&self) -> bool {
+        true
+    }
+
+    fn resolve(&self, file: &NativeFile) -> Result<Option<Vec<u8>>> {
+        let mut json = package_json::parse(file)?;
+        let incorrect: Vec<(String, Value)> = self.incorrect(&json).into_iter().cloned().collect();
+        if incorrect.is_empty() {
+            return Ok(None);
+        }
+        // Parents come before their fields, so an object set in place of
+        // another value gets the expected fields after it.
+        for (pointer, expected) in incorrect {
+            package_json::set(&mut json, &pointer, expected);
+        }
+        Ok(Some(package_json::write(file, &json)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::create;
+    use serde_json::json;
+
+    #[test]
+    fn rejects_required_fields_that_are_not_pointers() {
+        assert!(create(&json!({ "required": ["", "/name", "/repository/url"] })).is_ok());
+
+        let err = create(&json!({ "required": ["/name", "license"] }))
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Invalid required field 'license': expected a JSON pointer such as '/license'"
+        );
+    }
+}
@@ -0,0 +1,450 @@
+//! Native port of `src/policies/PackageJsonSorted.ts`.
+//!
+//! Fields are sorted in the order of `sort-package-json` 2.x: known fields
+//! first in its order, then other fields alphabetically, then fields that
+//! start with `_`. The values it sorts by simple rules are sorted too:
+//! dependencies, scripts with their `pre` and `post` hooks, objects with a
+//! conventional key order such as `repository`, and lists such as `files`
+//! that it deduplicates. Values it sorts by tool-specific rules, such as
+//! `exports` and `eslintConfig`, keep their order.
+
+use anyhow::Result;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::sync::LazyLock;
+
+use super::{NativeFile, NativePolicy, package_json};
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// How a field's value is sorted.
+#[derive(Clone, Copy)]
+enum Rule {
+    /// Left as it is.
+    Keep,
+
+    /// Keys sorted alphabetically.
+    Keys,
+
+    /// The listed keys first, then the others alphabetically.
+    KeysBy(&'static [&'static str]),
+
+    /// Keys sorted alphabetically at every level.
+    KeysDeep,
+
+    /// An array of people, each sorted as [`PERSON`].
+    People,
+
+    /// A person, sorted as [`PERSON`].
+    Person,
+
+    /// Scripts, each next to its `pre` and `post` hooks.
+    Scripts,
+
+    /// An array without duplicates.
+    Unique,
+
+    /// An array without duplicates, sorted.
+    UniqueSorted,
+}
+
+/// The key order of a person.
+const PERSON: &[&str] = &["name", "email", "url"];
+
+/// The fields of a package.json in order, with how their values are sorted.
+const FIELDS: &[(&str, Rule)] = &[
+    ("$schema", Rule::Keep),
+    ("name", Rule::Keep),
+    ("displayName", Rule::Keep),
+    ("version", Rule::Keep),
+    ("stableVersion", Rule::Keep),
+    ("private", Rule::Keep),
+    ("description", Rule::Keep),
+    ("categories", Rule::Unique),
+    ("keywords", Rule::Unique),
+    ("homepage", Rule::Keep),
+    ("bugs", Rule::KeysBy(&["url", "email"])),
+    ("repository", Rule::KeysBy(&["type", "url"])),
+    ("funding", Rule::KeysBy(&["type", "url"])),
+    ("license", Rule::KeysBy(&["type", "url"])),
+    ("qna", Rule::Keep),
+    ("author", Rule::Person),
+    ("maintainers", Rule::People),
+    ("contributors", Rule::People),
+    ("publisher", Rule::Keep),
+    ("sideEffects", Rule::Keep),
+    ("type", Rule::Keep),
+    ("imports", Rule::Keep),
+    ("exports", Rule::Keep),
+    ("main", Rule::Keep),
+    ("svelte", Rule::Keep),
+    ("umd:main", Rule::Keep),
+    ("jsdelivr", Rule::Keep),
+    ("unpkg", Rule::Keep),
+    ("module", Rule::Keep),
+    ("source", Rule::Keep),
+    ("jsnext:main", Rule::Keep),
+    ("browser", Rule::Keep),
+    ("react-native", Rule::Keep),
+    ("types", Rule::Keep),
+    ("typesVersions", Rule::Keep),
+    ("typings", Rule::Keep),
+    ("style", Rule::Keep),
+    ("example", Rule::Keep),
+    ("examplestyle", Rule::Keep),
+    ("assets", Rule::Keep),
+    ("bin", Rule::Keys),
+    ("man", Rule::Keep),
+    (
+        "directories",
+        Rule::KeysBy(&["lib", "bin", "man", "doc", "example", "test"]),
+    ),
+    ("files", Rule::Unique),
+    ("workspaces", Rule::Keep),
+    (
+        "binary",
+        Rule::KeysBy(&[
+            "module_name",
+            "module_path",
+            "remote_path",
+            "package_name",
+            "host",
+        ]),
+    ),
+    ("scripts", Rule::Scripts),
+    ("betterScripts", Rule::Scripts),
+    ("contributes", Rule::Keys),
+    ("activationEvents", Rule::Unique),
+    ("husky", Rule::Keep),
+    ("simple-git-hooks", Rule::Keep),
+    ("pre-commit", Rule::Keep),
+    ("commitlint", Rule::Keys),
+    ("lint-staged", Rule::Keep),
+    ("nano-staged", Rule::Keep),
+    ("config", Rule::Keys),
+    ("nodemonConfig", Rule::Keys),
+    ("browserify", Rule::Keys),
+    ("babel", Rule::Keys),
+    ("browserslist", Rule::Keep),
+    ("xo", Rule::Keys),
+    ("prettier", Rule::Keep),
+    ("eslintConfig", Rule::Keep),
+    ("eslintIgnore", Rule::Keep),
+    ("npmpkgjsonlint", Rule::Keys),
+    ("npmPackageJsonLintConfig", Rule::Keys),
+    ("npmpackagejsonlint", Rule::Keys),
+    ("release", Rule::Keys),
+    ("remarkConfig", Rule::Keys),
+    ("stylelint", Rule::Keep),
+    ("ava", Rule::Keys),
+    ("jest", Rule::Keys),
+    ("jest-junit", Rule::Keys),
+    ("jest-stare", Rule::Keys),
+    ("mocha", Rule::Keys),
+    ("nyc", Rule::Keys),
+    ("c8", Rule::Keys),
+    ("tap", Rule::Keys),
+    ("oclif", Rule::KeysDeep),
+    ("resolutions", Rule::Keys),
+    ("dependencies", Rule::Keys),
+    ("devDependencies", Rule::Keys),
+    ("dependenciesMeta", Rule::KeysDeep),
+    ("peerDependencies", Rule::Keys),
+    ("peerDependenciesMeta", Rule::KeysDeep),
+    ("optionalDependencies", Rule::Keys),
+    ("bundledDependencies", Rule::UniqueSorted),
+    ("bundleDependencies", Rule::UniqueSorted),
+    ("extensionPack", Rule::UniqueSorted),
+    ("extensionDependencies", Rule::UniqueSorted),
+    ("flat", Rule::Keep),
+    ("packageManager", Rule::Keep),
+    ("engines", Rule::Keys),
+    ("engineStrict", Rule::Keys),
+    ("volta", Rule::KeysBy(&["node", "npm", "yarn"])),
+    ("languageName", Rule::Keep),
+    ("os", Rule::Keep),
+    ("cpu", Rule::Keep),
+    ("preferGlobal", Rule::Keys),
+    ("publishConfig", Rule::Keys),
+    ("icon", Rule::Keep),
+    ("badges", Rule::Keep),
+    ("galleryBanner", Rule::Keys),
+    ("preview", Rule::Keep),
+    ("markdown", Rule::Keep),
+    ("pnpm", Rule::Keep),
+];
+
+/// Scripts npm runs with `pre` and `post` hooks even if the package does
+/// not define them.
+const NPM_SCRIPTS: &[&str] = &[
+    "install",
+    "pack",
+    "prepare",
+    "publish",
+    "restart",
+    "shrinkwrap",
+    "start",
+    "stop",
+    "test",
+    "uninstall",
+    "version",
+];
+
+/// A command that runs scripts in sequence with `npm-run-all`.
+static SEQUENTIAL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[\s&;<>|(])(?:run-s|npm-run-all2? .*(?:--sequential|--serial|-s))").unwrap()
+});
+
+/// Ensures package.json files are sorted consistently.
+struct PackageJsonSorted;
+
+pub fn create(_config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    Ok(Box::new(PackageJsonSorted))
+}
+
+impl NativePolicy for PackageJsonSorted {
+    fn description(&self) -> &str {
+        "Ensures package.json files are sorted consistently using sort-package-json."
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: package_json::PATTERN.to_string(),
+            flags: "i".to_string(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        package_json::TAGS
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        let json = package_json::parse(file)?;
+        // Maps compare equal regardless of key order, so compare the JSON.
+        if serde_json::to_string(&sort(&json))? == serde_json::to_string(&json)? {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        Ok(HandlerResult::Failure(PolicyErrorResult {
+            error_messages: Some(Vec::new()),
+            ..PolicyErrorResult::default()
+        }))
+    }
+
+    fn has_resolver(&self) -> bool {
+        true
+    }
+
+    fn resolve(&self, file: &NativeFile) -> Result<Option<Vec<u8>>> {
+        let json = package_json::parse(file)?;
+        Ok(Some(package_json::write(file, &sort(&json))?))
+    }
+}
+
+/// Compare keys as JS sorts strings, by UTF-16 code units.
+fn compare(a: &str, b: &str) -> Ordering {
+    a.encode_utf16().cmp(b.encode_utf16())
+}
+
+/// Sort a package.json. Anything but an object is left as it is.
+fn sort(json: &Value) -> Value {
+    let Value::Object(fields) = json else {
+        return json.clone();
+    };
+
+    let mut others: Vec<&String> = fields
+        .keys()
+        .filter(|key| !FIELDS.iter().any(|(field, _)| field == key))
+        .collect();
+    others.sort_by(|a, b| {
+        a.starts_with('_')
+            .cmp(&b.starts_with('_'))
+            .then(compare(a, b))
+    });
+
+    let mut sorted = Map::new();
+    for (field, rule) in FIELDS {
+        if let Some(value) = fields.get(*field) {
+            let value = match rule {
+                Rule::Scripts => sort_scripts(value, json),
+                _ => apply(*rule, value),
+            };
+            sorted.insert(field.to_string(), value);
+        }
+    }
+    for key in others {
+        sorted.insert(key.clone(), fields[key].clone());
+    }
+    Value::Object(sorted)
+}
+
+/// Sort a value by a rule. Values of the wrong type are left as they are.
+fn apply(rule: Rule, value: &Value) -> Value {
+    match (rule, value) {
+        (Rule::Keys, Value::Object(object)) => sort_keys(object, &[], false),
+        (Rule::KeysBy(order), Value::Object(object)) => sort_keys(object, order, false),
+        (Rule::KeysDeep, Value::Object(object)) => sort_keys(object, &[], true),
+        (Rule::Person, Value::Object(object)) => sort_keys(object, PERSON, false),
+        (Rule::People, Value::Array(people)) => Value::Array(
+            people
+                .iter()
+                .map(|person| apply(Rule::Person, person))
+                .collect(),
+        ),
+        (Rule::Unique | Rule::UniqueSorted, Value::Array(items)) => {
+            let mut unique: Vec<Value> = Vec::new();
+            for item in items {
+                if !unique.contains(item) {
+                    unique.push(item.clone());
+                }
+            }
+            if matches!(rule, Rule::UniqueSorted) {
+                unique.sort_by(|a, b| compare(&js_string(a), &js_string(b)));
+            }
+            Value::Array(unique)
+        }
+        _ => value.clone(),
+    }
+}
+
+/// A value as `Array.prototype.sort` compares it.
+fn js_string(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// Sort the keys of an object: the keys in `order` first, then the others
+/// alphabetically. If `deep` is set, nested objects are sorted too.
+fn sort_keys(object: &Map<String, Value>, order: &[&str], deep: bool) -> Value {
+    let mut keys: Vec<&String> = object.keys().collect();
+    keys.sort_by(|a, b| {
+        let rank = |key: &str| order.iter().position(|known| *known == key);
+        match (rank(a), rank(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => compare(a, b),
+        }
+    });
+    Value::Object(
+        keys.into_iter()
+            .map(|key| {
+                let value = match &object[key] {
+                    Value::Object(nested) if deep => sort_keys(nested, &[], true),
+                    value => value.clone(),
+                };
+                (key.clone(), value)
+            })
+            .collect(),
+    )
+}
+
+/// Sort scripts alphabetically, keeping each next to its `pre` and `post`
+/// hooks. Scripts keep their order if a script runs others in sequence by
+/// a wildcard with `npm-run-all`, where the order matters.
+fn sort_scripts(value: &Value, json: &Value) -> Value {
+    let Value::Object(scripts) = value else {
+        return value.clone();
+    };
+
+    let names: Vec<&str> = scripts.keys().map(String::as_str).collect();
+    let mut hooked: Vec<&str> = Vec::new();
+    let mut keys: Vec<&str> = names
+        .iter()
+        .map(|name| {
+            let base = name
+                .strip_prefix("pre")
+                .or_else(|| name.strip_prefix("post"))
+                .unwrap_or(name);
+            if NPM_SCRIPTS.contains(&base) || names.contains(&base) {
+                hooked.push(base);
+                base
+            } else {
+                name
+            }
+        })
+        .collect();
+    if !has_sequential_script(json) {
+        keys.sort_by(|a, b| compare(a, b));
+    }
+
+    let mut order: Vec<String> = Vec::new();
+    for key in keys {
+        if hooked.contains(&key) {
+            order.extend([format!("pre{key}"), key.to_string(), format!("post{key}")]);
+        } else {
+            order.push(key.to_string());
+        }
+    }
+    let mut sorted = Map::new();
+    for key in order {
+        if let Some(script) = scripts.get(&key) {
+            sorted.entry(key).or_insert_with(|| script.clone());
+        }
+    }
+    Value::Object(sorted)
+}
+
+/// Returns true if the package uses `npm-run-all` and a script runs others
+/// in sequence by a wildcard.
+fn has_sequential_script(json: &Value) -> bool {
+    let uses_npm_run_all = ["npm-run-all", "npm-run-all2"]
+        .iter()
+        .any(|name| json.pointer(&format!("/devDependencies/{name}")).is_some());
+    if !uses_npm_run_all {
+        return false;
+    }
+    ["scripts", "betterScripts"]
+        .iter()
+        .filter_map(|field| json.get(field).and_then(Value::as_object))
+        .flat_map(|scripts| scripts.values())
+        .filter_map(Value::as_str)
+        .any(|command| command.contains('*') && SEQUENTIAL.is_match(command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sort;
+    use serde_json::json;
+
+    #[test]
+    fn sorts_like_sort_package_json() {
+        let input = json!({
+            "_private": true,
+            "scripts": {
+                "test": "vitest",
+                "build": "tsc",
+                "postbuild": "echo done",
+                "prebuild": "rimraf dist",
+                "pretest": "lint",
+            },
+            "zed": 1,
+            "devDependencies": { "b": "1", "a": "1" },
+            "keywords": ["a", "b", "a"],
+            "repository": { "url": "x", "directory": "d", "type": "git" },
+            "version": "1.0.0",
+            "name": "a",
+        });
+        let expected = json!({
+            "name": "a",
+            "version": "1.0.0",
+            "keywords": ["a", "b"],
+            "repository": { "type": "git", "url": "x", "directory": "d" },
+            "scripts": {
+                "prebuild": "rimraf dist",
+                "build": "tsc",
+                "postbuild": "echo done",
+                "pretest": "lint",
+                "test": "vitest",
+            },
+            "devDependencies": { "a": "1", "b": "1" },
+            "zed": 1,
+            "_private": true,
+        });
+        let sorted = sort(&input);
+        assert_eq!(sorted.to_string(), expected.to_string());
+        assert_eq!(sort(&sorted).to_string(), sorted.to_string());
+    }
+}
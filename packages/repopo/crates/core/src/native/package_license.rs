@@ -0,0 +1,209 @@
+//! Native port of `src/policies/PackageLicense.ts`.
+//!
+//! The native policy can also validate the `license` field with the
+//! embedded SPDX list in [`super::spdx`], which the TS policy cannot.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{NativeFile, NativePolicy, package_json, spdx};
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Policy settings, as for the TS policy.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    /// Skip packages with `private: true`. Defaults to true.
+    skip_private: Option<bool>,
+
+    /// The name of the license file. Defaults to `LICENSE`.
+    license_file_name: Option<String>,
+
+    /// Validate the `license` field against the SPDX licence list. Defaults
+    /// to false. Native only.
+    validate_spdx: Option<bool>,
+
+    /// Allowed SPDX licence identifiers for the `license` field. If set,
+    /// only these are accepted. Native only.
+    allowed_licences: Option<Vec<String>>,
+}
+
+const MANUAL_FIX: &str = "Use a valid SPDX licence identifier (e.g., MIT, Apache-2.0, MPL-2.0).";
+
+/// Ensures each package has a license file that matches the root's.
+struct PackageLicense {
+    skip_private: bool,
+    license_file_name: String,
+    validate_spdx: bool,
+    allowed_licences: Option<Vec<String>>,
+}
+
+pub fn create(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    let settings: Settings =
+        serde_json::from_value::<Option<Settings>>(config.clone())?.unwrap_or_default();
+    Ok(Box::new(PackageLicense {
+        skip_private: settings.skip_private.unwrap_or(true),
+        license_file_name: settings
+            .license_file_name
+            .unwrap_or_else(|| "LICENSE".to_string()),
+        validate_spdx: settings.validate_spdx.unwrap_or(false),
+        allowed_licences: settings.allowed_licences,
+    }))
+}
+
+/// How a package's license file differs from the root's.
+enum Problem {
+    NoRootLicense,
+    Missing,
+    Different,
+}
+
+impl PackageLicense {
+    /// The paths of the root license file and the package's.
+    fn paths(&self, file: &NativeFile) -> (PathBuf, PathBuf) {
+        let root = Path::new(file.root);
+        let package_dir = Path::new(file.path).parent().unwrap_or(Path::new(""));
+        (
+            root.join(&self.license_file_name),
+            root.join(package_dir).join(&self.license_file_name),
+        )
+    }
+
+    fn is_skipped(&self, json: &Value) -> bool {
+        self.skip_private && json.get("private") == Some(&Value::Bool(true))
+    }
+
+    /// The problem with a package's license file, if it has one.
+    fn problem(&self, file: &NativeFile) -> Result<Option<Problem>> {
+        let (root_license, package_license) = self.paths(file);
+        if !root_license.exists() {
+            return Ok(Some(Problem::NoRootLicense));
+        }
+        if !package_license.exists() {
+            return Ok(Some(Problem::Missing));
+        }
+        let read = |path: &Path| {
+            fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+        };
+        if read(&package_license)? != read(&root_license)? {
+            return Ok(Some(Problem::Different));
+        }
+        Ok(None)
+    }
+
+    /// The problems with the package's `license` field, if it is checked.
+    fn invalid_license(&self, json: &Value) -> Option<spdx::Invalid> {
+        if !self.validate_spdx && self.allowed_licences.is_none() {
+            return None;
+        }
+        let license = json.get("license").and_then(Value::as_str)?;
+        spdx::validate(
+            &[license],
+            self.allowed_licences.as_deref(),
+            self.validate_spdx,
+        )
+    }
+}
+
+impl NativePolicy for PackageLicense {
+    fn description(&self) -> &str {
+        "Ensures each package has a LICENSE file that matches the root repository LICENSE."
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: package_json::PATTERN.to_string(),
+            flags: "i".to_string(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        package_json::TAGS
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        let json = package_json::parse(file)?;
+        if self.is_skipped(&json) {
+            return Ok(HandlerResult::Pass(true));
+        }
+        let problem = self.problem(file)?;
+        let invalid = self.invalid_license(&json);
+        if problem.is_none() && invalid.is_none() {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        let name = json
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        let license = &self.license_file_name;
+        let mut messages = Vec::new();
+        let mut fixable = false;
+        match problem {
+            Some(Problem::NoRootLicense) => messages.push(format!(
+                "Cannot validate package LICENSE: root {license} file not found"
+            )),
+            Some(Problem::Missing) => {
+                messages.push(format!("{license} file missing for package \"{name}\""));
+                fixable = true;
+            }
+            Some(Problem::Different) => {
+                messages.push(format!(
+                    "{license} file in package \"{name}\" doesn't match root {license}"
+                ));
+                fixable = true;
+            }
+            None => {}
+        }
+        let mut manual_fix = None;
+        if let Some(invalid) = invalid {
+            messages.extend(invalid.errors);
+            fixable |= !invalid.corrections.is_empty();
+            manual_fix = Some(MANUAL_FIX.to_string());
+        }
+        Ok(HandlerResult::Failure(PolicyErrorResult {
+            error_messages: Some(messages),
+            manual_fix,
+            fixable: Some(fixable),
+            ..PolicyErrorResult::default()
+        }))
+    }
+
+    fn has_resolver(&self) -> bool {
+        true
+    }
+
+    fn cacheable(&self) -> bool {
+        false
+    }
+
+    /// Copies the root license file into the package, and corrects the
+    /// `license` field if it can.
+    fn resolve(&self, file: &NativeFile) -> Result<Option<Vec<u8>>> {
+        let mut json = package_json::parse(file)?;
+        if self.is_skipped(&json) {
+            return Ok(None);
+        }
+        let mut fixed = false;
+        if let Some(Problem::Missing | Problem::Different) = self.problem(file)? {
+            let (root_license, package_license) = self.paths(file);
+            fs::copy(&root_license, &package_license)
+                .with_context(|| format!("Failed to copy {}", root_license.display()))?;
+            fixed = true;
+        }
+        let correction = self
+            .invalid_license(&json)
+            .and_then(|invalid| invalid.corrections.into_iter().next());
+        match correction {
+            Some((_, corrected)) => {
+                package_json::set(&mut json, "/license", Value::from(corrected));
+                Ok(Some(package_json::write(file, &json)?))
+            }
+            None if fixed => Ok(Some(file.contents()?.to_vec())),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Native port of `src/policies/PackagePrivateField.ts`.
+//!
+//! Package name patterns are globs with the syntax of file patterns, where
+//! `*` does not match `/`, as with picomatch.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{NativeFile, NativePolicy, package_json};
+use crate::pattern::Matcher;
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Policy settings, as for the TS policy.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    /// Packages that must not have `private: true`.
+    must_publish: Option<Vec<String>>,
+
+    /// Packages that must have `private: true`.
+    must_be_private: Option<Vec<String>>,
+
+    /// Packages that may be either private or public.
+    may_publish: Option<Vec<String>>,
+
+    /// What packages that match no list must be. Defaults to private.
+    unmatched_packages: Option<Unmatched>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Unmatched {
+    #[default]
+    Private,
+    Public,
+    Ignore,
+}
+
+/// Enforces the private field in package.json based on package name.
+struct PackagePrivateField {
+    /// The TS policy skips every package without a config.
+    enabled: bool,
+    must_publish: Vec<Matcher>,
+    must_be_private: Vec<Matcher>,
+    may_publish: Vec<Matcher>,
+    unmatched: Unmatched,
+}
+
+pub fn create(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    let settings: Option<Settings> = serde_json::from_value(config.clone())?;
+    let enabled = settings.is_some();
+    let settings = settings.unwrap_or_default();
+    Ok(Box::new(PackagePrivateField {
+        enabled,
+        must_publish: compile(settings.must_publish)?,
+        must_be_private: compile(settings.must_be_private)?,
+        may_publish: compile(settings.may_publish)?,
+        unmatched: settings.unmatched_packages.unwrap_or_default(),
+    }))
+}
+
+/// Compile package name globs.
+fn compile(globs: Option<Vec<String>>) -> Result<Vec<Matcher>> {
+    globs
+        .unwrap_or_default()
+        .into_iter()
+        .map(|glob| {
+            Matcher::new(&FilePattern::Glob {
+                glob: glob.clone(),
+                ignore_case: false,
+            })
+            .with_context(|| format!("Invalid package pattern: {glob}"))
+        })
+        .collect()
+}
+
+fn matches_any(name: &str, matchers: &[Matcher]) -> bool {
+    matchers.iter().any(|matcher| matcher.is_match(name))
+}
+
+impl PackagePrivateField {
+    /// Whether a package must be private, or `None` if it may be either.
+    fn required_private(&self, name: &str) -> Option<bool> {
+        if matches_any(name, &self.must_publish) {
+            return Some(false);
+        }
+        if matches_any(name, &self.must_be_private) {
+            return Some(true);
+        }
+        if matches_any(name, &self.may_publish) {
+            return None;
+        }
+        match self.unmatched {
+            Unmatched::Private => Some(true),
+            Unmatched::Public => Some(false),
+            Unmatched::Ignore => None,
+        }
+    }
+
+    /// Whether a package must be private, if it is in the wrong state.
+    fn mismatch(&self, json: &Value) -> Option<(String, bool)> {
+        if !self.enabled {
+            return None;
+        }
+        let name = json.get("name").and_then(Value::as_str)?;
+        if name == "root" {
+            return None;
+        }
+        let required = self.required_private(name)?;
+        let private = json.get("private") == Some(&Value::Bool(true));
+        (required != private).then(|| (name.to_string(), required))
+    }
+}
+
+impl NativePolicy for PackagePrivateField {
+    fn description(&self) -> &str {
+        "Enforces the private field in package.json based on package scope or name patterns."
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: package_json::PATTERN.to_string(),
+            flags: "i".to_string(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        package_json::TAGS
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        let json = package_json::parse(file)?;
+        let Some((name, required)) = self.mismatch(&json) else {
+            return Ok(HandlerResult::Pass(true));
+        };
+
+        let message = if required {
+            format!(
+                "Package \"{name}\" must be marked private. Add \"private\": true to package.json."
+            )
+        } else {
+            format!(
+                "Package \"{name}\" must not be marked private. Remove \"private\": true from package.json to allow publishing."
+            )
+        };
+        Ok(HandlerResult::Failure(PolicyErrorResult {
+            error_messages: Some(vec![message]),
+            ..PolicyErrorResult::default()
+        }))
+    }
+
+    fn has_resolver(&self) -> bool {
+        true
+    }
+
+    fn resolve(&self, file: &NativeFile) -> Result<Option<Vec<u8>>> {
+        let mut json = package_json::parse(file)?;
+        let Some((_, required)) = self.mismatch(&json) else {
+            return Ok(None);
+        };
+        if required {
+            package_json::set(&mut json, "/private", Value::Bool(true));
+        } else if let Value::Object(fields) = &mut json {
+            fields.shift_remove("private");
+        }
+        Ok(Some(package_json::write(file, &json)?))
+    }
+}
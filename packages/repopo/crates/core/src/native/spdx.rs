@@ -0,0 +1,813 @@
+//! SPDX license expressions, for the native licence policies.
+//!
+//! Expressions are parsed as `spdx-expression-parse` parses them, against
+//! the license and exception lists in `spdx/`. Invalid licences are
+//! corrected as `spdx-correct` corrects them for the TS policies, which
+//! `test/data/spdx-conformance.json` checks. A licence that cannot be
+//! corrected gets the closest valid identifier as a suggestion.
+
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use crate::types::{HandlerResult, PolicyErrorResult};
+
+/// Compile a regex on first use.
+macro_rules! regex {
+    ($pattern:literal) => {{
+        static REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new($pattern).unwrap());
+        &*REGEX
+    }};
+}
+
+const LICENSE_LIST: &str = include_str!("spdx/licenses.txt");
+const DEPRECATED_LIST: &str = include_str!("spdx/deprecated.txt");
+const EXCEPTION_LIST: &str = include_str!("spdx/exceptions.txt");
+
+/// The identifiers in a list, skipping comments.
+fn ids(list: &'static str) -> impl Iterator<Item = &'static str> {
+    list.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// License identifiers, including deprecated ones.
+static LICENSES: LazyLock<HashSet<&str>> =
+    LazyLock::new(|| ids(LICENSE_LIST).chain(ids(DEPRECATED_LIST)).collect());
+
+/// License exception identifiers.
+static EXCEPTIONS: LazyLock<HashSet<&str>> = LazyLock::new(|| ids(EXCEPTION_LIST).collect());
+
+/// A parsed license expression.
+#[derive(Debug, PartialEq)]
+pub enum Expression {
+    /// A listed license, or any later version with `+`, optionally `WITH`
+    /// an exception.
+    License {
+        id: String,
+        plus: bool,
+        exception: Option<String>,
+    },
+
+    /// A `LicenseRef-`, optionally prefixed by a `DocumentRef-`.
+    LicenseRef(String),
+
+    And(Box<Expression>, Box<Expression>),
+
+    Or(Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    /// The licenses and license references in the expression.
+    pub fn licenses(&self) -> Vec<&str> {
+        match self {
+            Expression::License { id, .. } => vec![id],
+            Expression::LicenseRef(reference) => vec![reference],
+            Expression::And(left, right) | Expression::Or(left, right) => {
+                [left.licenses(), right.licenses()].concat()
+            }
+        }
+    }
+}
+
+/// Why an expression is invalid.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// An identifier that is not a listed license.
+    UnknownLicense(String),
+
+    /// An identifier after `WITH` that is not a listed exception.
+    UnknownException(String),
+
+    /// An expression that is not well formed.
+    Syntax,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    Operator(&'static str),
+    DocumentRef(&'a str),
+    LicenseRef(&'a str),
+    License(&'a str),
+    Exception(&'a str),
+}
+
+/// The length of the identifier at the start of a string.
+fn idstring(source: &str) -> usize {
+    source
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '.')
+        .unwrap_or(source.len())
+}
+
+/// Split an expression into tokens. Operators and identifiers are
+/// case-sensitive, as in `spdx-expression-parse` 3, which `spdx-correct`
+/// uses.
+fn scan<'a>(source: &'a str) -> Result<Vec<Token<'a>>, Error> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    loop {
+        rest = rest.trim_start_matches(' ');
+        if rest.is_empty() {
+            return Ok(tokens);
+        }
+
+        let operator = ["WITH", "AND", "OR", "(", ")", ":", "+"]
+            .into_iter()
+            .find(|op| rest.starts_with(op));
+        if let Some(operator) = operator {
+            if operator == "+" && source[..source.len() - rest.len()].ends_with(' ') {
+                return Err(Error::Syntax);
+            }
+            tokens.push(Token::Operator(operator));
+            rest = &rest[operator.len()..];
+            continue;
+        }
+
+        let prefixed = [
+            (
+                "DocumentRef-",
+                Token::DocumentRef as fn(&'a str) -> Token<'a>,
+            ),
+            ("LicenseRef-", Token::LicenseRef),
+        ]
+        .into_iter()
+        .find_map(|(prefix, token)| Some((rest.strip_prefix(prefix)?, token)));
+        if let Some((after, token)) = prefixed {
+            let len = idstring(after);
+            if len == 0 {
+                return Err(Error::Syntax);
+            }
+            tokens.push(token(&after[..len]));
+            rest = &after[len..];
+            continue;
+        }
+
+        let len = idstring(rest);
+        let id = &rest[..len];
+        if LICENSES.contains(id) {
+            tokens.push(Token::License(id));
+        } else if EXCEPTIONS.contains(id) {
+            tokens.push(Token::Exception(id));
+        } else if len == 0 {
+            return Err(Error::Syntax);
+        } else if tokens.last() == Some(&Token::Operator("WITH")) {
+            return Err(Error::UnknownException(id.to_string()));
+        } else {
+            return Err(Error::UnknownLicense(id.to_string()));
+        }
+        rest = &rest[len..];
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    /// Consume an operator if it is next.
+    fn operator(&mut self, operator: &'static str) -> bool {
+        let found = self.peek() == Some(Token::Operator(operator));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// An expression of `OR`s. `AND` binds more tightly.
+    fn expression(&mut self) -> Result<Option<Expression>, Error> {
+        self.binary("OR", Self::and)
+    }
+
+    fn and(&mut self) -> Result<Option<Expression>, Error> {
+        self.binary("AND", Self::atom)
+    }
+
+    fn binary(
+        &mut self,
+        operator: &'static str,
+        operand: fn(&mut Self) -> Result<Option<Expression>, Error>,
+    ) -> Result<Option<Expression>, Error> {
+        let Some(left) = operand(self)? else {
+            return Ok(None);
+        };
+        if !self.operator(operator) {
+            return Ok(Some(left));
+        }
+        let right = self.binary(operator, operand)?.ok_or(Error::Syntax)?;
+        let (left, right) = (Box::new(left), Box::new(right));
+        Ok(Some(if operator == "AND" {
+            Expression::And(left, right)
+        } else {
+            Expression::Or(left, right)
+        }))
+    }
+
+    fn atom(&mut self) -> Result<Option<Expression>, Error> {
+        if self.operator("(") {
+            let expression = self.expression()?.ok_or(Error::Syntax)?;
+            if !self.operator(")") {
+                return Err(Error::Syntax);
+            }
+            return Ok(Some(expression));
+        }
+
+        let mut reference = String::new();
+        if let Some(Token::DocumentRef(document)) = self.peek() {
+            self.pos += 1;
+            if !self.operator(":") {
+                return Err(Error::Syntax);
+            }
+            reference = format!("DocumentRef-{document}:");
+        }
+        match self.peek() {
+            Some(Token::LicenseRef(license)) => {
+                self.pos += 1;
+                Ok(Some(Expression::LicenseRef(format!(
+                    "{reference}LicenseRef-{license}"
+                ))))
+            }
+            _ if !reference.is_empty() => Err(Error::Syntax),
+            Some(Token::License(id)) => {
+                self.pos += 1;
+                let plus = self.operator("+");
+                let exception = if self.operator("WITH") {
+                    let Some(Token::Exception(exception)) = self.peek() else {
+                        return Err(Error::Syntax);
+                    };
+                    self.pos += 1;
+                    Some(exception.to_string())
+                } else {
+                    None
+                };
+                Ok(Some(Expression::License {
+                    id: id.to_string(),
+                    plus,
+                    exception,
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Parse a license expression such as `MIT OR (Apache-2.0 WITH
+/// LLVM-exception)`.
+pub fn parse(source: &str) -> Result<Expression, Error> {
+    let mut parser = Parser {
+        tokens: scan(source)?,
+        pos: 0,
+    };
+    match parser.expression()? {
+        Some(expression) if parser.pos == parser.tokens.len() => Ok(expression),
+        _ => Err(Error::Syntax),
+    }
+}
+
+fn valid(source: &str) -> bool {
+    parse(source).is_ok()
+}
+
+/// Correct a licence as `spdx-correct` does. Returns the licence itself if
+/// it is valid and current, or `None` if it cannot be corrected.
+pub fn correct(licence: &str) -> Option<String> {
+    let licence = licence.trim();
+    if licence.is_empty() {
+        return None;
+    }
+    if valid(licence) {
+        return Some(upgrade_gpl(licence));
+    }
+    let no_plus = licence.strip_suffix('+').unwrap_or(licence).trim();
+    if valid(no_plus) {
+        return Some(upgrade_gpl(no_plus));
+    }
+
+    valid_transformation(licence)
+        .or_else(|| {
+            any_correction(licence, |corrected| {
+                if valid(corrected) {
+                    Some(corrected.to_string())
+                } else {
+                    valid_transformation(corrected)
+                }
+            })
+        })
+        .or_else(|| valid_last_resort(licence))
+        .or_else(|| any_correction(licence, valid_last_resort))
+        .map(|corrected| upgrade_gpl(&corrected))
+}
+
+/// Replace deprecated GPL-family identifiers with their `-only` or
+/// `-or-later` forms.
+fn upgrade_gpl(licence: &str) -> String {
+    const ONLY: &[&str] = &[
+        "GPL-1.0", "LGPL-1.0", "AGPL-1.0", "GPL-2.0", "LGPL-2.0", "AGPL-2.0", "LGPL-2.1",
+    ];
+    const PLUS: &[&str] = &[
+        "GPL-1.0+",
+        "GPL-2.0+",
+        "GPL-3.0+",
+        "LGPL-2.0+",
+        "LGPL-2.1+",
+        "LGPL-3.0+",
+        "AGPL-1.0+",
+        "AGPL-3.0+",
+    ];
+    const LATER: &[&str] = &["GPL-3.0", "LGPL-3.0", "AGPL-3.0"];
+    if ONLY.contains(&licence) {
+        format!("{licence}-only")
+    } else if PLUS.contains(&licence) {
+        format!("{}-or-later", &licence[..licence.len() - 1])
+    } else if LATER.contains(&licence) {
+        format!("{licence}-or-later")
+    } else {
+        licence.to_string()
+    }
+}
+
+/// Simple corrections to nearly valid identifiers, in the order they are
+/// tried.
+fn transforms(licence: &str) -> Vec<String> {
+    let first_upper = {
+        let mut chars = licence.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    };
+    let creative_commons = licence
+        .replacen("Attribution", "BY", 1)
+        .replacen("NonCommercial", "NC", 1)
+        .replacen("NoDerivatives", "ND", 1);
+    let creative_commons = regex!(r" ([0-9])").replace(&creative_commons, "-${1}");
+    let creative_commons = regex!(r" ?International")
+        .replace(&creative_commons, "")
+        .into_owned();
+    vec![
+        // e.g. 'mit'
+        licence.to_uppercase(),
+        // e.g. 'MIT '
+        licence.trim().to_string(),
+        // e.g. 'M.I.T.'
+        licence.replace('.', ""),
+        // e.g. 'Apache- 2.0'
+        regex!(r"\s+").replace_all(licence, "").into_owned(),
+        // e.g. 'CC BY 4.0'
+        regex!(r"\s+").replace_all(licence, "-").into_owned(),
+        // e.g. 'LGPLv2.1'
+        licence.replacen('v', "-", 1),
+        // e.g. 'Apache 2.0'
+        regex!(r",?\s*([0-9])")
+            .replace(licence, "-${1}")
+            .into_owned(),
+        // e.g. 'GPL 2'
+        regex!(r",?\s*([0-9])")
+            .replace(licence, "-${1}.0")
+            .into_owned(),
+        // e.g. 'Apache Version 2.0'
+        regex!(r",?\s*(V\.|v\.|V|v|Version|version)\s*([0-9])")
+            .replace(licence, "-${2}")
+            .into_owned(),
+        // e.g. 'Apache Version 2'
+        regex!(r",?\s*(V\.|v\.|V|v|Version|version)\s*([0-9])")
+            .replace(licence, "-${2}.0")
+            .into_owned(),
+        // e.g. 'ZLIB'
+        first_upper,
+        // e.g. 'MPL/2.0'
+        licence.replacen('/', "-", 1),
+        // e.g. 'Apache 2'
+        regex!(r"([0-9])$")
+            .replace(
+                &regex!(r"\s*V\s*([0-9])").replace(licence, "-${1}"),
+                "${1}.0",
+            )
+            .into_owned(),
+        // e.g. 'GPL-2.0', 'GPL-3.0'
+        if licence.contains("3.0") {
+            format!("{licence}-or-later")
+        } else {
+            format!("{licence}-only")
+        },
+        // e.g. 'GPL-2.0-'
+        format!("{licence}only"),
+        // e.g. 'GPL2'
+        regex!(r"([0-9])$").replace(licence, "-${1}.0").into_owned(),
+        // e.g. 'BSD 3'
+        regex!(r"(-| )?([0-9])$")
+            .replace(licence, "-${2}-Clause")
+            .into_owned(),
+        // e.g. 'BSD clause 3'
+        regex!(r"(-| )clause(-| )([0-9])")
+            .replace(licence, "-${3}-Clause")
+            .into_owned(),
+        // e.g. 'New BSD license'
+        regex!(r"(?i)\b(Modified|New|Revised)(-| )?BSD((-| )License)?")
+            .replace(licence, "BSD-3-Clause")
+            .into_owned(),
+        // e.g. 'Simplified BSD license'
+        regex!(r"(?i)\bSimplified(-| )?BSD((-| )License)?")
+            .replace(licence, "BSD-2-Clause")
+            .into_owned(),
+        // e.g. 'Free BSD license'
+        regex!(r"(?i)\b(Free|Net)(-| )?BSD((-| )License)?")
+            .replace(licence, "BSD-2-Clause-${1}BSD")
+            .into_owned(),
+        // e.g. 'Clear BSD license'
+        regex!(r"(?i)\bClear(-| )?BSD((-| )License)?")
+            .replace(licence, "BSD-3-Clause-Clear")
+            .into_owned(),
+        // e.g. 'Old BSD License'
+        regex!(r"(?i)\b(Old|Original)(-| )?BSD((-| )License)?")
+            .replace(licence, "BSD-4-Clause")
+            .into_owned(),
+        // e.g. 'BY-NC-4.0'
+        format!("CC-{licence}"),
+        // e.g. 'BY-NC'
+        format!("CC-{licence}-4.0"),
+        // e.g. 'Attribution-NonCommercial'
+        creative_commons.clone(),
+        format!("CC-{creative_commons}-4.0"),
+    ]
+}
+
+fn valid_transformation(licence: &str) -> Option<String> {
+    transforms(licence)
+        .into_iter()
+        .map(|transformed| transformed.trim().to_string())
+        .find(|transformed| transformed != licence && valid(transformed))
+}
+
+/// Sort substitutions so that longer patterns are tried first, so that
+/// "Lesser General Public License" is tried before "General Public
+/// License".
+fn sort_longest_first(substitutions: &mut [(String, String)]) {
+    substitutions.sort_by(|(a, _), (b, _)| {
+        b.len()
+            .cmp(&a.len())
+            .then_with(|| a.to_uppercase().cmp(&b.to_uppercase()))
+    });
+}
+
+/// Common misspellings of license names and acronyms.
+static TRANSPOSITIONS: LazyLock<Vec<(String, String)>> = LazyLock::new(|| {
+    let mut transpositions: Vec<(String, String)> = [
+        ("APGL", "AGPL"),
+        ("Gpl", "GPL"),
+        ("GLP", "GPL"),
+        ("APL", "Apache"),
+        ("ISD", "ISC"),
+        ("GLP", "GPL"),
+        ("IST", "ISC"),
+        ("Claude", "Clause"),
+        (" or later", "+"),
+        (" International", ""),
+        ("GNU", "GPL"),
+        ("GUN", "GPL"),
+        ("+", ""),
+        ("GNU GPL", "GPL"),
+        ("GNU LGPL", "LGPL"),
+        ("GNU/GPL", "GPL"),
+        ("GNU GLP", "GPL"),
+        ("GNU LESSER GENERAL PUBLIC LICENSE", "LGPL"),
+        ("GNU Lesser General Public License", "LGPL"),
+        ("GNU LESSER GENERAL PUBLIC LICENSE", "LGPL-2.1"),
+        ("GNU Lesser General Public License", "LGPL-2.1"),
+        ("LESSER GENERAL PUBLIC LICENSE", "LGPL"),
+        ("Lesser General Public License", "LGPL"),
+        ("LESSER GENERAL PUBLIC LICENSE", "LGPL-2.1"),
+        ("Lesser General Public License", "LGPL-2.1"),
+        ("GNU General Public License", "GPL"),
+        ("Gnu public license", "GPL"),
+        ("GNU Public License", "GPL"),
+        ("GNU GENERAL PUBLIC LICENSE", "GPL"),
+        ("MTI", "MIT"),
+        ("Mozilla Public License", "MPL"),
+        ("Universal Permissive License", "UPL"),
+        ("WTH", "WTF"),
+        ("WTFGPL", "WTFPL"),
+        ("-License", ""),
+    ]
+    .into_iter()
+    .map(|(from, to)| (from.to_string(), to.to_string()))
+    .collect();
+    sort_longest_first(&mut transpositions);
+    transpositions
+});
+
+/// If all else fails, licences that contain these substrings, in upper
+/// case, are taken to mean these licenses.
+static LAST_RESORTS: LazyLock<Vec<(String, String)>> = LazyLock::new(|| {
+    let mut last_resorts: Vec<(String, String)> = [
+        ("UNLI", "Unlicense"),
+        ("WTF", "WTFPL"),
+        ("2 CLAUSE", "BSD-2-Clause"),
+        ("2-CLAUSE", "BSD-2-Clause"),
+        ("3 CLAUSE", "BSD-3-Clause"),
+        ("3-CLAUSE", "BSD-3-Clause"),
+        ("AFFERO", "AGPL-3.0-or-later"),
+        ("AGPL", "AGPL-3.0-or-later"),
+        ("APACHE", "Apache-2.0"),
+        ("ARTISTIC", "Artistic-2.0"),
+        ("Affero", "AGPL-3.0-or-later"),
+        ("BEER", "Beerware"),
+        ("BOOST", "BSL-1.0"),
+        ("BSD", "BSD-2-Clause"),
+        ("CDDL", "CDDL-1.1"),
+        ("ECLIPSE", "EPL-1.0"),
+        ("FUCK", "WTFPL"),
+        ("GNU", "GPL-3.0-or-later"),
+        ("LGPL", "LGPL-3.0-or-later"),
+        ("GPLV1", "GPL-1.0-only"),
+        ("GPL-1", "GPL-1.0-only"),
+        ("GPLV2", "GPL-2.0-only"),
+        ("GPL-2", "GPL-2.0-only"),
+        ("GPL", "GPL-3.0-or-later"),
+        ("MIT +NO-FALSE-ATTRIBS", "MITNFA"),
+        ("MIT", "MIT"),
+        ("MPL", "MPL-2.0"),
+        ("X11", "X11"),
+        ("ZLIB", "Zlib"),
+    ]
+    .into_iter()
+    .map(|(from, to)| (from.to_string(), to.to_string()))
+    .collect();
+
+    // Licenses with a single version, such as `CECILL-2.1`, are the last
+    // resort for their names without the version, except `APL`, which is
+    // taken to mean Apache.
+    let mut versions: Vec<(&str, Vec<&str>)> = Vec::new();
+    for id in ids(LICENSE_LIST) {
+        let Some(name) = regex!(r"^(.*)-[0-9]+\.[0-9]+$")
+            .captures(id)
+            .and_then(|captures| captures.get(1))
+        else {
+            continue;
+        };
+        match versions
+            .iter_mut()
+            .find(|(known, _)| *known == name.as_str())
+        {
+            Some((_, ids)) => ids.push(id),
+            None => versions.push((name.as_str(), vec![id])),
+        }
+    }
+    last_resorts.extend(
+        versions
+            .into_iter()
+            .filter(|(name, ids)| ids.len() == 1 && *name != "APL")
+            .map(|(name, ids)| (name.to_string(), ids[0].to_string())),
+    );
+
+    sort_longest_first(&mut last_resorts);
+    last_resorts
+});
+
+fn valid_last_resort(licence: &str) -> Option<String> {
+    let upper = licence.to_uppercase();
+    LAST_RESORTS
+        .iter()
+        .find(|(substring, _)| upper.contains(substring.as_str()))
+        .map(|(_, id)| id.clone())
+}
+
+/// Apply the first transposition that `check` can correct.
+fn any_correction(licence: &str, check: impl Fn(&str) -> Option<String>) -> Option<String> {
+    TRANSPOSITIONS
+        .iter()
+        .filter(|(from, _)| licence.contains(from.as_str()))
+        .find_map(|(from, to)| check(&licence.replacen(from.as_str(), to, 1)))
+}
+
+/// The listed identifier closest to an unknown one, if any is close enough
+/// to be a likely typo.
+pub fn closest(id: &str, exception: bool) -> Option<&'static str> {
+    let list = if exception {
+        EXCEPTION_LIST
+    } else {
+        LICENSE_LIST
+    };
+    let id = id.to_lowercase();
+    let (distance, closest) = ids(list)
+        .map(|known| (edit_distance(&id, &known.to_lowercase()), known))
+        .min_by_key(|(distance, _)| *distance)?;
+    (distance <= (id.chars().count() / 3).max(1)).then_some(closest)
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Licences that failed validation.
+pub struct Invalid {
+    pub errors: Vec<String>,
+
+    /// Corrected licences by original, for the licences that can be fixed.
+    pub corrections: Vec<(String, String)>,
+}
+
+impl Invalid {
+    /// The failure to report. Only corrections can be fixed.
+    pub fn failure(&self, manual_fix: &str) -> HandlerResult {
+        HandlerResult::Failure(PolicyErrorResult {
+            error: Some(self.errors.join("; ")),
+            manual_fix: Some(manual_fix.to_string()),
+            fixable: Some(!self.corrections.is_empty()),
+            ..PolicyErrorResult::default()
+        })
+    }
+
+    /// The correction for a licence, if it has one.
+    pub fn correction(&self, licence: &str) -> Option<&str> {
+        self.corrections
+            .iter()
+            .find(|(original, _)| original == licence)
+            .map(|(_, corrected)| corrected.as_str())
+    }
+}
+
+/// Validate licences against an allowlist, then as SPDX expressions, as
+/// `validateLicenceField` in `src/policyDefiners/spdxValidator.ts` does.
+/// An expression is allowed if it is in the allowlist, or all of its
+/// licenses are. Returns `None` if the licences are valid.
+pub fn validate(licences: &[&str], allowed: Option<&[String]>, spdx: bool) -> Option<Invalid> {
+    if let Some(allowed) = allowed {
+        let errors: Vec<String> = licences
+            .iter()
+            .filter(|licence| !is_allowed(licence, allowed))
+            .map(|licence| {
+                format!(
+                    "Licence \"{licence}\" is not in the allowed list: {}",
+                    allowed.join(", ")
+                )
+            })
+            .collect();
+        if !errors.is_empty() {
+            return Some(Invalid {
+                errors,
+                corrections: Vec::new(),
+            });
+        }
+    }
+
+    if !spdx {
+        return None;
+    }
+    let mut errors = Vec::new();
+    let mut corrections = Vec::new();
+    for licence in licences {
+        let corrected = if regex!(r" (AND|OR|WITH) ").is_match(licence) {
+            correct_expression(licence)
+        } else {
+            correct(licence)
+        };
+        match corrected {
+            Some(corrected) if corrected == *licence => {}
+            Some(corrected) => {
+                errors.push(format!("Licence \"{licence}\" should be \"{corrected}\""));
+                corrections.push((licence.to_string(), corrected));
+            }
+            None => {
+                let suggestion = match parse(licence.trim()) {
+                    Err(Error::UnknownLicense(id)) => closest(&id, false),
+                    Err(Error::UnknownException(id)) => closest(&id, true),
+                    _ => None,
+                };
+                errors.push(match suggestion {
+                    Some(suggestion) => format!(
+                        "Licence \"{licence}\" is not a recognized SPDX identifier. Did you mean \"{suggestion}\"?"
+                    ),
+                    None => format!("Licence \"{licence}\" is not a recognized SPDX identifier"),
+                });
+            }
+        }
+    }
+    (!errors.is_empty()).then_some(Invalid {
+        errors,
+        corrections,
+    })
+}
+
+/// Correct each unknown license in an expression on its own, where
+/// `spdx-correct` would replace the whole expression with one license.
+fn correct_expression(expression: &str) -> Option<String> {
+    let Err(Error::UnknownLicense(id)) = parse(expression) else {
+        return valid(expression).then(|| expression.to_string());
+    };
+    let corrected = correct(&id)?;
+    let id = Regex::new(&format!(r"(^|[ (]){}($|[ )+])", regex::escape(&id)))
+        .expect("escaped identifier is a valid pattern");
+    let replaced = id.replace(expression, format!("${{1}}{corrected}${{2}}"));
+    if replaced == expression {
+        return None;
+    }
+    correct_expression(&replaced)
+}
+
+fn is_allowed(licence: &str, allowed: &[String]) -> bool {
+    if allowed.iter().any(|known| known == licence) {
+        return true;
+    }
+    parse(licence).is_ok_and(|expression| {
+        expression
+            .licenses()
+            .iter()
+            .all(|license| allowed.iter().any(|known| known == license))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Expression, closest, correct, parse, validate};
+
+    /// Corrections are checked against `spdx-correct` by
+    /// `test/spdx-conformance.test.ts`.
+    const CONFORMANCE: &str = include_str!("../../../../test/data/spdx-conformance.json");
+
+    #[test]
+    fn corrects_like_spdx_correct() {
+        let cases: Vec<(String, Option<String>)> =
+            serde_json::from_str(CONFORMANCE).expect("conformance fixture is valid JSON");
+        for (licence, expected) in cases {
+            assert_eq!(correct(&licence), expected, "{licence:?}");
+        }
+    }
+
+    #[test]
+    fn parses_expressions() {
+        let license = |id: &str, plus, exception: Option<&str>| Expression::License {
+            id: id.to_string(),
+            plus,
+            exception: exception.map(str::to_string),
+        };
+        assert_eq!(
+            parse("MIT OR (GPL-2.0+ WITH Classpath-exception-2.0 AND DocumentRef-a:LicenseRef-b)"),
+            Ok(Expression::Or(
+                Box::new(license("MIT", false, None)),
+                Box::new(Expression::And(
+                    Box::new(license("GPL-2.0", true, Some("Classpath-exception-2.0"))),
+                    Box::new(Expression::LicenseRef(
+                        "DocumentRef-a:LicenseRef-b".to_string()
+                    )),
+                )),
+            ))
+        );
+        assert_eq!(
+            parse("MIT OR Apahce-2.0"),
+            Err(Error::UnknownLicense("Apahce-2.0".to_string()))
+        );
+        assert_eq!(parse("MIT AND"), Err(Error::Syntax));
+        assert_eq!(parse("MIT +"), Err(Error::Syntax));
+        assert_eq!(closest("Apahce-2.0", false), Some("Apache-2.0"));
+        assert_eq!(closest("Proprietary", false), None);
+    }
+
+    #[test]
+    fn validates_licences() {
+        let errors = |licences: &[&str], allowed: Option<&[String]>| {
+            validate(licences, allowed, true).map(|invalid| (invalid.errors, invalid.corrections))
+        };
+        let allowed = ["MIT".to_string(), "Apache-2.0".to_string()];
+        assert!(errors(&["MIT OR Apache-2.0"], Some(&allowed)).is_none());
+        assert_eq!(
+            errors(&["MIT OR ISC"], Some(&allowed)).unwrap().0,
+            ["Licence \"MIT OR ISC\" is not in the allowed list: MIT, Apache-2.0"]
+        );
+        assert_eq!(
+            errors(&["MIT OR Apache-2 OR mit"], None).unwrap().1,
+            [(
+                "MIT OR Apache-2 OR mit".to_string(),
+                "MIT OR Apache-2.0 OR MIT".to_string()
+            )]
+        );
+        assert_eq!(
+            errors(&["Apahce-2.0 WITH LLVM-exception OR Proprietary"], None)
+                .unwrap()
+                .0,
+            [
+                "Licence \"Apahce-2.0 WITH LLVM-exception OR Proprietary\" is not a recognized SPDX identifier. Did you mean \"Apache-2.0\"?"
+            ]
+        );
+        assert_eq!(
+            errors(&["MIT WITH LLVM-exceptoin"], None).unwrap().0,
+            [
+                "Licence \"MIT WITH LLVM-exceptoin\" is not a recognized SPDX identifier. Did you mean \"LLVM-exception\"?"
+            ]
+        );
+    }
+}
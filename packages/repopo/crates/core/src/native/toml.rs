@@ -0,0 +1,37 @@
+//! Reading TOML files for native policies.
+//!
+//! Documents are parsed with `toml_edit`, which keeps comments and
+//! formatting, so policies can check the parsed values and write back
+//! fixes that change nothing else.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use toml_edit::{DocumentMut, Value};
+
+use super::NativeFile;
+
+/// Parse a file as TOML.
+pub fn parse(file: &NativeFile) -> Result<DocumentMut> {
+    let contents = std::str::from_utf8(file.contents()?)
+        .with_context(|| format!("{} is not UTF-8", file.path))?;
+    contents
+        .parse()
+        .with_context(|| format!("Failed to parse {}", file.path))
+}
+
+/// Read and parse a TOML file that is not the file a policy runs on.
+pub fn read(path: &Path) -> Result<DocumentMut> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    contents
+        .parse()
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Replace a string value, keeping the comments and whitespace around it.
+pub fn replace_str(value: &mut Value, new: &str) {
+    let decor = value.decor().clone();
+    *value = Value::from(new);
+    *value.decor_mut() = decor;
+}
@@ -0,0 +1,293 @@
+//! Native ports of `src/policies/CargoTomlSorted.ts` and
+//! `src/policies/GleamTomlSorted.ts`.
+//!
+//! Keys are sorted in the parsed document rather than by moving lines, so
+//! comments move with the key they precede, and quoted keys, multiline
+//! values, and inline tables are handled. Sub-tables such as
+//! `[dependencies.foo]` always follow the keys of their parent table, so
+//! they are sorted separately.
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+use super::{NativeFile, NativePolicy, toml};
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Policy settings, as for the TS policies.
+#[derive(Debug, Default, Deserialize)]
+struct Settings {
+    /// Sections whose keys should be sorted alphabetically.
+    sections: Option<Vec<String>>,
+}
+
+/// A kind of manifest whose dependency sections are kept sorted.
+struct Manifest {
+    description: &'static str,
+    pattern: &'static str,
+    tags: &'static [&'static str],
+    default_sections: &'static [&'static str],
+}
+
+const CARGO: Manifest = Manifest {
+    description: "Ensures dependency sections in Cargo.toml are sorted alphabetically.",
+    pattern: r"(^|\/)Cargo\.toml$",
+    tags: &["cargo"],
+    default_sections: &["dependencies", "dev-dependencies", "build-dependencies"],
+};
+
+const GLEAM: Manifest = Manifest {
+    description: "Ensures dependency sections in gleam.toml are sorted alphabetically.",
+    pattern: r"(^|\/)gleam\.toml$",
+    tags: &["gleam"],
+    default_sections: &["dependencies", "dev-dependencies"],
+};
+
+/// Ensures dependency sections of a manifest are sorted alphabetically.
+struct TomlSorted {
+    manifest: &'static Manifest,
+    sections: Vec<String>,
+}
+
+pub fn create_cargo(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    create(&CARGO, config)
+}
+
+pub fn create_gleam(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    create(&GLEAM, config)
+}
+
+fn create(
+    manifest: &'static Manifest,
+    config: &serde_json::Value,
+) -> Result<Box<dyn NativePolicy>> {
+    let settings: Settings =
+        serde_json::from_value::<Option<Settings>>(config.clone())?.unwrap_or_default();
+    Ok(Box::new(TomlSorted {
+        manifest,
+        sections: settings.sections.unwrap_or_else(|| {
+            manifest
+                .default_sections
+                .iter()
+                .map(|section| section.to_string())
+                .collect()
+        }),
+    }))
+}
+
+impl TomlSorted {
+    /// The configured sections of a document that are not sorted.
+    fn unsorted_sections<'a>(&'a self, doc: &DocumentMut) -> Vec<&'a str> {
+        self.sections
+            .iter()
+            .filter(|section| doc.get(section).is_some_and(|item| !is_sorted(item)))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+impl NativePolicy for TomlSorted {
+    fn description(&self) -> &str {
+        self.manifest.description
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: self.manifest.pattern.to_string(),
+            flags: String::new(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        self.manifest.tags
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        let doc = toml::parse(file)?;
+        let unsorted = self.unsorted_sections(&doc);
+        if unsorted.is_empty() {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        Ok(HandlerResult::Failure(PolicyErrorResult {
+            error: Some(format!("Unsorted sections: [{}]", unsorted.join("], ["))),
+            manual_fix: Some(
+                "Sort the dependency keys alphabetically in the listed sections.".to_string(),
+            ),
+            ..PolicyErrorResult::default()
+        }))
+    }
+
+    fn has_resolver(&self) -> bool {
+        true
+    }
+
+    fn resolve(&self, file: &NativeFile) -> Result<Option<Vec<u8>>> {
+        let mut doc = toml::parse(file)?;
+        let unsorted: Vec<String> = self
+            .unsorted_sections(&doc)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        for section in unsorted {
+            if let Some(item) = doc.get_mut(&section) {
+                sort(item);
+            }
+        }
+        Ok(Some(doc.to_string().into_bytes()))
+    }
+}
+
+/// Returns true if an item is a table written with its own header, which
+/// follows the keys of its parent table.
+fn is_header(item: &Item) -> bool {
+    match item {
+        Item::Table(table) => !table.is_dotted(),
+        Item::ArrayOfTables(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns true if the keys of a table are sorted, and its sub-tables with
+/// headers are sorted after them. Anything but a table is sorted.
+fn is_sorted(item: &Item) -> bool {
+    let keys: Vec<(bool, &str)> = match item {
+        Item::Table(table) => table
+            .iter()
+            .map(|(key, item)| (is_header(item), key))
+            .collect(),
+        Item::Value(Value::InlineTable(table)) => {
+            table.iter().map(|(key, _)| (false, key)).collect()
+        }
+        _ => return true,
+    };
+    keys.is_sorted()
+}
+
+/// Sort the keys of a table, and its sub-tables with headers.
+fn sort(item: &mut Item) {
+    let table = match item {
+        Item::Table(table) => table,
+        Item::Value(Value::InlineTable(table)) => {
+            table.sort_values_by(|key1, _, key2, _| key1.get().cmp(key2.get()));
+            return;
+        }
+        _ => return,
+    };
+    table.sort_values_by(|key1, item1, key2, item2| {
+        (is_header(item1), key1.get()).cmp(&(is_header(item2), key2.get()))
+    });
+
+    // Tables with headers are written in the order of their positions in the
+    // document, so give the sub-tables the positions they had between them,
+    // in the new order, keeping the tables nested in each together.
+    let mut old = Vec::new();
+    for (_, child) in table.iter() {
+        let start = old.len();
+        positions(child, &mut old);
+        old[start..].sort_unstable();
+    }
+    let mut new = old.clone();
+    new.sort_unstable();
+    let moved: HashMap<isize, isize> = old.into_iter().zip(new).collect();
+    for (_, child) in table.iter_mut() {
+        reposition(child, &moved);
+    }
+}
+
+/// Collect the document positions of the tables with headers in an item.
+fn positions(item: &Item, out: &mut Vec<isize>) {
+    let tables: Vec<&Table> = match item {
+        Item::Table(table) if !table.is_dotted() => vec![table],
+        Item::ArrayOfTables(array) => array.iter().collect(),
+        _ => return,
+    };
+    for table in tables {
+        out.extend(table.position());
+        for (_, child) in table.iter() {
+            positions(child, out);
+        }
+    }
+}
+
+/// Move the tables with headers in an item to new document positions.
+fn reposition(item: &mut Item, moved: &HashMap<isize, isize>) {
+    let tables: Vec<&mut Table> = match item {
+        Item::Table(table) if !table.is_dotted() => vec![table],
+        Item::ArrayOfTables(array) => array.iter_mut().collect(),
+        _ => return,
+    };
+    for table in tables {
+        if let Some(position) = table.position().and_then(|p| moved.get(&p)) {
+            table.set_position(Some(*position));
+        }
+        for (_, child) in table.iter_mut() {
+            reposition(child, moved);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_sorted, sort};
+    use toml_edit::DocumentMut;
+
+    #[test]
+    fn sorts_keys_and_sub_tables_keeping_comments() {
+        let input = r#"[package]
+name = "a"
+
+[dependencies]
+# Serialization
+serde = { version = "1", features = ["derive"] } # pinned
+anyhow = "1"
+"quoted" = [
+    "multiline",
+]
+
+[dependencies.zed]
+version = "1"
+
+[dependencies.zed.features]
+a = true
+
+[dependencies.bar]
+version = "2" # bar
+
+[dev-dependencies]
+b = "1"
+"#;
+        let expected = r#"[package]
+name = "a"
+
+[dependencies]
+anyhow = "1"
+"quoted" = [
+    "multiline",
+]
+# Serialization
+serde = { version = "1", features = ["derive"] } # pinned
+
+[dependencies.bar]
+version = "2" # bar
+
+[dependencies.zed]
+version = "1"
+
+[dependencies.zed.features]
+a = true
+
+[dev-dependencies]
+b = "1"
+"#;
+
+        let mut doc: DocumentMut = input.parse().unwrap();
+        assert!(!is_sorted(&doc["dependencies"]));
+        sort(&mut doc["dependencies"]);
+        assert_eq!(doc.to_string(), expected);
+
+        let doc: DocumentMut = expected.parse().unwrap();
+        assert!(is_sorted(&doc["dependencies"]));
+    }
+}
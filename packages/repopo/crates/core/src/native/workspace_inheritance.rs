@@ -0,0 +1,155 @@
+//! Native port of `src/policies/WorkspaceInheritance.ts`.
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::path::Path;
+use toml_edit::Item;
+
+use super::{NativeFile, NativePolicy, toml};
+use crate::types::{FilePattern, HandlerResult, PolicyErrorResult};
+
+/// Policy settings, as for the TS policy.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    /// Package fields that should use workspace inheritance.
+    inherit: Option<Vec<String>>,
+
+    /// Crate paths that are allowed to override inherited fields.
+    allow_overrides: Option<Vec<String>>,
+}
+
+const DEFAULT_INHERIT_FIELDS: &[&str] = &["version", "authors", "license", "repository"];
+
+/// Ensures Cargo workspace members inherit fields from the workspace config.
+struct WorkspaceInheritance {
+    inherit: Vec<String>,
+    allow_overrides: Vec<String>,
+}
+
+pub fn create(config: &serde_json::Value) -> Result<Box<dyn NativePolicy>> {
+    let settings: Settings =
+        serde_json::from_value::<Option<Settings>>(config.clone())?.unwrap_or_default();
+    Ok(Box::new(WorkspaceInheritance {
+        inherit: settings.inherit.unwrap_or_else(|| {
+            DEFAULT_INHERIT_FIELDS
+                .iter()
+                .map(|field| field.to_string())
+                .collect()
+        }),
+        allow_overrides: settings.allow_overrides.unwrap_or_default(),
+    }))
+}
+
+impl WorkspaceInheritance {
+    /// Check if a member path matches any override pattern.
+    fn is_override_allowed(&self, member: &str) -> bool {
+        self.allow_overrides
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => member.starts_with(prefix),
+                None => member == pattern,
+            })
+    }
+}
+
+/// Check a single member's fields for workspace inheritance compliance.
+fn check_member_inheritance(
+    member: &str,
+    root: &str,
+    available_fields: &[&str],
+    errors: &mut Vec<String>,
+) {
+    // Member might not exist (handled by WorkspaceMembersValid)
+    let Ok(member_toml) = toml::read(&Path::new(root).join(member).join("Cargo.toml")) else {
+        return;
+    };
+    let Some(member_pkg) = member_toml.get("package").and_then(Item::as_table_like) else {
+        return;
+    };
+
+    for field in available_fields {
+        let Some(value) = member_pkg.get(field) else {
+            continue;
+        };
+        let inherited = value
+            .as_table_like()
+            .and_then(|table| table.get("workspace"))
+            .and_then(Item::as_bool)
+            == Some(true);
+        if !inherited {
+            errors.push(format!(
+                "{member}: package.{field} should use workspace inheritance ({field}.workspace = true)"
+            ));
+        }
+    }
+}
+
+impl NativePolicy for WorkspaceInheritance {
+    fn description(&self) -> &str {
+        "Ensures Cargo workspace members inherit shared fields (version, authors, etc.) from workspace config."
+    }
+
+    fn match_pattern(&self) -> FilePattern {
+        FilePattern::Regex {
+            source: r"^Cargo\.toml$".to_string(),
+            flags: String::new(),
+        }
+    }
+
+    fn tags(&self) -> &[&str] {
+        &["cargo"]
+    }
+
+    fn check(&self, file: &NativeFile) -> Result<HandlerResult> {
+        let doc = toml::parse(file)?;
+        let Some(workspace) = doc.get("workspace").and_then(Item::as_table_like) else {
+            return Ok(HandlerResult::Pass(true));
+        };
+
+        let members: Vec<&str> = workspace
+            .get("members")
+            .and_then(Item::as_array)
+            .map(|members| members.iter().filter_map(|m| m.as_str()).collect())
+            .unwrap_or_default();
+        if members.is_empty() {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        // Check which fields are defined at the workspace level
+        let workspace_pkg = workspace.get("package").and_then(Item::as_table_like);
+        let available_fields: Vec<&str> = self
+            .inherit
+            .iter()
+            .map(String::as_str)
+            .filter(|field| workspace_pkg.is_some_and(|pkg| pkg.contains_key(field)))
+            .collect();
+        if available_fields.is_empty() {
+            return Ok(HandlerResult::Pass(true));
+        }
+
+        let mut errors = Vec::new();
+        for member in members {
+            if member.contains('*') || self.is_override_allowed(member) {
+                continue;
+            }
+            check_member_inheritance(member, file.root, &available_fields, &mut errors);
+        }
+
+        if errors.is_empty() {
+            return Ok(HandlerResult::Pass(true));
+        }
+        Ok(HandlerResult::Failure(PolicyErrorResult {
+            error: Some(errors.join("; ")),
+            manual_fix: Some(
+                "Use workspace inheritance in member Cargo.toml files, e.g., `version.workspace = true`."
+                    .to_string(),
+            ),
+            ..PolicyErrorResult::default()
+        }))
+    }
+
+    fn cacheable(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,346 @@
+//! Matching of file patterns: JS regexes and globs.
+//!
+//! Globs are translated to JS regex syntax with the same rules as `glob()`
+//! in `src/glob.ts`, then compiled like any other policy regex. Both
+//! translations are checked against `test/data/glob-conformance.json`.
+
+use anyhow::{Context, Result};
+
+use crate::jsregex::JsRegex;
+use crate::types::FilePattern;
+
+/// A compiled [`FilePattern`].
+#[derive(Debug)]
+pub struct Matcher {
+    regex: JsRegex,
+
+    /// The pattern is a negated glob, so a regex match means no match.
+    negated: bool,
+}
+
+impl Matcher {
+    pub fn new(pattern: &FilePattern) -> Result<Self> {
+        match pattern {
+            FilePattern::Regex { source, flags } => Ok(Self {
+                regex: JsRegex::new(source, flags)?,
+                negated: false,
+            }),
+            FilePattern::Glob { glob, ignore_case } => {
+                let (source, negated) = glob_to_regex_source(glob);
+                let flags = if *ignore_case { "i" } else { "" };
+                let regex = JsRegex::new(&source, flags)
+                    .with_context(|| format!("Failed to compile glob: {glob}"))?;
+                Ok(Self { regex, negated })
+            }
+        }
+    }
+
+    /// Returns true if the pattern matches a repo-relative path.
+    pub fn is_match(&self, path: &str) -> bool {
+        self.regex.is_match(path) != self.negated
+    }
+
+    /// The Rust regex equivalent to this pattern, if it can be combined
+    /// into a [`regex::RegexSet`].
+    pub fn set_pattern(&self) -> Option<&str> {
+        if self.negated {
+            None
+        } else {
+            self.regex.fast_pattern()
+        }
+    }
+}
+
+/// Translate a glob to the source of an anchored JS regex. Returns whether
+/// the glob is negated with a leading `!`; the source is for the glob
+/// without it.
+pub fn glob_to_regex_source(glob: &str) -> (String, bool) {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut start = 0;
+    let mut negated = false;
+    while chars.get(start) == Some(&'!') {
+        negated = !negated;
+        start += 1;
+    }
+    if chars[start..].starts_with(&['.', '/']) {
+        start += 2;
+    }
+
+    let body = translate(&chars, start, chars.len(), true, true);
+    (format!("^{body}$"), negated)
+}
+
+fn push_escaped(out: &mut String, c: char) {
+    if "\\^$.|?*+()[]{}/".contains(c) {
+        out.push('\\');
+    }
+    out.push(c);
+}
+
+/// Escape a character that a glob escaped inside a class. Letters and digits
+/// stay literal.
+fn push_class_escaped(out: &mut String, c: char) {
+    if !c.is_ascii_alphanumeric() {
+        out.push('\\');
+    }
+    out.push(c);
+}
+
+/// Translate `chars[start..end]`. `starts_segment` and `ends_segment` say
+/// whether the range is bounded by a path separator (or the end of the
+/// glob) on either side, which decides whether a `**` at its edge is a
+/// globstar.
+fn translate(
+    chars: &[char],
+    start: usize,
+    end: usize,
+    starts_segment: bool,
+    ends_segment: bool,
+) -> String {
+    let at_segment_start = |index: usize| {
+        if index == start {
+            starts_segment
+        } else {
+            chars[index - 1] == '/'
+        }
+    };
+    let at_segment_end = |index: usize| {
+        if index == end {
+            ends_segment
+        } else {
+            chars[index] == '/'
+        }
+    };
+
+    let mut out = String::new();
+    let mut i = start;
+    while i < end {
+        match chars[i] {
+            '\\' => {
+                if i + 1 < end {
+                    push_escaped(&mut out, chars[i + 1]);
+                    i += 2;
+                } else {
+                    out.push_str(r"\\");
+                    i += 1;
+                }
+            }
+            '/' => {
+                // A trailing `/**` also matches the directory itself.
+                if ends_segment && i + 3 == end && chars[i + 1..end] == ['*', '*'] {
+                    out.push_str(r"(?:\/.*)?");
+                    i += 3;
+                } else {
+                    out.push_str(r"\/");
+                    i += 1;
+                }
+            }
+            '*' => {
+                let mut stars = 1;
+                while i + stars < end && chars[i + stars] == '*' {
+                    stars += 1;
+                }
+                let next = i + stars;
+                if stars == 2 && at_segment_start(i) && at_segment_end(next) {
+                    if next < end {
+                        // `**/` matches any number of leading segments.
+                        out.push_str(r"(?:[^/]*\/)*");
+                        i = next + 1;
+                    } else {
+                        out.push_str(".*");
+                        i = next;
+                    }
+                } else {
+                    out.push_str("[^/]*");
+                    i = next;
+                }
+            }
+            '?' => {
+                out.push_str("[^/]");
+                i += 1;
+            }
+            '[' => match find_class_end(chars, i, end) {
+                Some(close) => {
+                    translate_class(&chars[i + 1..close], &mut out);
+                    i = close + 1;
+                }
+                None => {
+                    out.push_str(r"\[");
+                    i += 1;
+                }
+            },
+            '{' => {
+                let alternatives = find_brace_end(chars, i, end)
+                    .map(|close| (close, split_alternatives(chars, i + 1, close)))
+                    .filter(|(_, alternatives)| alternatives.len() >= 2);
+                let Some((close, alternatives)) = alternatives else {
+                    out.push_str(r"\{");
+                    i += 1;
+                    continue;
+                };
+
+                let starts_alt = at_segment_start(i);
+                let ends_alt = at_segment_end(close + 1);
+                let translated: Vec<String> = alternatives
+                    .into_iter()
+                    .map(|(alt_start, alt_end)| {
+                        translate(chars, alt_start, alt_end, starts_alt, ends_alt)
+                    })
+                    .collect();
+                out.push_str(&format!("(?:{})", translated.join("|")));
+                i = close + 1;
+            }
+            c => {
+                push_escaped(&mut out, c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Find the `]` that closes the class opened at `open`.
+fn find_class_end(chars: &[char], open: usize, end: usize) -> Option<usize> {
+    let mut i = open + 1;
+    if matches!(chars.get(i), Some('!' | '^')) {
+        i += 1;
+    }
+    // A `]` right after the opening bracket is literal.
+    if chars.get(i) == Some(&']') {
+        i += 1;
+    }
+    while i < end {
+        match chars[i] {
+            '\\' => i += 2,
+            ']' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Translate the contents of a `[...]` class.
+fn translate_class(body: &[char], out: &mut String) {
+    out.push('[');
+    let mut i = 0;
+    if matches!(body.first(), Some('!' | '^')) {
+        // A negated class never matches a path separator.
+        out.push_str("^/");
+        i += 1;
+    }
+    while i < body.len() {
+        match body[i] {
+            '\\' if i + 1 < body.len() => {
+                push_class_escaped(out, body[i + 1]);
+                i += 1;
+            }
+            c @ ('\\' | '[' | ']' | '^') => push_class_escaped(out, c),
+            c => out.push(c),
+        }
+        i += 1;
+    }
+    out.push(']');
+}
+
+/// Find the `}` that closes the brace opened at `open`.
+fn find_brace_end(chars: &[char], open: usize, end: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = open;
+    while i < end {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => {
+                if let Some(close) = find_class_end(chars, i, end) {
+                    i = close;
+                }
+            }
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split `chars[start..end]` at top-level commas.
+fn split_alternatives(chars: &[char], start: usize, end: usize) -> Vec<(usize, usize)> {
+    let mut alternatives = Vec::new();
+    let mut depth = 0;
+    let mut alt_start = start;
+    let mut i = start;
+    while i < end {
+        match chars[i] {
+            '\\' => i += 1,
+            '[' => {
+                if let Some(close) = find_class_end(chars, i, end) {
+                    i = close;
+                }
+            }
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push((alt_start, i));
+                alt_start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    alternatives.push((alt_start, end));
+    alternatives
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Matcher;
+    use crate::types::FilePattern;
+    use serde::Deserialize;
+
+    /// A glob and the paths it is checked against.
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ConformanceCase {
+        glob: String,
+        ignore_case: bool,
+        /// Pairs of path and whether `glob()` matches it.
+        cases: Vec<(String, bool)>,
+    }
+
+    /// The same fixture is checked against the TypeScript `glob()` function
+    /// by `test/glob.test.ts`.
+    const CONFORMANCE: &str = include_str!("../../../test/data/glob-conformance.json");
+
+    #[test]
+    fn matches_typescript_globs() {
+        let suite: Vec<ConformanceCase> =
+            serde_json::from_str(CONFORMANCE).expect("conformance fixture is valid JSON");
+
+        let mut failures = Vec::new();
+        for case in suite {
+            let pattern = FilePattern::Glob {
+                glob: case.glob.clone(),
+                ignore_case: case.ignore_case,
+            };
+            let matcher = match Matcher::new(&pattern) {
+                Ok(matcher) => matcher,
+                Err(err) => {
+                    failures.push(format!("{}: {err:#}", case.glob));
+                    continue;
+                }
+            };
+            for (path, expected) in &case.cases {
+                if matcher.is_match(path) != *expected {
+                    failures.push(format!("{} on {path:?}: expected {expected}", case.glob));
+                }
+            }
+        }
+
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
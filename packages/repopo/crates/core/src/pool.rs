@@ -0,0 +1,259 @@
+use anyhow::{Context, Result};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::ipc::Sidecar;
+use crate::types::{HandlerResult, LoadConfigResponse};
+
+/// The kind of batch call a task makes against a sidecar.
+#[derive(Debug, Clone, Copy)]
+pub enum BatchKind {
+    /// Run the policy handler, optionally asking it to resolve in place.
+    Handler { resolve: bool },
+    /// Run the policy's standalone resolver.
+    Resolver,
+}
+
+/// A unit of work dispatched to one sidecar in the pool.
+#[derive(Debug)]
+pub struct BatchTask {
+    /// Index of the policy in the load_config response array.
+    pub policy_id: usize,
+
+    /// Repo-relative paths to the files in this chunk.
+    pub files: Vec<String>,
+
+    /// Which batch call to make.
+    pub kind: BatchKind,
+}
+
+/// The outcome of a single [`BatchTask`].
+pub struct BatchOutput {
+    /// Index of the policy the task ran.
+    pub policy_id: usize,
+
+    /// Which batch call the task made.
+    pub kind: BatchKind,
+
+    /// Per-file results, or the IPC error that aborted the task.
+    pub results: Result<Vec<(String, HandlerResult)>>,
+
+    /// Wall time spent waiting on the sidecar for this task.
+    pub elapsed: Duration,
+}
+
+/// A pool of sidecar processes that run policy batches in parallel.
+///
+/// The first sidecar is spawned eagerly and used to load the config.
+/// Additional sidecars are spawned lazily, the first time there is enough
+/// work to keep them busy, so small runs only pay for one JS runtime.
+pub struct SidecarPool {
+    runtime: String,
+    sidecar_path: String,
+    git_root: String,
+    config_path: Option<String>,
+    max_workers: usize,
+    workers: Vec<Sidecar>,
+}
+
+impl SidecarPool {
+    /// Spawn a pool that will grow to at most `max_workers` sidecars.
+    pub fn spawn(
+        runtime: &str,
+        sidecar_path: &str,
+        git_root: &str,
+        max_workers: usize,
+    ) -> Result<Self> {
+        let primary = Sidecar::spawn(runtime, sidecar_path, git_root)?;
+
+        Ok(Self {
+            runtime: runtime.to_string(),
+            sidecar_path: sidecar_path.to_string(),
+            git_root: git_root.to_string(),
+            config_path: None,
+            max_workers: max_workers.max(1),
+            workers: vec![primary],
+        })
+    }
+
+    /// The maximum number of sidecars this pool will run.
+    pub fn max_workers(&self) -> usize {
+        self.max_workers
+    }
+
+    /// Load the repopo configuration on every sidecar in the pool.
+    ///
+    /// Sidecars spawned later load the same config before taking work, so
+    /// policy indices stay consistent across the pool. Calling this again
+    /// reloads the config, e.g. after the config file changed.
+    pub fn load_config(&mut self, config_path: Option<&str>) -> Result<LoadConfigResponse> {
+        self.config_path = config_path.map(String::from);
+        if self.workers.is_empty() {
+            let primary = Sidecar::spawn(&self.runtime, &self.sidecar_path, &self.git_root)?;
+            self.workers.push(primary);
+        }
+
+        let (primary, others) = self
+            .workers
+            .split_first_mut()
+            .context("Sidecar pool has no workers")?;
+        let config = primary.load_config(config_path, &self.git_root)?;
+
+        let git_root = &self.git_root;
+        thread::scope(|s| {
+            let handles: Vec<_> = others
+                .iter_mut()
+                .map(|sidecar| s.spawn(move || sidecar.load_config(config_path, git_root)))
+                .collect();
+
+            handles.into_iter().try_for_each(|h| {
+                h.join()
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("Config reload thread panicked")))
+                    .map(|_| ())
+            })
+        })?;
+
+        Ok(config)
+    }
+
+    /// Spawn sidecars in parallel until the pool has `target` workers.
+    fn grow(&mut self, target: usize) -> Result<()> {
+        let target = target.min(self.max_workers);
+        if self.workers.len() >= target {
+            return Ok(());
+        }
+
+        let spawned: Vec<Result<Sidecar>> = thread::scope(|s| {
+            let handles: Vec<_> = (self.workers.len()..target)
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut sidecar =
+                            Sidecar::spawn(&self.runtime, &self.sidecar_path, &self.git_root)?;
+                        sidecar.load_config(self.config_path.as_deref(), &self.git_root)?;
+                        Ok(sidecar)
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|h| {
+                    h.join()
+                        .unwrap_or_else(|_| Err(anyhow::anyhow!("Sidecar spawn thread panicked")))
+                })
+                .collect()
+        });
+
+        for sidecar in spawned {
+            self.workers
+                .push(sidecar.context("Failed to start additional sidecar worker")?);
+        }
+
+        Ok(())
+    }
+
+    /// Run a set of batch tasks across the pool.
+    ///
+    /// Tasks are pulled from a shared queue by one thread per sidecar.
+    /// Outputs are returned in the same order as `tasks`, regardless of
+    /// which worker finished first. A sidecar whose request fails is
+    /// stopped and removed from the pool, and a new one is spawned the next
+    /// time there is work for it.
+    pub fn run_batches(&mut self, tasks: Vec<BatchTask>) -> Result<Vec<BatchOutput>> {
+        if tasks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.grow(tasks.len())?;
+
+        let task_count = tasks.len();
+        let queue = Mutex::new(tasks.into_iter().enumerate());
+        let outputs: Mutex<Vec<(usize, BatchOutput)>> = Mutex::new(Vec::with_capacity(task_count));
+        let active = task_count.min(self.workers.len());
+
+        let failed: Vec<bool> = thread::scope(|s| {
+            let handles: Vec<_> = self
+                .workers
+                .iter_mut()
+                .take(active)
+                .map(|sidecar| {
+                    let queue = &queue;
+                    let outputs = &outputs;
+                    s.spawn(move || {
+                        loop {
+                            let next = queue.lock().expect("batch queue poisoned").next();
+                            let Some((index, task)) = next else {
+                                break;
+                            };
+
+                            let start = Instant::now();
+                            let results = match task.kind {
+                                BatchKind::Handler { resolve } => {
+                                    sidecar.run_handler_batch(task.policy_id, &task.files, resolve)
+                                }
+                                BatchKind::Resolver => {
+                                    sidecar.run_resolver_batch(task.policy_id, &task.files)
+                                }
+                            };
+                            let failed = results.is_err();
+
+                            outputs.lock().expect("batch outputs poisoned").push((
+                                index,
+                                BatchOutput {
+                                    policy_id: task.policy_id,
+                                    kind: task.kind,
+                                    results,
+                                    elapsed: start.elapsed(),
+                                },
+                            ));
+
+                            // A failed request leaves the IPC stream in an unknown
+                            // state, so this worker stops taking new tasks.
+                            if failed {
+                                return true;
+                            }
+                        }
+                        false
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap_or(true))
+                .collect()
+        });
+
+        let mut failed = failed.into_iter();
+        self.workers.retain_mut(|sidecar| {
+            let keep = !failed.next().unwrap_or(false);
+            if !keep {
+                sidecar.kill();
+            }
+            keep
+        });
+
+        let mut outputs = outputs.into_inner().expect("batch outputs poisoned");
+        if outputs.len() != task_count {
+            // Every worker bailed out after an error before the queue drained.
+            let err = outputs
+                .into_iter()
+                .find_map(|(_, output)| output.results.err());
+            return Err(err.unwrap_or_else(|| {
+                anyhow::anyhow!("Sidecar pool finished without running every batch")
+            }));
+        }
+
+        outputs.sort_by_key(|(index, _)| *index);
+        Ok(outputs.into_iter().map(|(_, output)| output).collect())
+    }
+
+    /// Shut down every sidecar in the pool.
+    pub fn shutdown(&mut self) -> Result<()> {
+        for sidecar in &mut self.workers {
+            sidecar.shutdown()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,392 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::time::Duration;
+
+use crate::baseline::BaselineSummary;
+use crate::engine::PerfStats;
+use crate::junit::write_junit;
+use crate::sarif::write_sarif;
+use crate::types::{HandlerResult, Severity};
+
+/// Output format for check results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored, human-readable text on stderr.
+    Text,
+    /// A single JSON document on stdout.
+    Json,
+    /// A SARIF 2.1.0 log on stdout, for code scanning tools.
+    Sarif,
+    /// JUnit XML on stdout, for CI test dashboards.
+    Junit,
+}
+
+/// What happened to a single file under a single policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    /// The file passed the policy check.
+    Pass,
+    /// The file failed and was not fixed.
+    Fail,
+    /// The file failed and was fixed by the handler or resolver.
+    Fixed,
+    /// A fix was attempted but did not succeed.
+    FixFailed,
+    /// The file failed with a violation recorded in the baseline.
+    Baselined,
+    /// The file failed a policy that a directive in it disables.
+    Suppressed,
+}
+
+impl Outcome {
+    /// Returns true if this outcome should fail the run.
+    pub fn is_failure(self) -> bool {
+        matches!(self, Outcome::Fail | Outcome::FixFailed)
+    }
+}
+
+/// The result of one policy on one file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileResult {
+    /// Repo-relative path to the file.
+    pub file: String,
+
+    /// The classified outcome.
+    pub outcome: Outcome,
+
+    /// The severity of a failure, from the result or the policy's config.
+    #[serde(default)]
+    pub severity: Severity,
+
+    /// The raw handler or resolver result the outcome was derived from.
+    pub result: HandlerResult,
+}
+
+/// All results for a single policy, in the order they were produced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyReport {
+    /// The name of the policy.
+    pub name: String,
+
+    /// A description of what the policy checks.
+    pub description: String,
+
+    /// Results for every file the policy ran on, including passes.
+    pub results: Vec<FileResult>,
+}
+
+/// The collected results of a check run.
+#[derive(Serialize, Deserialize)]
+pub struct CheckReport {
+    /// Per-policy results, in config order. Policies that matched no
+    /// files are included with no results.
+    pub policies: Vec<PolicyReport>,
+
+    /// Timing information for the run.
+    pub stats: PerfStats,
+
+    /// How the baseline changed the results, if one was applied.
+    #[serde(default)]
+    pub baseline: Option<BaselineSummary>,
+}
+
+impl CheckReport {
+    /// Returns true if no policy reported an error and there are no more
+    /// than `max_warnings` warnings, if set.
+    pub fn success(&self, max_warnings: Option<usize>) -> bool {
+        self.violations(Severity::Error) == 0
+            && max_warnings.is_none_or(|max| self.violations(Severity::Warning) <= max)
+    }
+
+    /// Count failures of the given severity across all policies.
+    pub fn violations(&self, severity: Severity) -> usize {
+        self.policies
+            .iter()
+            .flat_map(|p| &p.results)
+            .filter(|r| r.outcome.is_failure() && r.severity == severity)
+            .count()
+    }
+
+    /// Count results with the given outcome across all policies.
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.policies
+            .iter()
+            .flat_map(|p| &p.results)
+            .filter(|r| r.outcome == outcome)
+            .count()
+    }
+
+    /// Write the report in the requested format. `max_warnings` is the
+    /// warning limit the run is held to.
+    pub fn write(
+        &self,
+        format: OutputFormat,
+        quiet: bool,
+        max_warnings: Option<usize>,
+    ) -> Result<()> {
+        match format {
+            OutputFormat::Text => {
+                self.write_text(quiet, max_warnings);
+                Ok(())
+            }
+            OutputFormat::Json => self.write_json(&mut std::io::stdout().lock(), max_warnings),
+            OutputFormat::Sarif => write_sarif(self, &mut std::io::stdout().lock()),
+            OutputFormat::Junit => write_junit(self, &mut std::io::stdout().lock()),
+        }
+    }
+
+    /// Print human-readable results to stderr.
+    fn write_text(&self, quiet: bool, max_warnings: Option<usize>) {
+        for policy in &self.policies {
+            for r in &policy.results {
+                match r.outcome {
+                    Outcome::Pass | Outcome::Baselined | Outcome::Suppressed => {}
+                    Outcome::Fixed => {
+                        if !quiet {
+                            eprintln!(
+                                "Resolved {} policy failure for file: {}",
+                                policy.name, r.file
+                            );
+                        }
+                    }
+                    Outcome::FixFailed => {
+                        let msg = format!(
+                            "Error fixing {} policy {} in {}",
+                            policy.name,
+                            failure_noun(r.severity),
+                            r.file
+                        );
+                        eprintln!("{}", msg.yellow());
+                        if let Some(err) = r.result.error_message() {
+                            eprintln!("\t{err}");
+                        }
+                    }
+                    Outcome::Fail => {
+                        let fixable_tag = if r.result.is_fixable() {
+                            " (autofixable)".green().to_string()
+                        } else {
+                            String::new()
+                        };
+                        let noun = failure_noun(r.severity);
+                        let noun = match r.severity {
+                            Severity::Error => noun.normal(),
+                            Severity::Warning => noun.yellow(),
+                            Severity::Info => noun.cyan(),
+                        };
+                        let msg = format!(
+                            "'{}' policy {noun}{fixable_tag}: {}",
+                            policy.name.bold(),
+                            r.file
+                        );
+                        eprintln!("{}", msg);
+                        if let Some(err) = r.result.error_message() {
+                            eprintln!("\t{err}");
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(baseline) = &self.baseline
+            && !quiet
+        {
+            baseline.write_text();
+        }
+
+        let warnings = self.violations(Severity::Warning);
+        if let Some(max) = max_warnings
+            && warnings > max
+        {
+            let msg = format!("Too many warnings: {warnings} (limit {max}).");
+            eprintln!("{}", msg.red());
+        }
+    }
+
+    /// Write the report as a single JSON document.
+    fn write_json(&self, out: &mut impl Write, max_warnings: Option<usize>) -> Result<()> {
+        let results: Vec<JsonResult> = self
+            .policies
+            .iter()
+            .flat_map(|p| p.results.iter().map(move |r| (p, r)))
+            .filter(|(_, r)| r.outcome != Outcome::Pass)
+            .map(|(p, r)| {
+                let (fixable, fixed) = match &r.result {
+                    HandlerResult::Failure(err) => (err.fixable, err.fixed),
+                    HandlerResult::Pass(_) => (None, None),
+                };
+                JsonResult {
+                    policy: &p.name,
+                    file: &r.file,
+                    outcome: r.outcome,
+                    severity: r.severity,
+                    error: r.result.error_message(),
+                    fixable,
+                    fixed,
+                    manual_fix: r.result.manual_fix(),
+                }
+            })
+            .collect();
+
+        let policies: Vec<JsonPolicy> = self
+            .policies
+            .iter()
+            .map(|p| JsonPolicy {
+                name: &p.name,
+                description: &p.description,
+                checked: p.results.len(),
+                failed: p.results.iter().filter(|r| r.outcome.is_failure()).count(),
+            })
+            .collect();
+
+        let doc = JsonReport {
+            success: self.success(max_warnings),
+            summary: JsonSummary {
+                files: self.stats.total_files,
+                policies: self.policies.len(),
+                checked: self.policies.iter().map(|p| p.results.len()).sum(),
+                passed: self.count(Outcome::Pass),
+                failed: self.count(Outcome::Fail),
+                fixed: self.count(Outcome::Fixed),
+                fix_failed: self.count(Outcome::FixFailed),
+                baselined: self.count(Outcome::Baselined),
+                suppressed: self.count(Outcome::Suppressed),
+                errors: self.violations(Severity::Error),
+                warnings: self.violations(Severity::Warning),
+                info: self.violations(Severity::Info),
+            },
+            policies,
+            results,
+            baseline: self.baseline.as_ref(),
+            perf: JsonPerf {
+                total_files: self.stats.total_files,
+                handler_times_ms: to_millis(&self.stats.handler_times),
+                resolver_times_ms: to_millis(&self.stats.resolver_times),
+                cache_hits: self.stats.cache_hits,
+                cache_misses: self.stats.cache_misses,
+            },
+        };
+
+        serde_json::to_writer_pretty(&mut *out, &doc).context("Failed to write JSON report")?;
+        writeln!(out).context("Failed to write JSON report")?;
+        Ok(())
+    }
+}
+
+impl BaselineSummary {
+    /// Print how many violations were suppressed and which entries are
+    /// fixed to stderr.
+    fn write_text(&self) {
+        if self.suppressed > 0 {
+            eprintln!(
+                "{}",
+                format!(
+                    "{} known violations suppressed by {}.",
+                    self.suppressed, self.file
+                )
+                .dimmed()
+            );
+        }
+        if self.fixed.is_empty() {
+            return;
+        }
+
+        for entry in &self.fixed {
+            eprintln!(
+                "'{}' violation in {} is fixed but still in the baseline.",
+                entry.policy.bold(),
+                entry.file
+            );
+        }
+        eprintln!(
+            "{}",
+            "Run `repopo-core baseline create` to remove fixed entries.".yellow()
+        );
+    }
+}
+
+/// How text output refers to a failure of the given severity.
+fn failure_noun(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "failure",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+/// Convert per-policy durations into a sorted map of milliseconds.
+fn to_millis(times: &HashMap<String, Duration>) -> BTreeMap<&str, f64> {
+    times
+        .iter()
+        .map(|(name, dur)| (name.as_str(), dur.as_secs_f64() * 1000.0))
+        .collect()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonReport<'a> {
+    success: bool,
+    summary: JsonSummary,
+    policies: Vec<JsonPolicy<'a>>,
+    results: Vec<JsonResult<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    baseline: Option<&'a BaselineSummary>,
+    perf: JsonPerf<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonSummary {
+    files: usize,
+    policies: usize,
+    checked: usize,
+    passed: usize,
+    failed: usize,
+    fixed: usize,
+    fix_failed: usize,
+    baselined: usize,
+    suppressed: usize,
+    errors: usize,
+    warnings: usize,
+    info: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonPolicy<'a> {
+    name: &'a str,
+    description: &'a str,
+    checked: usize,
+    failed: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonResult<'a> {
+    policy: &'a str,
+    file: &'a str,
+    outcome: Outcome,
+    severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fixable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fixed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    manual_fix: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonPerf<'a> {
+    total_files: usize,
+    handler_times_ms: BTreeMap<&'a str, f64>,
+    resolver_times_ms: BTreeMap<&'a str, f64>,
+    cache_hits: usize,
+    cache_misses: usize,
+}
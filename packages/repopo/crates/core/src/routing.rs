@@ -0,0 +1,366 @@
+//! Routing of files to the policies that apply to them.
+//!
+//! Every match and exclude pattern in the config that the `regex` crate can
+//! run is combined into one [`RegexSet`], so each file is tested against all
+//! of them in a single pass. Patterns that need backtracking and negated
+//! globs cannot join the set and are tested one at a time, only when their
+//! result can still change the route.
+//!
+//! With nested config files, each file is routed only to the policies in
+//! effect in the deepest directory with a config that contains it.
+
+use anyhow::{Context, Result};
+use regex::RegexSet;
+
+use crate::pattern::Matcher;
+use crate::types::{LoadConfigResponse, PolicyMeta};
+
+/// The role of a pattern in the config.
+#[derive(Debug, Clone, Copy)]
+enum PatternRef {
+    /// A global exclude, by index.
+    GlobalExclude(usize),
+    /// An exclude added by a nested config, by scope and exclude index.
+    ScopeExclude(usize, usize),
+    /// The match pattern of a policy.
+    Match(usize),
+    /// An exclude pattern of a policy, by policy and exclude index.
+    Exclude(usize, usize),
+}
+
+/// Where a single file is routed.
+#[derive(Debug, Default)]
+pub struct Route {
+    /// The file matches a global exclude, so no policy applies.
+    pub excluded_globally: bool,
+
+    /// Indexes of the policies that apply to the file.
+    pub policies: Vec<usize>,
+
+    /// Indexes of the policies whose match pattern matches the file but
+    /// that exclude it.
+    pub excluded_from: Vec<usize>,
+}
+
+/// Where a list of files is routed, grouped by policy.
+#[derive(Debug, Default)]
+pub struct RoutingTable {
+    /// The files each policy applies to, by policy index, in input order.
+    pub files_by_policy: Vec<Vec<String>>,
+
+    /// The files excluded from each policy by its own excludes, by policy
+    /// index.
+    pub excluded_by_policy: Vec<Vec<String>>,
+
+    /// The files matching a global exclude.
+    pub excluded_globally: Vec<String>,
+}
+
+/// Which patterns match a single file, for explaining its route.
+#[derive(Debug)]
+pub struct Explanation {
+    /// Index of the config scope the file is in, if the config has scopes.
+    pub scope: Option<usize>,
+
+    /// Indexes of the global excludes that match the file.
+    pub global_excludes: Vec<usize>,
+
+    /// Indexes of the excludes of the file's scope that match the file.
+    pub scope_excludes: Vec<usize>,
+
+    /// How each policy's patterns match the file, by policy index.
+    pub policies: Vec<PolicyExplanation>,
+}
+
+/// Which of a policy's patterns match a single file.
+#[derive(Debug, Default, Clone)]
+pub struct PolicyExplanation {
+    /// The policy is in effect in the file's scope.
+    pub in_effect: bool,
+
+    /// The policy's match pattern matches the file.
+    pub matched: bool,
+
+    /// Indexes of the policy's excludes that match the file.
+    pub excludes: Vec<usize>,
+}
+
+/// Routes files to policies in one pass over the combined patterns.
+pub struct Router {
+    policy_count: usize,
+    patterns: Vec<(PatternRef, Matcher)>,
+
+    /// The directory of each config scope, and which policies are in
+    /// effect in it, by policy index.
+    scopes: Vec<(String, Vec<bool>)>,
+
+    /// The patterns combined into `set`, by index into `patterns`.
+    set: RegexSet,
+    set_patterns: Vec<usize>,
+
+    /// The patterns tested one at a time, by index into `patterns`.
+    others: Vec<usize>,
+}
+
+impl Router {
+    /// Compile the match and exclude patterns of a config.
+    pub fn new(config: &LoadConfigResponse) -> Result<Self> {
+        let policies: &[PolicyMeta] = &config.policies;
+        let mut patterns: Vec<(PatternRef, Matcher)> = Vec::new();
+
+        for (index, pattern) in config.exclude_files.iter().enumerate() {
+            let matcher =
+                Matcher::new(pattern).context("Failed to compile global exclude patterns")?;
+            patterns.push((PatternRef::GlobalExclude(index), matcher));
+        }
+
+        let mut scopes = Vec::with_capacity(config.scopes.len());
+        for (scope_id, scope) in config.scopes.iter().enumerate() {
+            for (index, pattern) in scope.exclude_files.iter().enumerate() {
+                let matcher = Matcher::new(pattern).with_context(|| {
+                    format!(
+                        "Failed to compile exclude patterns for the config in '{}'",
+                        scope.directory
+                    )
+                })?;
+                patterns.push((PatternRef::ScopeExclude(scope_id, index), matcher));
+            }
+
+            let mut in_effect = vec![false; policies.len()];
+            for &id in &scope.policies {
+                *in_effect.get_mut(id).with_context(|| {
+                    format!(
+                        "Config in '{}' refers to unknown policy {id}",
+                        scope.directory
+                    )
+                })? = true;
+            }
+            scopes.push((scope.directory.clone(), in_effect));
+        }
+
+        for (policy_id, meta) in policies.iter().enumerate() {
+            patterns.push((
+                PatternRef::Match(policy_id),
+                Matcher::new(&meta.match_pattern)?,
+            ));
+            for (index, pattern) in meta.exclude_files.iter().enumerate() {
+                let matcher = Matcher::new(pattern).with_context(|| {
+                    format!(
+                        "Failed to compile exclude patterns for policy '{}'",
+                        meta.name
+                    )
+                })?;
+                patterns.push((PatternRef::Exclude(policy_id, index), matcher));
+            }
+        }
+
+        let (set_patterns, others): (Vec<usize>, Vec<usize>) =
+            (0..patterns.len()).partition(|&i| patterns[i].1.set_pattern().is_some());
+        let set = RegexSet::new(
+            set_patterns
+                .iter()
+                .filter_map(|&i| patterns[i].1.set_pattern()),
+        )
+        .context("Failed to combine file patterns")?;
+
+        Ok(Self {
+            policy_count: policies.len(),
+            patterns,
+            scopes,
+            set,
+            set_patterns,
+            others,
+        })
+    }
+
+    /// The config scope a repo-relative path is in: the one for the
+    /// deepest directory that contains it.
+    fn scope_for(&self, file: &str) -> Option<usize> {
+        self.scopes
+            .iter()
+            .enumerate()
+            .filter(|(_, (directory, _))| {
+                directory.is_empty()
+                    || file
+                        .strip_prefix(directory.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|(_, (directory, _))| directory.len())
+            .map(|(scope_id, _)| scope_id)
+    }
+
+    /// Returns true if a policy is in effect in a scope.
+    fn in_effect(&self, scope: Option<usize>, id: usize) -> bool {
+        scope.is_none_or(|scope| self.scopes[scope].1[id])
+    }
+
+    /// Returns true if a pattern excludes files in a scope from all
+    /// policies.
+    fn excludes_all(pattern: PatternRef, scope: Option<usize>) -> bool {
+        match pattern {
+            PatternRef::GlobalExclude(_) => true,
+            PatternRef::ScopeExclude(scope_id, _) => scope == Some(scope_id),
+            PatternRef::Match(_) | PatternRef::Exclude(..) => false,
+        }
+    }
+
+    /// Route a single repo-relative path.
+    pub fn route(&self, file: &str) -> Route {
+        let scope = self.scope_for(file);
+        let mut excluded_globally = false;
+        let mut matched = vec![false; self.policy_count];
+        let mut excluded = vec![false; self.policy_count];
+
+        for index in self.set.matches(file).iter() {
+            match self.patterns[self.set_patterns[index]].0 {
+                PatternRef::Match(id) => matched[id] = self.in_effect(scope, id),
+                PatternRef::Exclude(id, _) => excluded[id] = true,
+                pattern => excluded_globally |= Self::excludes_all(pattern, scope),
+            }
+        }
+
+        let others = || self.others.iter().map(|&i| &self.patterns[i]);
+
+        // Global excludes first, since they make everything else moot.
+        for (pattern, matcher) in others() {
+            if Self::excludes_all(*pattern, scope) && !excluded_globally && matcher.is_match(file) {
+                excluded_globally = true;
+            }
+        }
+        if excluded_globally {
+            return Route {
+                excluded_globally,
+                ..Route::default()
+            };
+        }
+
+        for (pattern, matcher) in others() {
+            if let PatternRef::Match(id) = *pattern
+                && !matched[id]
+                && self.in_effect(scope, id)
+                && matcher.is_match(file)
+            {
+                matched[id] = true;
+            }
+        }
+        for (pattern, matcher) in others() {
+            if let PatternRef::Exclude(id, _) = *pattern
+                && matched[id]
+                && !excluded[id]
+                && matcher.is_match(file)
+            {
+                excluded[id] = true;
+            }
+        }
+
+        let mut route = Route::default();
+        for id in (0..self.policy_count).filter(|&id| matched[id]) {
+            if excluded[id] {
+                route.excluded_from.push(id);
+            } else {
+                route.policies.push(id);
+            }
+        }
+        route
+    }
+
+    /// Route a list of repo-relative paths.
+    pub fn table(&self, files: &[String]) -> RoutingTable {
+        let mut table = RoutingTable {
+            files_by_policy: vec![Vec::new(); self.policy_count],
+            excluded_by_policy: vec![Vec::new(); self.policy_count],
+            excluded_globally: Vec::new(),
+        };
+
+        for file in files {
+            let route = self.route(file);
+            if route.excluded_globally {
+                table.excluded_globally.push(file.clone());
+                continue;
+            }
+            for id in route.policies {
+                table.files_by_policy[id].push(file.clone());
+            }
+            for id in route.excluded_from {
+                table.excluded_by_policy[id].push(file.clone());
+            }
+        }
+
+        table
+    }
+
+    /// Test every pattern against a single path, without the shortcuts
+    /// [`Router::route`] takes.
+    pub fn explain(&self, file: &str) -> Explanation {
+        let scope = self.scope_for(file);
+        let mut explanation = Explanation {
+            scope,
+            global_excludes: Vec::new(),
+            scope_excludes: Vec::new(),
+            policies: (0..self.policy_count)
+                .map(|id| PolicyExplanation {
+                    in_effect: self.in_effect(scope, id),
+                    ..PolicyExplanation::default()
+                })
+                .collect(),
+        };
+
+        for (pattern, matcher) in &self.patterns {
+            if !matcher.is_match(file) {
+                continue;
+            }
+            match *pattern {
+                PatternRef::GlobalExclude(index) => explanation.global_excludes.push(index),
+                PatternRef::ScopeExclude(scope_id, index) => {
+                    if scope == Some(scope_id) {
+                        explanation.scope_excludes.push(index);
+                    }
+                }
+                PatternRef::Match(id) => explanation.policies[id].matched = true,
+                PatternRef::Exclude(id, index) => explanation.policies[id].excludes.push(index),
+            }
+        }
+
+        explanation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Router;
+    use crate::types::LoadConfigResponse;
+
+    #[test]
+    fn routes_files_to_the_policies_of_their_nested_config() {
+        let text = r#"{ "kind": "regex", "source": "\\.txt$" }"#;
+        let config: LoadConfigResponse = serde_json::from_str(&format!(
+            r#"{{
+                "policies": [
+                    {{ "name": "A", "description": "", "matchPattern": {text} }},
+                    {{ "name": "B", "description": "", "matchPattern": {text} }},
+                    {{ "name": "A", "description": "", "matchPattern": {text}, "scope": "pkg" }}
+                ],
+                "scopes": [
+                    {{ "directory": "", "policies": [0, 1] }},
+                    {{ "directory": "pkg", "policies": [2, 1] }},
+                    {{ "directory": "pkg/inner", "policies": [2],
+                       "excludeFiles": [{{ "kind": "glob", "glob": "**/gen/**" }}] }}
+                ]
+            }}"#
+        ))
+        .unwrap();
+        let router = Router::new(&config).unwrap();
+
+        let cases: [(&str, &[usize]); 6] = [
+            ("a.txt", &[0, 1]),
+            ("pkg/a.txt", &[1, 2]),
+            ("pkgs/a.txt", &[0, 1]),
+            ("pkg/inner/a.txt", &[2]),
+            ("pkg/inner/gen/a.txt", &[]),
+            ("pkg/gen/a.txt", &[1, 2]),
+        ];
+        for (file, expected) in cases {
+            assert_eq!(router.route(file).policies, expected, "{file}");
+        }
+    }
+}
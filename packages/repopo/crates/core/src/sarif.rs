@@ -0,0 +1,215 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::Write;
+
+use crate::report::{CheckReport, Outcome};
+use crate::types::Severity;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const TOOL_NAME: &str = "repopo";
+const TOOL_URI: &str = "https://github.com/tylerbutler/tools-monorepo/tree/main/packages/repopo";
+
+/// Base URI id that result locations are relative to. Code scanning
+/// resolves it to the repository checkout.
+const SRCROOT: &str = "%SRCROOT%";
+
+/// Write a check report as a SARIF 2.1.0 log.
+///
+/// Each policy becomes a rule and each unresolved failure becomes a result
+/// located at the repo-relative file path. Policies are file-level checks,
+/// so every result points at the first line of its file.
+pub fn write_sarif(report: &CheckReport, out: &mut impl Write) -> Result<()> {
+    let rules: Vec<SarifRule> = report
+        .policies
+        .iter()
+        .map(|p| SarifRule {
+            id: &p.name,
+            name: &p.name,
+            short_description: SarifMessage {
+                text: p.description.clone(),
+            },
+        })
+        .collect();
+
+    let results: Vec<SarifResult> = report
+        .policies
+        .iter()
+        .enumerate()
+        .flat_map(|(index, p)| p.results.iter().map(move |r| (index, p, r)))
+        .filter(|(_, _, r)| r.outcome.is_failure())
+        .map(|(index, p, r)| {
+            let text = match (r.outcome, r.result.error_message()) {
+                (Outcome::FixFailed, Some(err)) => format!("Error fixing policy failure: {err}"),
+                (Outcome::FixFailed, None) => "Error fixing policy failure".to_string(),
+                (_, Some(err)) => err,
+                (_, None) => format!("'{}' policy failure", p.name),
+            };
+
+            SarifResult {
+                rule_id: &p.name,
+                rule_index: index,
+                level: match r.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info => "note",
+                },
+                message: SarifMessage { text },
+                locations: vec![SarifLocation {
+                    physical_location: SarifPhysicalLocation {
+                        artifact_location: SarifArtifactLocation {
+                            uri: encode_uri_path(&r.file),
+                            uri_base_id: SRCROOT,
+                        },
+                        region: SarifRegion {
+                            start_line: r.result.line().unwrap_or(1),
+                        },
+                    },
+                }],
+                properties: SarifResultProperties {
+                    autofixable: r.result.is_fixable(),
+                    manual_fix: r.result.manual_fix(),
+                },
+            }
+        })
+        .collect();
+
+    let log = SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![SarifRun {
+            tool: SarifTool {
+                driver: SarifDriver {
+                    name: TOOL_NAME,
+                    information_uri: TOOL_URI,
+                    version: env!("CARGO_PKG_VERSION"),
+                    rules,
+                },
+            },
+            results,
+        }],
+    };
+
+    serde_json::to_writer_pretty(&mut *out, &log).context("Failed to write SARIF report")?;
+    writeln!(out).context("Failed to write SARIF report")?;
+    Ok(())
+}
+
+/// Percent-encode the characters in a repo-relative path that are not
+/// valid in a URI reference. Path separators are kept as-is.
+fn encode_uri_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'/'
+            | b'!'
+            | b'$'
+            | b'&'
+            | b'\''
+            | b'('
+            | b')'
+            | b'*'
+            | b'+'
+            | b','
+            | b';'
+            | b'='
+            | b':'
+            | b'@' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+#[derive(Serialize)]
+struct SarifLog<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'a str,
+    version: &'a str,
+    runs: Vec<SarifRun<'a>>,
+}
+
+#[derive(Serialize)]
+struct SarifRun<'a> {
+    tool: SarifTool<'a>,
+    results: Vec<SarifResult<'a>>,
+}
+
+#[derive(Serialize)]
+struct SarifTool<'a> {
+    driver: SarifDriver<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver<'a> {
+    name: &'a str,
+    information_uri: &'a str,
+    version: &'a str,
+    rules: Vec<SarifRule<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule<'a> {
+    id: &'a str,
+    name: &'a str,
+    short_description: SarifMessage,
+}
+
+#[derive(Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult<'a> {
+    rule_id: &'a str,
+    rule_index: usize,
+    level: &'a str,
+    message: SarifMessage,
+    locations: Vec<SarifLocation>,
+    properties: SarifResultProperties<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    region: SarifRegion,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactLocation {
+    uri: String,
+    uri_base_id: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResultProperties<'a> {
+    autofixable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    manual_fix: Option<&'a str>,
+}
@@ -0,0 +1,325 @@
+//! Inline suppression directives.
+//!
+//! A comment of the form `repopo-disable <Policy>[, <Policy>...] -- reason`
+//! on its own line disables the named policies for the whole file. The
+//! comment syntax depends on the file's extension. JSON has no comments, so
+//! directives for `data.json` go in a `data.json.repopo-disable` file next
+//! to it, one per line without a comment marker.
+//!
+//! A suppressed policy still runs, so that a directive that no longer
+//! suppresses a failure can be reported.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::num::NonZeroUsize;
+use std::panic;
+use std::path::Path;
+use std::thread;
+
+use crate::cache::{self, CheckCache};
+
+/// The keyword that starts a directive.
+const DIRECTIVE: &str = "repopo-disable";
+
+/// Extension of the files that hold directives for JSON files.
+const SIDECAR_EXTENSION: &str = ".repopo-disable";
+
+/// How comments are written in a file type.
+struct CommentStyle {
+    /// Tokens that start a comment running to the end of the line.
+    line: &'static [&'static str],
+
+    /// Tokens that open and close a block comment.
+    block: Option<(&'static str, &'static str)>,
+}
+
+const C_STYLE: CommentStyle = CommentStyle {
+    line: &["//"],
+    block: Some(("/*", "*/")),
+};
+const GLEAM: CommentStyle = CommentStyle {
+    line: &["//"],
+    block: None,
+};
+const HASH: CommentStyle = CommentStyle {
+    line: &["#"],
+    block: None,
+};
+const HTML: CommentStyle = CommentStyle {
+    line: &[],
+    block: Some(("<!--", "-->")),
+};
+/// Lines of a sidecar file are directives without a comment marker.
+const BARE: CommentStyle = CommentStyle {
+    line: &[""],
+    block: None,
+};
+
+/// The comment style of a file, by extension.
+fn comment_style(file: &str) -> Option<&'static CommentStyle> {
+    let extension = file.rsplit_once('.')?.1;
+    match extension {
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" | "rs" => Some(&C_STYLE),
+        "gleam" => Some(&GLEAM),
+        "toml" => Some(&HASH),
+        "html" | "htm" => Some(&HTML),
+        _ => None,
+    }
+}
+
+/// A single policy disabled by a directive.
+#[derive(Debug, Clone)]
+pub struct Suppression {
+    /// Repo-relative path to the file the directive is written in.
+    pub source: String,
+
+    /// 1-based line number of the directive.
+    pub line: usize,
+
+    /// The reason given after `--`, if any.
+    pub reason: Option<String>,
+}
+
+impl Suppression {
+    /// The line of the directive, if it is written in `file` rather than in
+    /// a file next to it.
+    pub fn line_in(&self, file: &str) -> Option<usize> {
+        (self.source == file).then_some(self.line)
+    }
+}
+
+impl std::fmt::Display for Suppression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.source, self.line)?;
+        if let Some(reason) = &self.reason {
+            write!(f, " ({reason})")?;
+        }
+        Ok(())
+    }
+}
+
+/// The directives of a set of files.
+#[derive(Debug, Default)]
+pub struct Suppressions {
+    /// Suppressions by repo-relative file and policy name.
+    by_file: HashMap<String, HashMap<String, Suppression>>,
+}
+
+impl Suppressions {
+    /// Read the directives of repo-relative files, across threads. The
+    /// hash of each file that is read is given to the cache, if any, so
+    /// that the check reads every file once.
+    pub fn scan(git_root: &str, files: &[&String], cache: Option<&mut CheckCache>) -> Result<Self> {
+        let hash = cache.is_some();
+        let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let chunk_size = files.len().div_ceil(workers).max(1);
+        let scanned: Vec<Vec<Scanned>> = thread::scope(|scope| {
+            let handles: Vec<_> = files
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|file| scan_file(git_root, file, hash))
+                            .collect::<Result<Vec<Scanned>>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|payload| panic::resume_unwind(payload))
+                })
+                .collect::<Result<_>>()
+        })?;
+
+        let mut by_file = HashMap::new();
+        let mut cache = cache;
+        for (file, scanned) in files.iter().zip(scanned.into_iter().flatten()) {
+            if let (Some(cache), Some(hash)) = (cache.as_deref_mut(), scanned.hash) {
+                cache.seed(file, hash);
+            }
+            if !scanned.suppressions.is_empty() {
+                by_file.insert(file.to_string(), scanned.suppressions);
+            }
+        }
+        Ok(Self { by_file })
+    }
+
+    /// Read the directives of a repo-relative file from its unsaved `text`.
+    /// The directives of a JSON file are in a file of their own, so they are
+    /// read from disk.
+    pub fn scan_text(git_root: &str, file: &str, text: &str) -> Result<Self> {
+        let suppressions = match comment_style(file) {
+            Some(style) => parse(file, style, text),
+            None => scan_file(git_root, file, false)?.suppressions,
+        };
+        let mut by_file = HashMap::new();
+        if !suppressions.is_empty() {
+            by_file.insert(file.to_string(), suppressions);
+        }
+        Ok(Self { by_file })
+    }
+
+    /// The directive disabling a policy for a file, if any.
+    pub fn get(&self, file: &str, policy: &str) -> Option<&Suppression> {
+        self.by_file.get(file)?.get(policy)
+    }
+
+    /// Every directive, as file, policy name, and suppression.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str, &Suppression)> {
+        self.by_file.iter().flat_map(|(file, suppressions)| {
+            suppressions
+                .iter()
+                .map(move |(policy, s)| (file.as_str(), policy.as_str(), s))
+        })
+    }
+}
+
+/// The directives of a file, and the hash of its content if it was read.
+#[derive(Default)]
+struct Scanned {
+    suppressions: HashMap<String, Suppression>,
+    hash: Option<String>,
+}
+
+/// Read the directives that apply to a repo-relative file, by policy name,
+/// and hash the file's content if `hash` is set and the file is read.
+/// Files without a known comment syntax, and files that cannot be read as
+/// text, have none.
+fn scan_file(git_root: &str, file: &str, hash: bool) -> Result<Scanned> {
+    let (source, style) = if file.ends_with(".json") {
+        (format!("{file}{SIDECAR_EXTENSION}"), &BARE)
+    } else if let Some(style) = comment_style(file) {
+        (file.to_string(), style)
+    } else {
+        return Ok(Scanned::default());
+    };
+
+    let path = Path::new(git_root).join(&source);
+    let bytes = match fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(err) if matches!(err.kind(), ErrorKind::NotFound | ErrorKind::IsADirectory) => {
+            return Ok(Scanned::default());
+        }
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to read {}", path.display()));
+        }
+    };
+    let hash = (hash && source == file).then(|| cache::hash_contents(&bytes));
+    let contents = match String::from_utf8(bytes) {
+        Ok(contents) if contents.contains(DIRECTIVE) => contents,
+        _ => {
+            return Ok(Scanned {
+                hash,
+                ..Scanned::default()
+            });
+        }
+    };
+
+    Ok(Scanned {
+        suppressions: parse(&source, style, &contents),
+        hash,
+    })
+}
+
+/// The directives in the contents of `source`, by policy name.
+fn parse(source: &str, style: &CommentStyle, contents: &str) -> HashMap<String, Suppression> {
+    let mut suppressions = HashMap::new();
+    for (index, line) in contents.lines().enumerate() {
+        let Some((policies, reason)) = parse_line(line.trim(), style) else {
+            continue;
+        };
+        for policy in policies {
+            suppressions
+                .entry(policy.to_string())
+                .or_insert_with(|| Suppression {
+                    source: source.to_string(),
+                    line: index + 1,
+                    reason: reason.map(str::to_string),
+                });
+        }
+    }
+    suppressions
+}
+
+/// Parse a trimmed line that may be a comment holding a directive. Returns
+/// the disabled policy names and the reason.
+fn parse_line<'a>(line: &'a str, style: &CommentStyle) -> Option<(Vec<&'a str>, Option<&'a str>)> {
+    let body = style
+        .line
+        .iter()
+        .find_map(|open| line.strip_prefix(open))
+        .or_else(|| {
+            let (open, close) = style.block?;
+            let body = line.strip_prefix(open)?;
+            Some(body.strip_suffix(close).unwrap_or(body))
+        })?;
+
+    let rest = body.trim_start().strip_prefix(DIRECTIVE)?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let (names, reason) = match rest.split_once("--") {
+        Some((names, reason)) => (names, Some(reason.trim()).filter(|r| !r.is_empty())),
+        None => (rest, None),
+    };
+    let policies: Vec<&str> = names
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|name| !name.is_empty())
+        .collect();
+    if policies.is_empty() {
+        return None;
+    }
+    Some((policies, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BARE, C_STYLE, HASH, HTML, parse_line};
+
+    #[test]
+    fn parses_directives_in_each_comment_style() {
+        let cases = [
+            (
+                C_STYLE,
+                "// repopo-disable A -- reason",
+                Some((vec!["A"], Some("reason"))),
+            ),
+            (
+                C_STYLE,
+                "/* repopo-disable A, B */",
+                Some((vec!["A", "B"], None)),
+            ),
+            (
+                C_STYLE,
+                "//repopo-disable A B -- two words",
+                Some((vec!["A", "B"], Some("two words"))),
+            ),
+            (C_STYLE, "// repopo-disabled A", None),
+            (C_STYLE, "// repopo-disable", None),
+            (C_STYLE, "let x = 1; // repopo-disable A", None),
+            (HASH, "# repopo-disable A --", Some((vec!["A"], None))),
+            (
+                HTML,
+                "<!-- repopo-disable A -- why -->",
+                Some((vec!["A"], Some("why"))),
+            ),
+            (
+                BARE,
+                "repopo-disable A -- why",
+                Some((vec!["A"], Some("why"))),
+            ),
+            (BARE, "# repopo-disable A", None),
+        ];
+
+        for (style, line, expected) in cases {
+            assert_eq!(parse_line(line, &style), expected, "{line}");
+        }
+    }
+}
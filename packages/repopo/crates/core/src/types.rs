@@ -11,21 +11,122 @@ pub struct PolicyMeta {
     /// A description of what the policy checks.
     pub description: String,
 
-    /// The regex pattern string that determines which files this policy applies to.
-    /// This is the source string from the JS RegExp, without delimiters.
-    pub match_pattern: String,
+    /// Tags that group related policies, such as `cargo` or `package`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// The pattern that determines which files this policy applies to.
+    pub match_pattern: FilePattern,
 
-    /// Regex flags from the JS RegExp (e.g. "i" for case-insensitive).
+    /// The severity of this policy's violations, unless a result overrides
+    /// it.
     #[serde(default)]
-    pub match_flags: String,
+    pub severity: Severity,
 
     /// Whether this policy has a resolver (auto-fix capability).
     #[serde(default)]
     pub has_resolver: bool,
 
-    /// Per-policy file exclusion patterns (regex strings).
+    /// Per-policy file exclusion patterns.
+    #[serde(default)]
+    pub exclude_files: Vec<FilePattern>,
+
+    /// Fingerprint of the policy's config, used to invalidate cached results.
     #[serde(default)]
-    pub exclude_files: Vec<String>,
+    pub config_hash: Option<String>,
+
+    /// Whether a pass can be cached by the content of the checked file.
+    /// False for policies that read other files.
+    #[serde(default = "cacheable_by_default")]
+    pub cacheable: bool,
+
+    /// Repo-relative directory of the config file that configured the
+    /// policy. Empty for the root config.
+    #[serde(default)]
+    pub scope: String,
+
+    /// Whether the policy is built into repopo-core and runs in-process
+    /// instead of in the sidecar.
+    #[serde(default)]
+    pub native: bool,
+}
+
+fn cacheable_by_default() -> bool {
+    true
+}
+
+impl PolicyMeta {
+    /// A key that tells apart the instances of a policy configured by
+    /// different config files.
+    pub fn key(&self) -> String {
+        if self.scope.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}@{}", self.name, self.scope)
+        }
+    }
+}
+
+/// A pattern matched against repo-relative file paths.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "lowercase",
+    rename_all_fields = "camelCase"
+)]
+pub enum FilePattern {
+    /// A JS regular expression.
+    Regex {
+        /// The source string from the JS RegExp, without delimiters.
+        source: String,
+
+        /// Regex flags from the JS RegExp (e.g. "i" for case-insensitive).
+        #[serde(default)]
+        flags: String,
+    },
+
+    /// A glob created with `glob()` in the config, e.g. `**/*.ts`.
+    Glob {
+        /// The glob as written in the config.
+        glob: String,
+
+        /// Whether the glob matches case-insensitively.
+        #[serde(default)]
+        ignore_case: bool,
+    },
+}
+
+impl std::fmt::Display for FilePattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilePattern::Regex { source, flags } => write!(f, "/{source}/{flags}"),
+            FilePattern::Glob { glob, .. } => write!(f, "glob({glob})"),
+        }
+    }
+}
+
+/// How serious a policy violation is. Only errors fail a run by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Reported but never fails the run.
+    Info,
+    /// Reported, and fails the run only past `--max-warnings` or with
+    /// `--deny-warnings`.
+    Warning,
+    /// Fails the run.
+    #[default]
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
 }
 
 /// The result of running a policy handler, received from the Node sidecar.
@@ -39,7 +140,7 @@ pub enum HandlerResult {
 }
 
 /// A policy error result from a handler.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PolicyErrorResult {
     /// The error message.
@@ -64,6 +165,14 @@ pub struct PolicyErrorResult {
 
     /// Instructions for manual fix.
     pub manual_fix: Option<String>,
+
+    /// Overrides the severity configured for the policy.
+    #[serde(default)]
+    pub severity: Option<Severity>,
+
+    /// The 1-based line of the violation, if the policy knows it.
+    #[serde(default)]
+    pub line: Option<usize>,
 }
 
 impl HandlerResult {
@@ -116,6 +225,14 @@ impl HandlerResult {
         }
     }
 
+    /// The severity of the result, given the policy's configured severity.
+    pub fn severity(&self, policy_severity: Severity) -> Severity {
+        match self {
+            HandlerResult::Failure(err) => err.severity.unwrap_or(policy_severity),
+            HandlerResult::Pass(_) => policy_severity,
+        }
+    }
+
     /// Get manual fix instructions.
     pub fn manual_fix(&self) -> Option<&str> {
         match self {
@@ -123,6 +240,14 @@ impl HandlerResult {
             _ => None,
         }
     }
+
+    /// The 1-based line of the violation, if the policy gave one.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            HandlerResult::Failure(err) => err.line,
+            _ => None,
+        }
+    }
 }
 
 /// IPC request sent from Rust to the Node sidecar.
@@ -219,6 +344,7 @@ pub struct RunResolverBatchParams {
 /// A single result item within a batch response.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
 pub struct BatchResultItem {
     /// The file this result corresponds to.
     pub file: String,
@@ -230,6 +356,7 @@ pub struct BatchResultItem {
 /// Response payload for batch handler/resolver calls.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
 pub struct BatchResponse {
     /// Results for each file in the batch.
     pub results: Vec<BatchResultItem>,
@@ -256,6 +383,14 @@ pub struct CompactBatchFailureItem {
 
     /// Instructions for manual fix.
     pub manual_fix: Option<String>,
+
+    /// Overrides the severity configured for the policy.
+    #[serde(default)]
+    pub severity: Option<Severity>,
+
+    /// The 1-based line of the violation, if the policy knows it.
+    #[serde(default)]
+    pub line: Option<usize>,
 }
 
 /// Compact response payload for batch handler/resolver calls.
@@ -291,7 +426,99 @@ pub struct LoadConfigResponse {
     /// Metadata for all configured policies.
     pub policies: Vec<PolicyMeta>,
 
-    /// Global file exclusion patterns (regex strings).
+    /// Global file exclusion patterns.
+    #[serde(default)]
+    pub exclude_files: Vec<FilePattern>,
+
+    /// Absolute path to the config file that was loaded, if any.
+    #[serde(default)]
+    pub config_file: Option<String>,
+
+    /// Version of the repopo package that provides the sidecar and policies.
+    #[serde(default)]
+    pub version: Option<String>,
+
+    /// The policies in effect for the repo root and for each directory with
+    /// a nested config file. Empty if the sidecar does not support nested
+    /// configs, in which case every policy is in effect everywhere.
+    #[serde(default)]
+    pub scopes: Vec<ConfigScope>,
+
+    /// The native policies the configs enable. [`crate::engine::PolicySet`]
+    /// moves them into `policies`.
+    #[serde(default)]
+    pub native_policies: Vec<NativePolicyConfig>,
+
+    /// Absolute paths to the local modules the config files import,
+    /// directly or through each other. Editing one changes the config.
+    #[serde(default)]
+    pub config_dependencies: Vec<String>,
+}
+
+impl LoadConfigResponse {
+    /// Absolute paths to the root config file and every nested one.
+    pub fn config_files(&self) -> Vec<&str> {
+        let nested = self
+            .scopes
+            .iter()
+            .filter(|scope| !scope.directory.is_empty())
+            .filter_map(|scope| scope.config_file.as_deref());
+        self.config_file
+            .as_deref()
+            .into_iter()
+            .chain(nested)
+            .collect()
+    }
+}
+
+/// The policies in effect for the files under a directory, after a nested
+/// config file extends the config of the directories above it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigScope {
+    /// Repo-relative directory the config applies to. Empty for the root.
+    pub directory: String,
+
+    /// Absolute path to the config file, if any.
+    #[serde(default)]
+    pub config_file: Option<String>,
+
+    /// Indexes of the policies in effect, into the load_config response
+    /// array.
+    pub policies: Vec<usize>,
+
+    /// Indexes of the native policies in effect, into `native_policies` of
+    /// the load_config response.
+    #[serde(default)]
+    pub native_policies: Vec<usize>,
+
+    /// File exclusion patterns added by the nested configs, on top of the
+    /// root config's global excludes.
+    #[serde(default)]
+    pub exclude_files: Vec<FilePattern>,
+}
+
+/// A native policy enabled by a config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NativePolicyConfig {
+    /// The name of the native policy.
+    pub name: String,
+
+    /// The policy's settings, as written in the config.
+    #[serde(default)]
+    pub config: serde_json::Value,
+
+    /// Per-policy file exclusion patterns.
+    #[serde(default)]
+    pub exclude_files: Vec<FilePattern>,
+
+    /// The severity of the policy's violations.
+    #[serde(default)]
+    pub severity: Severity,
+
+    /// Repo-relative directory of the config file that enabled the policy.
+    /// Empty for the root config.
     #[serde(default)]
-    pub exclude_files: Vec<String>,
+    pub scope: String,
 }
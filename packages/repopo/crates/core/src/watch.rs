@@ -0,0 +1,432 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::engine::{self, CheckOptions, PerfStats, PolicySet};
+use crate::files;
+use crate::filter::PolicyFilter;
+use crate::pool::SidecarPool;
+use crate::report::{CheckReport, FileResult, OutputFormat};
+
+/// How long to keep collecting file events after the first one before
+/// re-running policies. Editors often write a file in several steps.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// The current set of violations, keyed by policy name and file. A policy
+/// can report several results for one file, such as unused suppressions.
+type Violations = BTreeMap<(String, String), Vec<FileResult>>;
+
+/// Watch the working tree and re-run affected policies on every change.
+///
+/// The sidecar pool and compiled policies stay alive between runs. Each
+/// change only re-checks the changed files, so only the policies whose
+/// patterns match them are sent to the sidecar. Policies that read files
+/// other than the one being checked (`cacheable: false`) can be affected by
+/// any change, so they re-check every file on every change. A change to a
+/// config file reloads the config and re-checks the whole repository.
+///
+/// `.git` and ignored directories such as `node_modules` and `target` are
+/// not watched. The list of files is read from git once and then kept up to
+/// date from the events, except that a change to a `.gitignore` reads it
+/// again.
+pub fn run(pool: &mut SidecarPool, git_root: &str, options: &CheckOptions) -> Result<()> {
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to start file watcher")?;
+    let mut tree = Tree::load(&mut watcher, git_root)?;
+
+    let mut policies = PolicySet::load(pool, options.config_path, options.verbose)?;
+    let mut filters = Filters::new(&policies, options.filter);
+    let mut violations = Violations::new();
+    check_all(pool, &policies, &tree, git_root, options, &mut violations)?;
+    render(&policies, &violations)?;
+
+    loop {
+        let first = rx.recv().context("File watcher stopped unexpectedly")?;
+        let mut changed = BTreeSet::new();
+        collect_paths(first, git_root, &tree, &mut changed);
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            collect_paths(event, git_root, &tree, &mut changed);
+        }
+
+        if changed.is_empty() {
+            continue;
+        }
+
+        if options.verbose {
+            for file in &changed {
+                eprintln!("Changed: {file}");
+            }
+        }
+
+        // A new config file in any directory adds a nested config.
+        let config_files = policies.config.config_files();
+        let config_changed = changed.iter().any(|file| {
+            files::CONFIG_FILE_NAMES
+                .iter()
+                .any(|name| file == name || file.ends_with(&format!("/{name}")))
+        }) || config_files.iter().any(|config| {
+            Path::new(config)
+                .strip_prefix(git_root)
+                .is_ok_and(|rel| changed.contains(&to_repo_path(rel)))
+        });
+
+        // A changed .gitignore can add or remove any number of files.
+        let gitignore_changed = changed
+            .iter()
+            .any(|file| file == ".gitignore" || file.ends_with("/.gitignore"));
+
+        if config_changed || gitignore_changed {
+            if gitignore_changed {
+                if options.verbose {
+                    eprintln!("Ignore rules changed; re-reading the file list.");
+                }
+                tree.unwatch(&mut watcher, git_root);
+                tree = Tree::load(&mut watcher, git_root)?;
+            }
+            if config_changed {
+                if options.verbose {
+                    eprintln!("Config changed; reloading.");
+                }
+                policies = PolicySet::load(pool, options.config_path, options.verbose)?;
+                filters = Filters::new(&policies, options.filter);
+            }
+            violations.clear();
+            check_all(pool, &policies, &tree, git_root, options, &mut violations)?;
+        } else {
+            // Only files still in the tree are checked; the rest were
+            // deleted or are ignored. Either way their old violations no
+            // longer apply.
+            let existing = tree.update(&mut watcher, git_root, &changed)?;
+            violations.retain(|(_, file), _| tree.files.contains(file) && !changed.contains(file));
+
+            if !existing.is_empty() {
+                let options = CheckOptions {
+                    filter: &filters.cacheable,
+                    ..*options
+                };
+                let report = engine::check_files(pool, &policies, existing, git_root, &options)?;
+                record(report, &mut violations);
+            }
+
+            if let Some(filter) = &filters.uncacheable {
+                violations.retain(|(name, _), _| !filter.policies.contains(name));
+                let options = CheckOptions { filter, ..*options };
+                check_all(pool, &policies, &tree, git_root, &options, &mut violations)?;
+            }
+        }
+
+        render(&policies, &violations)?;
+    }
+}
+
+/// Check every file in the repository.
+fn check_all(
+    pool: &mut SidecarPool,
+    policies: &PolicySet,
+    tree: &Tree,
+    git_root: &str,
+    options: &CheckOptions,
+    violations: &mut Violations,
+) -> Result<()> {
+    let all_files = tree.files.iter().cloned().collect();
+    let report = engine::check_files(pool, policies, all_files, git_root, options)?;
+    record(report, violations);
+    Ok(())
+}
+
+/// Add the failures from a report to the violation set. The report's
+/// results for a policy and file replace any recorded before.
+fn record(report: CheckReport, violations: &mut Violations) {
+    let mut reported = Violations::new();
+    for policy in report.policies {
+        for result in policy.results {
+            if result.outcome.is_failure() {
+                reported
+                    .entry((policy.name.clone(), result.file.clone()))
+                    .or_default()
+                    .push(result);
+            }
+        }
+    }
+    violations.extend(reported);
+}
+
+/// The selected policies, split by whether they read only the file they
+/// check.
+struct Filters {
+    /// Runs the selected policies that read only the file they check, which
+    /// need to re-check only the changed files.
+    cacheable: PolicyFilter,
+
+    /// Runs the selected policies that read other files, if there are any.
+    uncacheable: Option<PolicyFilter>,
+}
+
+impl Filters {
+    fn new(policies: &PolicySet, filter: &PolicyFilter) -> Self {
+        let mut uncacheable: Vec<String> = policies
+            .config
+            .policies
+            .iter()
+            .filter(|policy| !policy.cacheable && filter.selects(policy))
+            .map(|policy| policy.name.clone())
+            .collect();
+        uncacheable.sort();
+        uncacheable.dedup();
+
+        let mut cacheable = filter.clone();
+        cacheable.skip.extend(uncacheable.iter().cloned());
+        Self {
+            cacheable,
+            uncacheable: (!uncacheable.is_empty()).then(|| PolicyFilter {
+                policies: uncacheable,
+                ..PolicyFilter::default()
+            }),
+        }
+    }
+}
+
+/// The files git lists and the directories being watched.
+struct Tree {
+    /// Repo-relative paths of the files git lists: tracked files and
+    /// untracked files that are not ignored.
+    files: BTreeSet<String>,
+
+    /// Repo-relative paths of the watched directories. The root is `""`.
+    dirs: HashSet<String>,
+
+    /// Repo-relative paths of the ignored directories, which are not
+    /// watched.
+    ignored_dirs: HashSet<String>,
+}
+
+impl Tree {
+    /// List the files and watch every directory that is not ignored.
+    fn load(watcher: &mut impl Watcher, git_root: &str) -> Result<Self> {
+        let mut tree = Self {
+            files: files::git_ls_files(git_root)?.into_iter().collect(),
+            dirs: HashSet::new(),
+            ignored_dirs: files::git_ignored_dirs(git_root)?.into_iter().collect(),
+        };
+        tree.watch(watcher, git_root, String::new(), false)?;
+        Ok(tree)
+    }
+
+    /// Whether `path` is `.git` or an ignored directory, or inside one.
+    fn is_skipped(&self, path: &str) -> bool {
+        path == ".git"
+            || path.starts_with(".git/")
+            || path
+                .match_indices('/')
+                .map(|(i, _)| &path[..i])
+                .chain([path])
+                .any(|dir| self.ignored_dirs.contains(dir))
+    }
+
+    /// Watch `dir` and the directories below it, and return the files in
+    /// them. If `check_ignored` is set, the directories are new and are
+    /// asked of git whether they are ignored.
+    fn watch(
+        &mut self,
+        watcher: &mut impl Watcher,
+        git_root: &str,
+        dir: String,
+        check_ignored: bool,
+    ) -> Result<Vec<String>> {
+        let root = Path::new(git_root);
+        let mut found = Vec::new();
+        let mut level = vec![dir];
+        while !level.is_empty() {
+            if check_ignored {
+                let ignored = files::git_ignored(git_root, &level)?;
+                level.retain(|dir| !ignored.contains(dir));
+                self.ignored_dirs.extend(ignored);
+            }
+
+            let mut next = Vec::new();
+            for dir in level {
+                if self.is_skipped(&dir) || self.dirs.contains(&dir) {
+                    continue;
+                }
+                // The directory may be gone by the time it is watched.
+                match watcher.watch(&root.join(&dir), RecursiveMode::NonRecursive) {
+                    Err(e) if matches!(e.kind, notify::ErrorKind::PathNotFound) => continue,
+                    result => {
+                        result.with_context(|| format!("Failed to watch {git_root}/{dir}"))?
+                    }
+                }
+                let Ok(entries) = fs::read_dir(root.join(&dir)) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    let path = if dir.is_empty() {
+                        name
+                    } else {
+                        format!("{dir}/{name}")
+                    };
+                    match entry.file_type() {
+                        Ok(kind) if kind.is_dir() => next.push(path),
+                        Ok(_) => found.push(path),
+                        Err(_) => {}
+                    }
+                }
+                self.dirs.insert(dir);
+            }
+            level = next;
+        }
+        Ok(found)
+    }
+
+    /// Update the tree for the changed paths, and return the changed files
+    /// that are in it.
+    fn update(
+        &mut self,
+        watcher: &mut impl Watcher,
+        git_root: &str,
+        changed: &BTreeSet<String>,
+    ) -> Result<Vec<String>> {
+        let mut candidates = Vec::new();
+        let mut new_dirs = Vec::new();
+        for path in changed {
+            match fs::symlink_metadata(Path::new(git_root).join(path)) {
+                Ok(meta) if meta.is_dir() => {
+                    if !self.dirs.contains(path) {
+                        new_dirs.push(path.clone());
+                    }
+                }
+                Ok(_) => candidates.push(path.clone()),
+                Err(_) => self.remove(watcher, git_root, path),
+            }
+        }
+        for dir in new_dirs {
+            candidates.extend(self.watch(watcher, git_root, dir, true)?);
+        }
+
+        let new: Vec<String> = candidates
+            .iter()
+            .filter(|file| !self.files.contains(*file) && !self.is_skipped(file))
+            .cloned()
+            .collect();
+        let ignored = files::git_ignored(git_root, &new)?;
+        self.files
+            .extend(new.into_iter().filter(|file| !ignored.contains(file)));
+
+        candidates.retain(|file| self.files.contains(file));
+        candidates.sort();
+        candidates.dedup();
+        Ok(candidates)
+    }
+
+    /// Forget a deleted file or directory and everything in it.
+    fn remove(&mut self, watcher: &mut impl Watcher, git_root: &str, path: &str) {
+        let prefix = format!("{path}/");
+        let inside = |p: &String| p == path || p.starts_with(&prefix);
+        self.files.retain(|file| !inside(file));
+        self.ignored_dirs.retain(|dir| !inside(dir));
+        self.dirs.retain(|dir| {
+            if !inside(dir) {
+                return true;
+            }
+            // The watch usually went away with the directory.
+            let _ = watcher.unwatch(&Path::new(git_root).join(dir));
+            false
+        });
+    }
+
+    /// Stop watching every directory.
+    fn unwatch(&mut self, watcher: &mut impl Watcher, git_root: &str) {
+        for dir in self.dirs.drain() {
+            let _ = watcher.unwatch(&Path::new(git_root).join(dir));
+        }
+    }
+}
+
+/// Add the repo-relative paths touched by a file event to `changed`.
+/// Paths in `.git` and ignored directories are left out.
+fn collect_paths(
+    event: notify::Result<Event>,
+    git_root: &str,
+    tree: &Tree,
+    changed: &mut BTreeSet<String>,
+) {
+    let Ok(event) = event else {
+        return;
+    };
+
+    // Reads and metadata-only updates (e.g. access times) can be caused by
+    // the policies themselves and would re-trigger the watcher endlessly.
+    let relevant = match event.kind {
+        EventKind::Create(_) | EventKind::Remove(_) => true,
+        EventKind::Modify(ModifyKind::Metadata(_)) => false,
+        EventKind::Modify(_) => true,
+        _ => false,
+    };
+    if !relevant {
+        return;
+    }
+
+    for path in event.paths {
+        let Ok(rel) = path.strip_prefix(git_root) else {
+            continue;
+        };
+        let rel = to_repo_path(rel);
+        if rel.is_empty() || tree.is_skipped(&rel) {
+            continue;
+        }
+        changed.insert(rel);
+    }
+}
+
+/// Convert a relative path to a repo-relative path with forward slashes.
+fn to_repo_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Redraw the current list of violations.
+fn render(policies: &PolicySet, violations: &Violations) -> Result<()> {
+    if std::io::stderr().is_terminal() {
+        // Clear the screen and move the cursor home.
+        eprint!("\x1b[2J\x1b[H");
+    }
+
+    let (mut reports, _) = engine::policy_reports(&policies.config.policies);
+    for ((name, _), results) in violations {
+        if let Some(report) = reports.iter_mut().find(|r| &r.name == name) {
+            report.results.extend(results.iter().cloned());
+        }
+    }
+
+    let report = CheckReport {
+        policies: reports,
+        stats: PerfStats::new(),
+        baseline: None,
+    };
+    report.write(OutputFormat::Text, true, None)?;
+
+    let files: BTreeSet<&str> = violations.keys().map(|(_, f)| f.as_str()).collect();
+    if violations.is_empty() {
+        eprintln!("{}", "No policy violations.".green());
+    } else {
+        eprintln!(
+            "\n{} policy violations in {} files.",
+            violations
+                .values()
+                .map(Vec::len)
+                .sum::<usize>()
+                .to_string()
+                .bold(),
+            files.len().to_string().bold()
+        );
+    }
+    eprintln!("{}", "Watching for changes... (Ctrl+C to exit)".dimmed());
+
+    Ok(())
+}
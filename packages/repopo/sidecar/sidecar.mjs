@@ -22,8 +22,12 @@
  *   { "ok": false, "error": "message" }
  */
 
-import { stat } from "node:fs/promises";
+import { execFile } from "node:child_process";
+import { createHash } from "node:crypto";
+import { readFile, stat } from "node:fs/promises";
+import path from "node:path";
 import { createInterface } from "node:readline";
+import { promisify } from "node:util";
 import { TypeScriptLoader } from "@tylerbu/lilconfig-loader-ts";
 import { run } from "effection";
 import { lilconfig } from "lilconfig";
@@ -34,12 +38,33 @@ let policiesByName = new Map();
 /** @type {Array<import("../src/policy.js").ConfiguredPolicy>} */
 let policiesByIndex = [];
 
+/** @type {Array<import("../src/config.js").NativePolicyConfig>} */
+let nativePoliciesByIndex = [];
+
 /** @type {string} */
 let storedGitRoot = "";
 
 /** @type {import("../src/config.js").RepopoConfig | undefined} */
 let loadedConfig = undefined;
 
+/** @type {string | undefined} */
+let loadedConfigFile = undefined;
+
+/**
+ * The config of the repo root and of each directory with a nested config
+ * file, deepest directories last. Policies in a scope are sidecar policies
+ * or native policy configs, by name.
+ * @type {Array<{ directory: string, configFile: string | undefined, policiesByName: Map<string, import("../src/policy.js").ConfiguredPolicy | import("../src/config.js").NativePolicyConfig>, excludeFiles: Array<string | RegExp> }>}
+ */
+let loadedScopes = [];
+
+/** Config file names, in the order they are searched for. */
+const configFileNames = [
+	"repopo.config.ts",
+	"repopo.config.mjs",
+	"repopo.config.cjs",
+];
+
 /**
  * Respond to the Rust side with a JSON message on stdout.
  * @param {object} response
@@ -50,46 +75,244 @@ function respond(response) {
 }
 
 /**
- * Serialize a RegExp to a pattern string and flags for the Rust side.
- * @param {RegExp} regex
- * @returns {{ source: string, flags: string }}
+ * Serialize a file pattern for the Rust side. Regexes created by `glob()`
+ * carry their glob, which Rust matches natively.
+ * @param {string | RegExp} pattern
+ * @param {string} flags The regex flags the pattern is matched with.
+ * @returns {{ kind: "regex", source: string, flags: string } | { kind: "glob", glob: string, ignoreCase: boolean }}
  */
-function serializeRegex(regex) {
+function serializePattern(pattern, flags) {
+	if (pattern instanceof RegExp && typeof pattern.glob === "string") {
+		return {
+			kind: "glob",
+			glob: pattern.glob,
+			ignoreCase: flags.includes("i"),
+		};
+	}
 	return {
-		source: regex.source,
-		flags: regex.flags,
+		kind: "regex",
+		source: pattern instanceof RegExp ? pattern.source : String(pattern),
+		flags,
 	};
 }
 
+/**
+ * Serialize an exclude pattern. Excludes are matched case-insensitively
+ * with any flags of their own dropped, the same as the TypeScript runner's
+ * `new RegExp(pattern, "i")`.
+ * @param {string | RegExp} pattern
+ */
+function serializeExclude(pattern) {
+	return serializePattern(pattern, "i");
+}
+
+/**
+ * Compute a stable fingerprint of a policy's config so the Rust side can
+ * invalidate cached results when the config changes. Functions in the
+ * config are not serializable and do not contribute to the hash.
+ * @param {unknown} config
+ * @returns {string}
+ */
+function hashConfig(config) {
+	return createHash("sha256")
+		.update(JSON.stringify(config ?? null))
+		.digest("hex");
+}
+
+/**
+ * Read the version of the repopo package that provides this sidecar.
+ * @returns {Promise<string | undefined>}
+ */
+async function readPackageVersion() {
+	try {
+		const pkg = JSON.parse(
+			await readFile(new URL("../package.json", import.meta.url), "utf8"),
+		);
+		return pkg.version;
+	} catch {
+		return undefined;
+	}
+}
+
 /**
  * Serialize policy metadata for the Rust side.
  * Strips out functions and sends only what Rust needs for matching.
  * @param {import("../src/policy.js").ConfiguredPolicy} policy
+ * @param {string} scope The directory of the config that configured the policy.
  * @returns {object}
  */
-function serializePolicy(policy) {
-	const match = serializeRegex(policy.match);
-
-	// Convert excludeFiles to regex pattern strings
+function serializePolicy(policy, scope) {
 	const excludeFiles = (policy.excludeFiles ?? policy.exclude ?? []).map(
-		(e) => {
-			if (e instanceof RegExp) {
-				return e.source;
-			}
-			return String(e);
-		},
+		serializeExclude,
 	);
 
 	return {
 		name: policy.name,
 		description: policy.description,
-		matchPattern: match.source,
-		matchFlags: match.flags,
+		tags: policy.tags ?? [],
+		matchPattern: serializePattern(policy.match, policy.match.flags),
+		severity: policy.severity ?? "error",
 		hasResolver: typeof policy.resolver === "function",
 		excludeFiles,
+		configHash: hashConfig(policy.config),
+		cacheable: policy.cacheable ?? true,
+		scope,
+	};
+}
+
+/**
+ * Serialize a native policy config for the Rust side, which knows the
+ * policy's description and match pattern.
+ * @param {import("../src/config.js").NativePolicyConfig} native
+ * @param {string} scope The directory of the config that enabled the policy.
+ * @returns {object}
+ */
+function serializeNativePolicy(native, scope) {
+	return {
+		name: native.name,
+		config: native.config ?? null,
+		excludeFiles: (native.excludeFiles ?? []).map(serializeExclude),
+		severity: native.severity ?? "error",
+		scope,
 	};
 }
 
+/**
+ * Find the config files in subdirectories of the repo, at most one per
+ * directory. Directories excluded from all policies by the root config are
+ * skipped.
+ * @param {string} gitRoot
+ * @param {Array<string | RegExp>} excludeFiles The root config's global excludes.
+ * @returns {Promise<Array<{ directory: string, file: string }>>}
+ */
+async function findNestedConfigs(gitRoot, excludeFiles) {
+	const { stdout } = await promisify(execFile)(
+		"git",
+		[
+			"ls-files",
+			"-co",
+			"--exclude-standard",
+			"--full-name",
+			"--",
+			...configFileNames.map((name) => `:(glob)**/${name}`),
+		],
+		{ cwd: gitRoot },
+	);
+
+	const excludes = excludeFiles.map((pattern) => new RegExp(pattern, "i"));
+	const rank = (file) => configFileNames.indexOf(path.posix.basename(file));
+	/** @type {Map<string, string>} */
+	const byDirectory = new Map();
+	for (const file of stdout.split("\n").filter(Boolean).sort()) {
+		const directory = path.posix.dirname(file);
+		if (directory === "." || excludes.some((re) => re.test(file))) {
+			continue;
+		}
+		const current = byDirectory.get(directory);
+		if (current === undefined || rank(file) < rank(current)) {
+			byDirectory.set(directory, file);
+		}
+	}
+
+	const depth = (directory) => directory.split("/").length;
+	return [...byDirectory]
+		.map(([directory, file]) => ({ directory, file }))
+		.sort((a, b) => depth(a.directory) - depth(b.directory));
+}
+
+/** Extensions tried for an import specifier, in order. */
+const moduleExtensions = [".ts", ".mts", ".cts", ".js", ".mjs", ".cjs", ".json"];
+
+/**
+ * Matches the specifier of a static import or export, a dynamic import, or a
+ * require call.
+ */
+const importPattern =
+	/(?:\bfrom\s*|\bimport\s*\(?\s*|\brequire\s*\(\s*)["'`]([^"'`]+)["'`]/g;
+
+/**
+ * Resolve a relative import specifier to a file: as written, with a `.js`
+ * extension swapped for its TypeScript counterpart, with an extension added,
+ * or as a directory index.
+ * @param {string} from The absolute path to the importing file.
+ * @param {string} specifier
+ * @returns {Promise<string | undefined>}
+ */
+async function resolveImport(from, specifier) {
+	const base = path.resolve(path.dirname(from), specifier);
+	const ext = path.extname(base);
+	const swapped = { ".js": ".ts", ".mjs": ".mts", ".cjs": ".cts" }[ext];
+	const candidates = [
+		base,
+		...(swapped === undefined ? [] : [base.slice(0, -ext.length) + swapped]),
+		...moduleExtensions.map((extension) => base + extension),
+		...moduleExtensions.map((extension) =>
+			path.join(base, `index${extension}`),
+		),
+	];
+	for (const candidate of candidates) {
+		const stats = await stat(candidate).catch(() => undefined);
+		if (stats?.isFile()) {
+			return candidate;
+		}
+	}
+	return undefined;
+}
+
+/**
+ * Find the local modules that config files import, directly or through each
+ * other, so the Rust side can tell when the config changed. Packages are left
+ * out; the version of repopo covers its policies.
+ * @param {string[]} configFiles Absolute paths.
+ * @returns {Promise<string[]>} Absolute paths, sorted.
+ */
+async function findConfigDependencies(configFiles) {
+	const seen = new Set(configFiles);
+	const pending = [...configFiles];
+	const dependencies = [];
+	while (pending.length > 0) {
+		const file = pending.pop();
+		const source = await readFile(file, "utf8").catch(() => "");
+		for (const [, specifier] of source.matchAll(importPattern)) {
+			if (!specifier.startsWith(".")) {
+				continue;
+			}
+			const resolved = await resolveImport(file, specifier);
+			if (resolved !== undefined && !seen.has(resolved)) {
+				seen.add(resolved);
+				dependencies.push(resolved);
+				pending.push(resolved);
+			}
+		}
+	}
+	return dependencies.sort();
+}
+
+/**
+ * Find the deepest loaded scope that contains a repo-relative path.
+ * @param {string} file
+ */
+function scopeFor(file) {
+	for (let i = loadedScopes.length - 1; i >= 0; i--) {
+		const { directory } = loadedScopes[i];
+		if (directory === "" || file.startsWith(`${directory}/`)) {
+			return loadedScopes[i];
+		}
+	}
+	return undefined;
+}
+
+/**
+ * Find the policy with a name that is in effect for a file, taking nested
+ * configs into account.
+ * @param {string} name
+ * @param {string | undefined} file
+ */
+function policyFor(name, file) {
+	const policy = scopeFor(file ?? "")?.policiesByName.get(name);
+	return policiesByIndex.includes(policy) ? policy : undefined;
+}
+
 /**
  * Check if a value is an Effection Operation (generator).
  * @param {unknown} value
@@ -152,13 +375,16 @@ async function handleLoadConfig(params) {
 		const maybeConfig = pathStats.isDirectory()
 			? await configLoader.search(searchPath)
 			: await configLoader.load(searchPath);
+		const configFrom = (result) => result?.config?.default ?? result?.config;
 
 		if (maybeConfig !== null) {
-			loadedConfig = maybeConfig.config?.default ?? maybeConfig.config;
+			loadedConfig = configFrom(maybeConfig);
+			loadedConfigFile = maybeConfig.filepath;
 		} else {
 			// Use default config
 			const { DefaultPolicyConfig } = await import("../esm/config.js");
 			loadedConfig = DefaultPolicyConfig;
+			loadedConfigFile = undefined;
 		}
 
 		// Store git root for reuse in batch calls
@@ -167,6 +393,7 @@ async function handleLoadConfig(params) {
 		// Build the policy map and index array
 		policiesByName.clear();
 		policiesByIndex = [];
+		nativePoliciesByIndex = [];
 		const policies = loadedConfig?.policies ?? [];
 		for (const p of policies) {
 			policiesByName.set(p.name, p);
@@ -174,12 +401,78 @@ async function handleLoadConfig(params) {
 		}
 
 		// Serialize for Rust
-		const serializedPolicies = policies.map(serializePolicy);
-		const excludeFiles = (loadedConfig?.excludeFiles ?? []).map((e) => {
-			if (e instanceof RegExp) {
-				return e.source;
+		const serializedPolicies = policies.map((p) => serializePolicy(p, ""));
+		const serializedNativePolicies = [];
+		const rootExcludes = loadedConfig?.excludeFiles ?? [];
+		const excludeFiles = rootExcludes.map(serializeExclude);
+
+		/**
+		 * Add the native policies of a config to a scope. A native policy
+		 * replaces a policy with the same name.
+		 */
+		const addNativePolicies = (config, scopePolicies, directory) => {
+			for (const native of config?.nativePolicies ?? []) {
+				scopePolicies.set(native.name, native);
+				if (!nativePoliciesByIndex.includes(native)) {
+					nativePoliciesByIndex.push(native);
+					serializedNativePolicies.push(
+						serializeNativePolicy(native, directory),
+					);
+				}
 			}
-			return String(e);
+		};
+
+		// Each nested config extends the config of the nearest directory
+		// above it: its policies replace the inherited ones with the same
+		// name, and its excludes add to the inherited ones.
+		const rootPolicies = new Map(policiesByName);
+		addNativePolicies(loadedConfig, rootPolicies, "");
+		loadedScopes = [
+			{
+				directory: "",
+				configFile: loadedConfigFile,
+				policiesByName: rootPolicies,
+				excludeFiles: [],
+			},
+		];
+		const nestedConfigs = await findNestedConfigs(storedGitRoot, rootExcludes);
+		for (const nested of nestedConfigs) {
+			const configFile = path.join(storedGitRoot, nested.file);
+			const config = configFrom(await configLoader.load(configFile));
+			const parent = scopeFor(nested.directory);
+			const scopePolicies = new Map(parent.policiesByName);
+			for (const p of config?.policies ?? []) {
+				scopePolicies.set(p.name, p);
+				if (!policiesByIndex.includes(p)) {
+					policiesByIndex.push(p);
+					serializedPolicies.push(serializePolicy(p, nested.directory));
+				}
+			}
+			addNativePolicies(config, scopePolicies, nested.directory);
+			loadedScopes.push({
+				directory: nested.directory,
+				configFile,
+				policiesByName: scopePolicies,
+				excludeFiles: [
+					...parent.excludeFiles,
+					...(config?.excludeFiles ?? []),
+				],
+			});
+		}
+
+		const scopes = loadedScopes.map((scope) => {
+			const inScope = [...scope.policiesByName.values()];
+			return {
+				directory: scope.directory,
+				configFile: scope.configFile,
+				policies: inScope
+					.filter((p) => policiesByIndex.includes(p))
+					.map((p) => policiesByIndex.indexOf(p)),
+				nativePolicies: inScope
+					.filter((p) => nativePoliciesByIndex.includes(p))
+					.map((p) => nativePoliciesByIndex.indexOf(p)),
+				excludeFiles: scope.excludeFiles.map(serializeExclude),
+			};
 		});
 
 		respond({
@@ -187,6 +480,15 @@ async function handleLoadConfig(params) {
 			data: {
 				policies: serializedPolicies,
 				excludeFiles,
+				configFile: loadedConfigFile,
+				version: await readPackageVersion(),
+				scopes,
+				nativePolicies: serializedNativePolicies,
+				configDependencies: await findConfigDependencies(
+					loadedScopes
+						.map((scope) => scope.configFile)
+						.filter((file) => file !== undefined),
+				),
 			},
 		});
 	} catch (err) {
@@ -203,7 +505,7 @@ async function handleLoadConfig(params) {
  */
 async function handleRunHandler(params) {
 	try {
-		const policy = policiesByName.get(params.policyName);
+		const policy = policyFor(params.policyName, params.file);
 		if (!policy) {
 			respond({
 				ok: false,
@@ -254,7 +556,7 @@ async function handleRunHandlerBatch(params) {
 		const resolve = params.resolve ?? false;
 		/** @type {string[]} */
 		const pass = [];
-		/** @type {Array<{file: string, error?: string, errorMessages?: string[], fixable?: boolean, fixed?: boolean, manualFix?: string}>} */
+		/** @type {Array<{file: string, error?: string, errorMessages?: string[], fixable?: boolean, fixed?: boolean, manualFix?: string, severity?: string, line?: number}>} */
 		const fail = [];
 
 		for (const file of params.files) {
@@ -276,6 +578,8 @@ async function handleRunHandlerBatch(params) {
 						fixable: result.fixable,
 						fixed: result.fixed,
 						manualFix: result.manualFix,
+						severity: result.severity,
+						line: result.line,
 					});
 				}
 			} catch (err) {
@@ -302,7 +606,7 @@ async function handleRunHandlerBatch(params) {
  */
 async function handleRunResolver(params) {
 	try {
-		const policy = policiesByName.get(params.policyName);
+		const policy = policyFor(params.policyName, params.file);
 		if (!policy) {
 			respond({
 				ok: false,
@@ -362,7 +666,7 @@ async function handleRunResolverBatch(params) {
 
 		/** @type {string[]} */
 		const pass = [];
-		/** @type {Array<{file: string, error?: string, errorMessages?: string[], fixable?: boolean, fixed?: boolean, manualFix?: string}>} */
+		/** @type {Array<{file: string, error?: string, errorMessages?: string[], fixable?: boolean, fixed?: boolean, manualFix?: string, severity?: string, line?: number}>} */
 		const fail = [];
 
 		for (const file of params.files) {
@@ -383,6 +687,8 @@ async function handleRunResolverBatch(params) {
 						fixable: result.fixable,
 						fixed: result.fixed,
 						manualFix: result.manualFix,
+						severity: result.severity,
+						line: result.line,
 					});
 				}
 			} catch (err) {
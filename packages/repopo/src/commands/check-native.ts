@@ -31,6 +31,17 @@ export class CheckNative extends Command {
 		stdin: Flags.boolean({
 			description: "Read list of files from stdin.",
 			required: false,
+			exclusive: ["changed-since", "staged"],
+		}),
+		"changed-since": Flags.string({
+			description:
+				"Only check files changed since the merge base of this ref and HEAD, including uncommitted and untracked files.",
+			required: false,
+			exclusive: ["staged"],
+		}),
+		staged: Flags.boolean({
+			description: "Only check files staged in the git index.",
+			required: false,
 		}),
 		verbose: Flags.boolean({
 			description: "Show verbose output including per-policy timing.",
@@ -63,6 +74,71 @@ export class CheckNative extends Command {
 			env: "REPOPO_RUNTIME",
 			options: ["auto", "node", "bun"],
 		}),
+		jobs: Flags.integer({
+			description:
+				"Maximum number of sidecar processes to run in parallel. Defaults to the number of CPUs.",
+			required: false,
+			char: "j",
+			env: "REPOPO_JOBS",
+			min: 1,
+		}),
+		format: Flags.string({
+			description:
+				"Output format. Structured formats are written to stdout.",
+			required: false,
+			options: ["text", "json", "sarif", "junit"],
+		}),
+		cache: Flags.boolean({
+			description:
+				"Skip files that passed on a previous run and have not changed since. Policies with `cacheable: false` always run.",
+			required: false,
+		}),
+		"cache-location": Flags.string({
+			description: "Directory for the check cache, relative to the git root.",
+			required: false,
+			dependsOn: ["cache"],
+		}),
+		policy: Flags.string({
+			description: "Only run this policy. Can be repeated; combines with --tag.",
+			required: false,
+			multiple: true,
+		}),
+		tag: Flags.string({
+			description:
+				"Only run policies with this tag, such as cargo or package. Can be repeated; combines with --policy.",
+			required: false,
+			multiple: true,
+		}),
+		"skip-policy": Flags.string({
+			description: "Do not run this policy. Can be repeated.",
+			required: false,
+			multiple: true,
+		}),
+		"max-warnings": Flags.integer({
+			description: "Fail if policies report more than this many warnings.",
+			required: false,
+			min: 0,
+			exclusive: ["deny-warnings"],
+		}),
+		"deny-warnings": Flags.boolean({
+			description: "Fail if policies report any warnings.",
+			required: false,
+		}),
+		baseline: Flags.string({
+			description:
+				"Baseline file of known violations to suppress. Defaults to .repopo-baseline.json in the git root, if it exists.",
+			required: false,
+			exclusive: ["no-baseline"],
+		}),
+		"no-baseline": Flags.boolean({
+			description: "Report every violation, even those in the baseline.",
+			required: false,
+		}),
+		"no-daemon": Flags.boolean({
+			description: "Run in this process even if a repopo-core daemon is running.",
+			required: false,
+			env: "REPOPO_NO_DAEMON",
+		}),
 	} as const;
 
 	public override async run(): Promise<void> {
@@ -79,6 +155,12 @@ export class CheckNative extends Command {
 		if (flags.stdin) {
 			args.push("--stdin");
 		}
+		if (flags["changed-since"]) {
+			args.push("--changed-since", flags["changed-since"]);
+		}
+		if (flags.staged) {
+			args.push("--staged");
+		}
 		if (flags.verbose) {
 			args.push("--verbose");
 		}
@@ -91,6 +173,42 @@ export class CheckNative extends Command {
 		if (flags.runtime) {
 			args.push("--runtime", flags.runtime);
 		}
+		if (flags.jobs !== undefined) {
+			args.push("--jobs", String(flags.jobs));
+		}
+		if (flags.format) {
+			args.push("--format", flags.format);
+		}
+		if (flags.cache) {
+			args.push("--cache");
+		}
+		if (flags["cache-location"]) {
+			args.push("--cache-location", flags["cache-location"]);
+		}
+		for (const name of flags.policy ?? []) {
+			args.push("--policy", name);
+		}
+		for (const tag of flags.tag ?? []) {
+			args.push("--tag", tag);
+		}
+		for (const name of flags["skip-policy"] ?? []) {
+			args.push("--skip-policy", name);
+		}
+		if (flags["max-warnings"] !== undefined) {
+			args.push("--max-warnings", String(flags["max-warnings"]));
+		}
+		if (flags["deny-warnings"]) {
+			args.push("--deny-warnings");
+		}
+		if (flags.baseline) {
+			args.push("--baseline", flags.baseline);
+		}
+		if (flags["no-baseline"]) {
+			args.push("--no-baseline");
+		}
+		if (flags["no-daemon"]) {
+			args.push("--no-daemon");
+		}
 
 		return new Promise<void>((resolvePromise, reject) => {
 			const child = spawn(binaryPath, args, {
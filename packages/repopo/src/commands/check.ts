@@ -13,6 +13,7 @@ import {
 	isPolicyFixResult,
 	type PolicyError,
 	type PolicyFailure,
+	type PolicySeverity,
 } from "../policy.js";
 import { type PolicyFileResult, PolicyRunner } from "../runner.js";
 
@@ -36,6 +37,13 @@ async function readStdin(): Promise<string> {
 	});
 }
 
+/** How output refers to a policy failure of each severity. */
+const failureNoun: Record<PolicySeverity, string> = {
+	error: "failure",
+	warning: "warning",
+	info: "note",
+};
+
 /**
  * This tool enforces policies across the code base via a series of handler functions. The handler functions are
  * associated with a regular expression, and all files matching that expression are passed to the handler function.
@@ -160,7 +168,12 @@ export class CheckPolicy<
 
 		// Handle new PolicyError with fixed property (fix was attempted inline)
 		if (isPolicyError(outcome) && outcome.fixed !== undefined) {
-			this.formatPolicyErrorFixResult(outcome, result.file, result.policy);
+			this.formatPolicyErrorFixResult(
+				outcome,
+				result.file,
+				result.policy,
+				result.severity ?? "error",
+			);
 			return;
 		}
 
@@ -175,7 +188,12 @@ export class CheckPolicy<
 		if (outcome === true) {
 			return;
 		}
-		this.formatPolicyFailure(outcome, result.file, result.policy);
+		this.formatPolicyFailure(
+			outcome,
+			result.file,
+			result.policy,
+			result.severity ?? "error",
+		);
 	}
 
 	private formatFixResult(
@@ -201,6 +219,7 @@ export class CheckPolicy<
 		result: PolicyError,
 		file: string,
 		policyName: string,
+		severity: PolicySeverity,
 	): void {
 		const messages = new StringBuilder();
 
@@ -209,11 +228,15 @@ export class CheckPolicy<
 				`Resolved ${policyName} policy failure for file: ${file}`,
 			);
 		} else {
-			messages.append(`Error fixing ${policyName} policy failure in ${file}`);
+			messages.append(
+				`Error fixing ${policyName} policy ${failureNoun[severity]} in ${file}`,
+			);
 			if (result.error) {
 				messages.append(`${newline}\t${result.error}`);
 			}
-			process.exitCode = 1;
+			if (severity === "error") {
+				process.exitCode = 1;
+			}
 		}
 
 		this.logMessages(messages);
@@ -242,6 +265,7 @@ export class CheckPolicy<
 		result: PolicyFailure | PolicyError,
 		file: string,
 		policyName: string,
+		severity: PolicySeverity,
 	): void {
 		const messages = new StringBuilder();
 
@@ -249,7 +273,7 @@ export class CheckPolicy<
 		if (isPolicyError(result)) {
 			const autoFixable = result.fixable ? chalk.green(" (autofixable)") : "";
 			messages.append(
-				`'${chalk.bold(policyName)}' policy failure${autoFixable}: ${file}`,
+				`'${chalk.bold(policyName)}' policy ${failureNoun[severity]}${autoFixable}: ${file}`,
 			);
 			messages.append(`${newline}\t${result.error}`);
 		} else {
@@ -257,7 +281,7 @@ export class CheckPolicy<
 				? chalk.green(" (autofixable)")
 				: "";
 			messages.append(
-				`'${chalk.bold(policyName)}' policy failure${autoFixable}: ${result.file}`,
+				`'${chalk.bold(policyName)}' policy ${failureNoun[severity]}${autoFixable}: ${result.file}`,
 			);
 			if (result.errorMessages?.length > 0) {
 				messages.append(
@@ -265,7 +289,10 @@ export class CheckPolicy<
 				);
 			}
 		}
-		process.exitCode = 1;
+		// Warnings and info results are reported without failing the run.
+		if (severity === "error") {
+			process.exitCode = 1;
+		}
 
 		this.logMessages(messages);
 	}
@@ -1,6 +1,11 @@
 import type { PolicyCreator } from "./generators.js";
 import { policy } from "./makePolicy.js";
-import type { ConfiguredPolicy, PolicyInstance } from "./policy.js";
+import type {
+	ConfiguredPolicy,
+	PolicyInstance,
+	PolicyName,
+	PolicySeverity,
+} from "./policy.js";
 import { DefaultPolicies } from "./policy.js";
 
 /**
@@ -9,6 +14,42 @@ import { DefaultPolicies } from "./policy.js";
 export type PolicyList = PolicyCreator[];
 
 /**
+ * A policy built into the native engine, enabled by name. Native policies run in the `repopo-core` process instead of
+ * in Node.
+ *
+ * @alpha
+ */
+export interface NativePolicyConfig {
+	/**
+	 * The name of a policy built into `repopo-core`.
+	 */
+	name: PolicyName;
+
+	/**
+	 * Settings for the policy. Each native policy documents its own settings.
+	 */
+	config?: unknown;
+
+	/**
+	 * File paths that match any of these expressions are excluded from the policy. Strings are regular expression
+	 * sources; use {@link glob} for glob patterns.
+	 */
+	excludeFiles?: (string | RegExp)[];
+
+	/**
+	 * The severity of the policy's violations. Defaults to `"error"`.
+	 */
+	severity?: PolicySeverity;
+}
+
+/**
+ * A repopo configuration.
+ *
+ * @remarks
+ * A config file in a subdirectory of the repo extends the config of the nearest directory above it, for the files under
+ * its directory. Its policies replace inherited policies with the same name, and its `excludeFiles` add to the inherited
+ * ones. Only the native engine supports nested configs.
+ *
  * @alpha
  */
 export interface RepopoConfig {
@@ -37,9 +78,22 @@ export interface RepopoConfig {
 
 	/**
 	 * An array of strings/regular expressions. File paths that match any of these expressions will be completely excluded
-	 * from all policies.
+	 * from all policies. Strings are regular expression sources; use {@link glob} for glob patterns.
 	 */
 	excludeFiles?: (string | RegExp)[];
+
+	/**
+	 * Policies built into the native engine to enable. A native policy replaces a policy in `policies` with the same
+	 * name. Only the native engine (`check-native` and `repopo-core`) runs native policies; `repopo check` ignores them.
+	 *
+	 * @example
+	 * ```typescript
+	 * const config: RepopoConfig = {
+	 *   nativePolicies: [{ name: "NoLargeBinaryFiles" }],
+	 * };
+	 * ```
+	 */
+	nativePolicies?: NativePolicyConfig[];
 }
 
 /**
@@ -0,0 +1,305 @@
+/**
+ * Glob patterns for matching repo-relative file paths.
+ *
+ * @remarks
+ * Globs are translated to regular expressions, so they can be used anywhere a policy accepts a `RegExp`. The native
+ * engine recognizes them and matches the original glob with its own translation of the same syntax.
+ *
+ * @packageDocumentation
+ */
+
+/**
+ * A regular expression created from a glob by {@link glob}.
+ *
+ * @alpha
+ */
+export interface GlobRegExp extends RegExp {
+	/**
+	 * The glob the regular expression was created from.
+	 */
+	readonly glob: string;
+}
+
+/**
+ * Create a regular expression that matches repo-relative paths against a glob.
+ *
+ * @remarks
+ * The glob must match the whole path. Supported syntax:
+ *
+ * - `*` matches any characters except `/`, and `?` matches one character except `/`.
+ *
+ * - `**` as a whole path segment matches any number of segments, including none. `src/**` also matches `src`.
+ *
+ * - `[abc]`, `[a-z]`, and `[!abc]` (or `[^abc]`) match one character from a set.
+ *
+ * - `{a,b}` matches any of the comma-separated alternatives, which may contain globs and nest.
+ *
+ * - A leading `!` negates the glob, and `\` escapes the next character.
+ *
+ * Dotfiles are matched like any other file.
+ *
+ * @example
+ * ```typescript
+ * excludeFiles: [glob("**\/*.d.ts"), glob("{dist,build}/**")]
+ * ```
+ *
+ * @param pattern - The glob to translate.
+ * @returns A regular expression that tests whether a path matches the glob.
+ *
+ * @alpha
+ */
+export function glob(pattern: string): GlobRegExp {
+	const regex = new RegExp(globToRegExpSource(pattern)) as RegExp & {
+		glob: string;
+	};
+	Object.defineProperty(regex, "glob", { value: pattern });
+	return regex;
+}
+
+/**
+ * Translate a glob to the source of an anchored regular expression.
+ *
+ * @remarks
+ * The native engine implements the same translation in `crates/core/src/pattern.rs`. Both are checked against
+ * `test/data/glob-conformance.json`.
+ */
+export function globToRegExpSource(pattern: string): string {
+	let start = 0;
+	let negated = false;
+	while (pattern[start] === "!") {
+		negated = !negated;
+		start++;
+	}
+	if (pattern.startsWith("./", start)) {
+		start += 2;
+	}
+
+	const body = translate(pattern, start, pattern.length, true, true);
+	return negated ? `^(?!${body}$)` : `^${body}$`;
+}
+
+/** Characters that must be escaped to appear literally in a regular expression. */
+const REGEX_SPECIAL = new Set([..."\\^$.|?*+()[]{}/"]);
+
+function escapeChar(char: string): string {
+	return REGEX_SPECIAL.has(char) ? `\\${char}` : char;
+}
+
+/** Escape a character that a glob escaped inside a class. Letters and digits stay literal. */
+function escapeClassChar(char: string): string {
+	return /[0-9A-Za-z]/.test(char) ? char : `\\${char}`;
+}
+
+/**
+ * Translate `pattern[start..end]`. `startsSegment` and `endsSegment` say whether the range is bounded by a path
+ * separator (or the end of the glob) on either side, which decides whether a `**` at its edge is a globstar.
+ */
+function translate(
+	pattern: string,
+	start: number,
+	end: number,
+	startsSegment: boolean,
+	endsSegment: boolean,
+): string {
+	let out = "";
+	let i = start;
+
+	const atSegmentStart = (index: number): boolean =>
+		index === start ? startsSegment : pattern[index - 1] === "/";
+	const atSegmentEnd = (index: number): boolean =>
+		index === end ? endsSegment : pattern[index] === "/";
+
+	while (i < end) {
+		const char = pattern[i] as string;
+		switch (char) {
+			case "\\": {
+				if (i + 1 < end) {
+					out += escapeChar(pattern[i + 1] as string);
+					i += 2;
+				} else {
+					out += "\\\\";
+					i++;
+				}
+				break;
+			}
+			case "/": {
+				// A trailing `/**` also matches the directory itself.
+				if (
+					endsSegment &&
+					i + 3 === end &&
+					pattern.startsWith("**", i + 1)
+				) {
+					out += "(?:\\/.*)?";
+					i += 3;
+				} else {
+					out += "\\/";
+					i++;
+				}
+				break;
+			}
+			case "*": {
+				let stars = 1;
+				while (i + stars < end && pattern[i + stars] === "*") {
+					stars++;
+				}
+				const next = i + stars;
+				if (stars === 2 && atSegmentStart(i) && atSegmentEnd(next)) {
+					if (next < end) {
+						// `**/` matches any number of leading segments.
+						out += "(?:[^/]*\\/)*";
+						i = next + 1;
+					} else {
+						out += ".*";
+						i = next;
+					}
+				} else {
+					out += "[^/]*";
+					i = next;
+				}
+				break;
+			}
+			case "?": {
+				out += "[^/]";
+				i++;
+				break;
+			}
+			case "[": {
+				const close = findClassEnd(pattern, i, end);
+				if (close === -1) {
+					out += "\\[";
+					i++;
+					break;
+				}
+				out += translateClass(pattern.slice(i + 1, close));
+				i = close + 1;
+				break;
+			}
+			case "{": {
+				const close = findBraceEnd(pattern, i, end);
+				const alternatives =
+					close === -1 ? [] : splitAlternatives(pattern, i + 1, close);
+				if (alternatives.length < 2) {
+					out += "\\{";
+					i++;
+					break;
+				}
+				const startsAlt = atSegmentStart(i);
+				const endsAlt = atSegmentEnd(close + 1);
+				out += `(?:${alternatives
+					.map(([altStart, altEnd]) =>
+						translate(pattern, altStart, altEnd, startsAlt, endsAlt),
+					)
+					.join("|")})`;
+				i = close + 1;
+				break;
+			}
+			default: {
+				out += escapeChar(char);
+				i++;
+			}
+		}
+	}
+
+	return out;
+}
+
+/** Find the `]` that closes the class opened at `open`, or -1. */
+function findClassEnd(pattern: string, open: number, end: number): number {
+	let i = open + 1;
+	if (pattern[i] === "!" || pattern[i] === "^") {
+		i++;
+	}
+	// A `]` right after the opening bracket is literal.
+	if (pattern[i] === "]") {
+		i++;
+	}
+	while (i < end) {
+		if (pattern[i] === "\\") {
+			i += 2;
+			continue;
+		}
+		if (pattern[i] === "]") {
+			return i;
+		}
+		i++;
+	}
+	return -1;
+}
+
+/** Translate the contents of a `[...]` class. */
+function translateClass(body: string): string {
+	let i = 0;
+	let out = "[";
+	if (body[0] === "!" || body[0] === "^") {
+		// A negated class never matches a path separator.
+		out += "^/";
+		i++;
+	}
+	for (; i < body.length; i++) {
+		const char = body[i] as string;
+		if (char === "\\" && i + 1 < body.length) {
+			out += escapeClassChar(body[i + 1] as string);
+			i++;
+		} else if ("\\[]^".includes(char)) {
+			out += escapeClassChar(char);
+		} else {
+			out += char;
+		}
+	}
+	return `${out}]`;
+}
+
+/** Find the `}` that closes the brace opened at `open`, or -1. */
+function findBraceEnd(pattern: string, open: number, end: number): number {
+	let depth = 0;
+	for (let i = open; i < end; i++) {
+		const char = pattern[i];
+		if (char === "\\") {
+			i++;
+		} else if (char === "[") {
+			const close = findClassEnd(pattern, i, end);
+			if (close !== -1) {
+				i = close;
+			}
+		} else if (char === "{") {
+			depth++;
+		} else if (char === "}") {
+			depth--;
+			if (depth === 0) {
+				return i;
+			}
+		}
+	}
+	return -1;
+}
+
+/** Split `pattern[start..end]` at top-level commas. */
+function splitAlternatives(
+	pattern: string,
+	start: number,
+	end: number,
+): [number, number][] {
+	const alternatives: [number, number][] = [];
+	let depth = 0;
+	let altStart = start;
+	for (let i = start; i < end; i++) {
+		const char = pattern[i];
+		if (char === "\\") {
+			i++;
+		} else if (char === "[") {
+			const close = findClassEnd(pattern, i, end);
+			if (close !== -1) {
+				i = close;
+			}
+		} else if (char === "{") {
+			depth++;
+		} else if (char === "}") {
+			depth--;
+		} else if (char === "," && depth === 0) {
+			alternatives.push([altStart, i]);
+			altStart = i + 1;
+		}
+	}
+	alternatives.push([altStart, end]);
+	return alternatives;
+}
@@ -16,10 +16,12 @@ export {
 	fromFluidHandlers,
 } from "./adapters/fluidFramework.js";
 export type {
+	NativePolicyConfig,
 	// PolicyList,
 	RepopoConfig,
 } from "./config.js";
 export type { ExcludedPolicyFileMap } from "./context.js";
+export { type GlobRegExp, glob } from "./glob.js";
 export {
 	makePolicy,
 	makePolicyDefinition,
@@ -50,6 +52,7 @@ export {
 	type PolicyName,
 	type PolicyResolver,
 	type PolicyResult,
+	type PolicySeverity,
 	type PolicyShape,
 	type PolicyStandaloneResolver,
 } from "./policy.js";
@@ -8,6 +8,7 @@ import type {
 	PolicyHandlerResult,
 	PolicyInstance,
 	PolicyInstanceSettings,
+	PolicySeverity,
 	PolicyShape,
 } from "./policy.js";
 
@@ -26,6 +27,40 @@ export interface PolicyOptions {
 	 * Patterns are matched against repo-relative paths.
 	 */
 	exclude?: (string | RegExp)[];
+
+	/**
+	 * The severity of this policy's violations. Use `"warning"` to introduce a
+	 * policy without failing runs. Defaults to `"error"`.
+	 */
+	severity?: PolicySeverity;
+}
+
+/** The values of {@link PolicySeverity}. */
+const policySeverities: ReadonlySet<unknown> = new Set<PolicySeverity>([
+	"error",
+	"warning",
+	"info",
+]);
+
+/**
+ * Whether the second argument of {@link policy} is options rather than a
+ * policy config. It must have only the keys of {@link PolicyOptions}, with
+ * values of their types, so a config such as `{ severity: "high" }` is not
+ * mistaken for options.
+ */
+function isPolicyOptions(value: unknown): value is PolicyOptions {
+	if (typeof value !== "object" || value === null) {
+		return false;
+	}
+	const entries = Object.entries(value);
+	return (
+		entries.length > 0 &&
+		entries.every(
+			([key, option]) =>
+				(key === "exclude" && Array.isArray(option)) ||
+				(key === "severity" && policySeverities.has(option)),
+		)
+	);
 }
 
 /**
@@ -99,16 +134,8 @@ export function policy<C>(
 		options = maybeOptions;
 	} else if (configOrOptions !== undefined) {
 		// Two-argument form: need to determine if it's config or options
-		// PolicyOptions has a specific shape with 'exclude' property
-		if (
-			typeof configOrOptions === "object" &&
-			configOrOptions !== null &&
-			"exclude" in configOrOptions &&
-			!("name" in configOrOptions) &&
-			Object.keys(configOrOptions).every((k) => k === "exclude")
-		) {
-			// It's options (has only 'exclude')
-			options = configOrOptions as PolicyOptions;
+		if (isPolicyOptions(configOrOptions)) {
+			options = configOrOptions;
 		} else {
 			// It's config
 			config = configOrOptions as C;
@@ -126,6 +153,7 @@ export function policy<C>(
 		config: effectiveConfig,
 		exclude: excludePatterns,
 		excludeFiles: excludePatterns,
+		severity: options?.severity,
 		_internalHandler: normalizeHandler(policyDef.handler),
 	};
 }
@@ -166,10 +194,12 @@ export function makePolicyDefinition<C = undefined>(
 	return {
 		name: args.name,
 		description: args.description,
+		tags: args.tags,
 		match: args.match,
 		handler: args.handler,
 		defaultConfig: args.defaultConfig,
 		resolver: args.resolver,
+		cacheable: args.cacheable,
 	};
 }
 
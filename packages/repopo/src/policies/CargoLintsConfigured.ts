@@ -28,6 +28,27 @@ export interface CargoLintsConfiguredConfig {
 	requireSection?: boolean;
 }
 
+/**
+ * Returns the value if it is a TOML table, or undefined otherwise.
+ */
+function asTable(value: unknown): Record<string, unknown> | undefined {
+	return typeof value === "object" && value !== null && !Array.isArray(value)
+		? (value as Record<string, unknown>)
+		: undefined;
+}
+
+/**
+ * Returns the level a lint is set to. A lint can be set to a level, or to a
+ * table with a `level` and a `priority`.
+ */
+function lintLevel(value: unknown): string | undefined {
+	if (typeof value === "string") {
+		return value;
+	}
+	const level = asTable(value)?.level;
+	return typeof level === "string" ? level : undefined;
+}
+
 function checkClippyLints(
 	clippy: Record<string, unknown>,
 	required: Record<string, string>,
@@ -39,9 +60,16 @@ function checkClippyLints(
 			errors.push(
 				`Missing clippy lint: ${lint} (expected level: "${expectedLevel}")`,
 			);
-		} else if (String(actual) !== expectedLevel) {
+			continue;
+		}
+		const level = lintLevel(actual);
+		if (level === undefined) {
+			errors.push(
+				`Clippy lint ${lint} has no level, expected "${expectedLevel}"`,
+			);
+		} else if (level !== expectedLevel) {
 			errors.push(
-				`Clippy lint ${lint} = "${String(actual)}", expected "${expectedLevel}"`,
+				`Clippy lint ${lint} = "${level}", expected "${expectedLevel}"`,
 			);
 		}
 	}
@@ -52,7 +80,7 @@ function checkLintsSection(
 	toml: CargoToml,
 	config: CargoLintsConfiguredConfig | undefined,
 ): PolicyHandlerResult {
-	const lints = toml.lints as Record<string, unknown> | undefined;
+	const lints = asTable(toml.lints);
 	const requireSection = config?.requireSection ?? true;
 
 	if (requireSection && lints === undefined) {
@@ -67,7 +95,12 @@ function checkLintsSection(
 		return true;
 	}
 
-	const clippy = lints.clippy as Record<string, unknown> | undefined;
+	// A crate that inherits the workspace's lints is configured there.
+	if (lints.workspace === true) {
+		return true;
+	}
+
+	const clippy = asTable(lints.clippy);
 	if (requireSection && clippy === undefined) {
 		return {
 			error: "Missing [lints.clippy] section in Cargo.toml.",
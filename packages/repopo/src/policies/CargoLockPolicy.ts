@@ -64,6 +64,7 @@ export const CargoLockPolicy: PolicyShape<CargoLockPolicyConfig> =
 		name: "CargoLockPolicy",
 		description:
 			"Context-aware Cargo.lock validation: binaries should commit it, libraries typically should not.",
+		cacheable: false,
 		handler: async (toml: CargoToml, { root, file, config }) => {
 			// Skip workspace root Cargo.toml
 			if (toml.workspace !== undefined && toml.package === undefined) {
@@ -36,6 +36,7 @@ export const GleamDocExists: PolicyShape<GleamDocExistsConfig> =
 		name: "GleamDocExists",
 		description:
 			"Validates that documentation files (README.md, etc.) exist for Gleam packages.",
+		cacheable: false,
 		handler: async (toml: GleamToml, { file, root, config }) => {
 			// Skip if the package looks like it's not for publishing
 			if (!(toml.description || toml.licences)) {
@@ -13,7 +13,9 @@ export const GleamManifestCommitted: PolicyDefinition = makePolicyDefinition({
 	name: "GleamManifestCommitted",
 	description:
 		"Ensures manifest.toml is committed alongside gleam.toml for reproducible builds.",
+	tags: ["gleam"],
 	match: /gleam\.toml$/,
+	cacheable: false,
 	handler: async ({ file, root }) => {
 		const dir = path.dirname(path.resolve(root, file));
 		const manifestPath = path.join(dir, "manifest.toml");
@@ -76,7 +76,9 @@ export const GleamMonorepoConsistency: PolicyDefinition<GleamMonorepoConsistency
 		name: "GleamMonorepoConsistency",
 		description:
 			"Enforces consistent metadata (licences, gleam version, etc.) across Gleam packages in a monorepo.",
+		tags: ["gleam"],
 		match: /^gleam\.toml$/,
+		cacheable: false,
 		handler: async ({ root, config }) => {
 			const packagePaths = config?.packages;
 			if (!packagePaths || packagePaths.length < 2) {
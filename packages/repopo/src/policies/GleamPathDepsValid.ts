@@ -60,6 +60,7 @@ export const GleamPathDepsValid: PolicyShape = defineGleamPolicy({
 	name: "GleamPathDepsValid",
 	description:
 		"Validates that all path dependency targets exist and contain a gleam.toml file.",
+	cacheable: false,
 	handler: async (toml: GleamToml, { file, root }) => {
 		const baseDir = path.resolve(root, path.dirname(file));
 		const errors: string[] = [
@@ -32,7 +32,9 @@ export const GleamProjectStructure: PolicyDefinition<GleamProjectStructureConfig
 		name: "GleamProjectStructure",
 		description:
 			"Validates that Gleam projects have the standard directory structure (src/, test/).",
+		tags: ["gleam"],
 		match: /gleam\.toml$/,
+		cacheable: false,
 		handler: async ({ file, root, config }) => {
 			const dir = path.dirname(path.resolve(root, file));
 			const requireSrc = config?.requireSrcDir ?? true;
@@ -95,7 +95,9 @@ export const GleamSharedDependencyVersions: PolicyDefinition<GleamSharedDependen
 		name: "GleamSharedDependencyVersions",
 		description:
 			"Detects dependency version mismatches across Gleam packages in a monorepo.",
+		tags: ["gleam"],
 		match: /^gleam\.toml$/,
+		cacheable: false,
 		handler: async ({ root, config }) => {
 			const packagePaths = config?.packages;
 			if (!packagePaths || packagePaths.length < 2) {
@@ -40,8 +40,10 @@ export const LicenseFileExists: PolicyDefinition<LicenseFileExistsSettings> =
 		name: "LicenseFileExists",
 		description:
 			"Ensures a LICENSE file exists in the repository root for open source projects.",
+		tags: ["package"],
 		// Only trigger on package.json to check once per repository
 		match: /^package\.json$/,
+		cacheable: false,
 		handler: async ({ root, config }) => {
 			const acceptedNames = config?.acceptedNames ?? DEFAULT_LICENSE_NAMES;
 
@@ -17,6 +17,7 @@ export const NoBuildDirectory: PolicyDefinition = makePolicyDefinition({
 	name: "NoBuildDirectory",
 	description:
 		"Prevents accidental commits of build directory contents (Gleam build/ output).",
+	tags: ["gleam"],
 	match: BUILD_DIR_REGEX,
 	// biome-ignore lint/correctness/useYield: no yield needed
 	handler: function* ({ file }): Operation<PolicyFailure> {
@@ -15,6 +15,7 @@ export const NoJsFileExtensions: PolicyDefinition = makePolicyDefinition({
 	name: "NoJsFileExtensions",
 	description:
 		"Prevents ambiguous .js files by requiring explicit .mjs or .cjs extensions based on module format.",
+	tags: ["js"],
 	match: /(^|\/)[^/]+\.js$/i,
 	// biome-ignore lint/correctness/useYield: no yield needed
 	handler: function* ({ file }): Operation<PolicyFailure> {
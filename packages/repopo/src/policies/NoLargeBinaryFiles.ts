@@ -127,6 +127,7 @@ export const NoLargeBinaryFiles: PolicyDefinition<NoLargeBinaryFilesSettings> =
 		name: "NoLargeBinaryFiles",
 		description:
 			"Prevents large binary files from being committed to avoid repository bloat; consider using Git LFS for large assets.",
+		tags: ["repo"],
 		// Match all files
 		match: /.*/,
 		handler: async ({ file, root, config }) => {
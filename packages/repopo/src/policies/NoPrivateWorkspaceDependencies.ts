@@ -128,6 +128,7 @@ export const NoPrivateWorkspaceDependencies = definePackagePolicy<
 	name: "NoPrivateWorkspaceDependencies",
 	description:
 		"Prevents publishable packages from depending on private workspace packages that won't be available on npm.",
+	cacheable: false,
 	// biome-ignore lint/correctness/useYield: no yield needed
 	handler: function* (json, { file, root, config }) {
 		// Private packages can depend on anything - they won't be published
@@ -30,6 +30,7 @@ export const NoTargetArtifacts: PolicyDefinition = makePolicyDefinition({
 	name: "NoTargetArtifacts",
 	description:
 		"Prevents accidental commits of Rust build artifacts (.rlib, .rmeta, .so, .dylib, etc.).",
+	tags: ["cargo"],
 	match: ARTIFACT_REGEX,
 	// biome-ignore lint/correctness/useYield: no yield needed
 	handler: function* ({ file }): Operation<PolicyFailure> {
@@ -76,6 +76,7 @@ export const NoUnsafeWithoutJustification: PolicyDefinition<NoUnsafeWithoutJusti
 		name: "NoUnsafeWithoutJustification",
 		description:
 			"Requires safety justification comments on unsafe blocks in Rust source files.",
+		tags: ["rust"],
 		match: /\.rs$/,
 		handler: async ({ file, root, config }) => {
 			const requiredComment = config?.requireComment ?? "// SAFETY:";
@@ -87,6 +88,7 @@ export const NoUnsafeWithoutJustification: PolicyDefinition<NoUnsafeWithoutJusti
 			const content = await readFile(resolve(root, file), "utf-8");
 			const lines = content.split("\n");
 			const violations: string[] = [];
+			let firstViolationLine: number | undefined;
 
 			for (let i = 0; i < lines.length; i++) {
 				const line = lines[i];
@@ -103,12 +105,14 @@ export const NoUnsafeWithoutJustification: PolicyDefinition<NoUnsafeWithoutJusti
 					violations.push(
 						`Line ${i + 1}: unsafe block without ${requiredComment} comment`,
 					);
+					firstViolationLine ??= i + 1;
 				}
 			}
 
 			if (violations.length > 0) {
 				return {
 					error: violations.join("; "),
+					line: firstViolationLine,
 					manualFix: `Add a "${requiredComment}" comment above each unsafe block explaining why it is safe.`,
 				};
 			}
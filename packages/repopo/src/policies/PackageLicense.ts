@@ -117,6 +117,7 @@ export const PackageLicense = definePackagePolicy<
 	name: "PackageLicense",
 	description:
 		"Ensures each package has a LICENSE file that matches the root repository LICENSE.",
+	cacheable: false,
 	handler: async (json, { file, root, resolve, config }) => {
 		const skipPrivate = config?.skipPrivate ?? true;
 		const licenseFileName =
@@ -128,8 +129,8 @@ export const PackageLicense = definePackagePolicy<
 		}
 
 		const packageName = json.name ?? "unknown";
-		// file is an absolute path, get the directory containing package.json
-		const packageDir = path.dirname(file);
+		// file may be relative, so resolve its directory against the root
+		const packageDir = path.resolve(root, path.dirname(file));
 		const packageLicensePath = path.join(packageDir, licenseFileName);
 		const rootLicensePath = path.join(root, licenseFileName);
 
@@ -286,6 +286,7 @@ export const PackageReadme = definePackagePolicy<
 	name: "PackageReadme",
 	description:
 		"Ensures each package has a README.md file with proper title and required content.",
+	cacheable: false,
 	handler: async (json, { file, resolve, config }) => {
 		const skipPrivate = config?.skipPrivate ?? true;
 		const requireMatchingTitle = config?.requireMatchingTitle ?? true;
@@ -217,6 +217,7 @@ export const PackageTestScripts = definePackagePolicy<
 	name: "PackageTestScripts",
 	description:
 		"Ensures packages have test scripts when test directories or test framework dependencies exist.",
+	cacheable: false,
 	handler: async (json, { file, config }) => {
 		// If no config provided, skip validation
 		if (config === undefined) {
@@ -230,6 +230,7 @@ export const RequiredGitignorePatterns: PolicyDefinition<RequiredGitignorePatter
 		name: "RequiredGitignorePatterns",
 		description:
 			"Ensures .gitignore contains required patterns to prevent committing sensitive files, dependencies, and build artifacts.",
+		tags: ["repo"],
 		match: /^\.gitignore$/,
 		handler: async ({ file, root, resolve, config }) => {
 			const patterns = config?.patterns ?? DEFAULT_PATTERNS;
@@ -36,6 +36,7 @@ export const RustDocExists: PolicyShape<RustDocExistsConfig> =
 		name: "RustDocExists",
 		description:
 			"Validates that documentation files (README.md, etc.) exist for Rust crates.",
+		cacheable: false,
 		handler: async (toml: CargoToml, { file, root, config }) => {
 			// Skip workspace root
 			if (toml.workspace !== undefined && toml.package === undefined) {
@@ -28,8 +28,10 @@ export const RustToolchainExists: PolicyDefinition<RustToolchainExistsConfig> =
 		name: "RustToolchainExists",
 		description:
 			"Ensures a rust-toolchain.toml file exists for reproducible Rust builds.",
+		tags: ["cargo"],
 		// Trigger on Cargo.toml to check once per project
 		match: /^Cargo\.toml$/,
+		cacheable: false,
 		handler: async ({ root, config }) => {
 			const names = config?.acceptedNames ?? DEFAULT_NAMES;
 			const hasToolchain = names.some((name) =>
@@ -28,8 +28,10 @@ export const RustfmtConfigExists: PolicyDefinition<RustfmtConfigExistsConfig> =
 		name: "RustfmtConfigExists",
 		description:
 			"Ensures a rustfmt.toml file exists for consistent Rust formatting.",
+		tags: ["cargo"],
 		// Trigger on Cargo.toml to check once per project
 		match: /^Cargo\.toml$/,
+		cacheable: false,
 		handler: async ({ root, config }) => {
 			const names = config?.acceptedNames ?? DEFAULT_NAMES;
 			const hasConfig = names.some((name) => existsSync(path.join(root, name)));
@@ -142,7 +142,9 @@ export const SharedDependencyVersions: PolicyDefinition<SharedDependencyVersions
 		name: "SharedDependencyVersions",
 		description:
 			"Detects dependency version mismatches across Cargo workspace members.",
+		tags: ["cargo"],
 		match: /^Cargo\.toml$/,
+		cacheable: false,
 		handler: async ({ file, root, config }) => {
 			const detectMismatches = config?.detectMismatches ?? true;
 			const content = await readFile(path.resolve(root, file), "utf-8");
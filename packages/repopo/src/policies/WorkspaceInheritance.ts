@@ -88,7 +88,9 @@ export const WorkspaceInheritance: PolicyDefinition<WorkspaceInheritanceConfig>
 		name: "WorkspaceInheritance",
 		description:
 			"Ensures Cargo workspace members inherit shared fields (version, authors, etc.) from workspace config.",
+		tags: ["cargo"],
 		match: /^Cargo\.toml$/,
+		cacheable: false,
 		handler: async ({ file, root, config }) => {
 			const content = await readFile(path.resolve(root, file), "utf-8");
 			const toml = await parseToml(content);
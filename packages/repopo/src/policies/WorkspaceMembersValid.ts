@@ -78,7 +78,9 @@ export const WorkspaceMembersValid: PolicyDefinition<WorkspaceMembersValidConfig
 		name: "WorkspaceMembersValid",
 		description:
 			"Validates that all Cargo workspace member paths exist and contain Cargo.toml files.",
+		tags: ["cargo"],
 		match: /^Cargo\.toml$/,
+		cacheable: false,
 		handler: async ({ file, root, config }) => {
 			const validatePaths = config?.validatePaths ?? true;
 
@@ -10,6 +10,14 @@ import { PackageJsonSorted } from "./policies/PackageJsonSorted.js";
  */
 export type PolicyName = string;
 
+/**
+ * How serious a policy violation is. Only errors fail a run by default; warnings fail it only when the native engine
+ * is run with `--max-warnings` or `--deny-warnings`, and info results never do.
+ *
+ * @alpha
+ */
+export type PolicySeverity = "error" | "warning" | "info";
+
 /**
  * Arguments passed to policy handler functions.
  *
@@ -72,6 +80,17 @@ export interface PolicyError {
 	 * An optional string that tells the user how to manually fix the failure.
 	 */
 	manualFix?: string | undefined;
+
+	/**
+	 * Overrides the severity configured for the policy for this violation.
+	 */
+	severity?: PolicySeverity | undefined;
+
+	/**
+	 * The 1-based line of the violation, if it has one. Editors and SARIF output point at this line; without it they
+	 * point at the start of the file.
+	 */
+	line?: number | undefined;
 }
 
 /**
@@ -212,7 +231,12 @@ export interface PolicyShape<C = void> {
 	description: string;
 
 	/**
-	 * A regular expression that matches files this policy applies to.
+	 * Tags that group related policies, such as `cargo` or `package`. Used to select a subset of policies to run.
+	 */
+	tags?: string[] | undefined;
+
+	/**
+	 * A regular expression that matches files this policy applies to. Use {@link glob} to match with a glob instead.
 	 */
 	match: RegExp;
 
@@ -230,6 +254,13 @@ export interface PolicyShape<C = void> {
 	 * A default configuration that will be used if none is provided.
 	 */
 	defaultConfig?: C | undefined;
+
+	/**
+	 * Set to `false` if the handler reads files other than the one it checks, such as the member manifests of a
+	 * workspace. The native engine's `--cache` only records passes by the content of the checked file, so such a policy
+	 * runs every time. Defaults to `true`.
+	 */
+	cacheable?: boolean | undefined;
 }
 
 /**
@@ -244,18 +275,22 @@ export interface PolicyShape<C = void> {
 export abstract class Policy<C = void> implements PolicyShape<C> {
 	public readonly name: string;
 	public readonly description: string;
+	public readonly tags?: string[] | undefined;
 	public readonly match: RegExp;
 	public readonly handler: PolicyHandler<C>;
 	public readonly defaultConfig?: C | undefined;
 	public readonly resolver?: PolicyStandaloneResolver<C> | undefined;
+	public readonly cacheable?: boolean | undefined;
 
 	public constructor(definition: PolicyShape<C>) {
 		this.name = definition.name;
 		this.description = definition.description;
+		this.tags = definition.tags;
 		this.match = definition.match;
 		this.handler = definition.handler;
 		this.defaultConfig = definition.defaultConfig;
 		this.resolver = definition.resolver;
+		this.cacheable = definition.cacheable;
 	}
 }
 
@@ -289,6 +324,11 @@ export interface ConfiguredPolicy<C = void> extends PolicyShape<C> {
 	 */
 	excludeFiles?: (string | RegExp)[] | undefined;
 
+	/**
+	 * The severity of this policy's violations. Defaults to `"error"`.
+	 */
+	severity?: PolicySeverity | undefined;
+
 	/**
 	 * Internal normalized handler (always an Effection Operation).
 	 * This is set by the policy() function and used by the execution engine.
@@ -304,7 +344,7 @@ export interface ConfiguredPolicy<C = void> extends PolicyShape<C> {
 export interface PolicyInstanceSettings<C> {
 	/**
 	 * An array of strings/regular expressions. File paths that match any of these expressions will be completely excluded
-	 * from policy.
+	 * from policy. Strings are regular expression sources; use {@link glob} for glob patterns.
 	 *
 	 * Paths will be matched relative to the root of the repo.
 	 */
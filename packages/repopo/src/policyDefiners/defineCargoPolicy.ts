@@ -66,6 +66,11 @@ export interface DefineCargoPolicyArgs<C> {
 	 * Optional default configuration for the policy.
 	 */
 	defaultConfig?: C;
+
+	/**
+	 * Set to `false` if the handler reads files other than the one it checks. See {@link PolicyShape.cacheable}.
+	 */
+	cacheable?: boolean;
 }
 
 /**
@@ -73,7 +78,7 @@ export interface DefineCargoPolicyArgs<C> {
  *
  * @remarks
  * This is a helper function that creates a policy pre-configured to match
- * Cargo.toml files. The handler receives the parsed TOML content.
+ * Cargo.toml files and tagged `cargo`. The handler receives the parsed TOML content.
  *
  * @example
  * ```typescript
@@ -95,12 +100,20 @@ export interface DefineCargoPolicyArgs<C> {
 export function defineCargoPolicy<C = undefined>(
 	args: DefineCargoPolicyArgs<C>,
 ): PolicyShape<C> {
-	const { name, description, handler: cargoHandler, defaultConfig } = args;
+	const {
+		name,
+		description,
+		handler: cargoHandler,
+		defaultConfig,
+		cacheable,
+	} = args;
 	return {
 		name,
 		description,
+		tags: ["cargo"],
 		match: CargoTomlRegexMatch,
 		defaultConfig,
+		cacheable,
 		handler: function* (innerArgs) {
 			const content: string = yield* call(() =>
 				readFile(resolve(innerArgs.root, innerArgs.file), "utf-8"),
@@ -66,6 +66,11 @@ export interface DefineGleamPolicyArgs<C> {
 	 * Optional default configuration for the policy.
 	 */
 	defaultConfig?: C;
+
+	/**
+	 * Set to `false` if the handler reads files other than the one it checks. See {@link PolicyShape.cacheable}.
+	 */
+	cacheable?: boolean;
 }
 
 /**
@@ -73,7 +78,7 @@ export interface DefineGleamPolicyArgs<C> {
  *
  * @remarks
  * This is a helper function that creates a policy pre-configured to match
- * gleam.toml files. The handler receives the parsed TOML content.
+ * gleam.toml files and tagged `gleam`. The handler receives the parsed TOML content.
  *
  * @example
  * ```typescript
@@ -94,12 +99,20 @@ export interface DefineGleamPolicyArgs<C> {
 export function defineGleamPolicy<C = undefined>(
 	args: DefineGleamPolicyArgs<C>,
 ): PolicyShape<C> {
-	const { name, description, handler: gleamHandler, defaultConfig } = args;
+	const {
+		name,
+		description,
+		handler: gleamHandler,
+		defaultConfig,
+		cacheable,
+	} = args;
 	return {
 		name,
 		description,
+		tags: ["gleam"],
 		match: GleamTomlRegexMatch,
 		defaultConfig,
+		cacheable,
 		handler: function* (innerArgs) {
 			const content: string = yield* call(() =>
 				readFile(resolve(innerArgs.root, innerArgs.file), "utf-8"),
@@ -65,6 +65,11 @@ export interface DefinePackagePolicyArgs<J, C> {
 	 * Optional default configuration for the policy.
 	 */
 	defaultConfig?: C;
+
+	/**
+	 * Set to `false` if the handler reads files other than the one it checks. See {@link PolicyShape.cacheable}.
+	 */
+	cacheable?: boolean;
 }
 
 /**
@@ -72,7 +77,7 @@ export interface DefinePackagePolicyArgs<J, C> {
  *
  * @remarks
  * This is a helper function that creates a policy pre-configured to match
- * package.json files. The handler receives the parsed JSON content.
+ * package.json files and tagged `package`. The handler receives the parsed JSON content.
  *
  * @example
  * ```typescript
@@ -93,12 +98,20 @@ export interface DefinePackagePolicyArgs<J, C> {
 export function definePackagePolicy<J = PackageJson, C = undefined>(
 	args: DefinePackagePolicyArgs<J, C>,
 ): PolicyShape<C> {
-	const { name, description, handler: packageHandler, defaultConfig } = args;
+	const {
+		name,
+		description,
+		handler: packageHandler,
+		defaultConfig,
+		cacheable,
+	} = args;
 	return {
 		name,
 		description,
+		tags: ["package"],
 		match: PackageJsonRegexMatch,
 		defaultConfig,
+		cacheable,
 		handler: function* (innerArgs) {
 			const json: J = yield* call(() =>
 				readJson(resolve(innerArgs.root, innerArgs.file)),
@@ -12,6 +12,7 @@ import type {
 	PolicyHandlerResult,
 	PolicyInstance,
 	PolicyName,
+	PolicySeverity,
 	PolicyStandaloneResolver,
 } from "./policy.js";
 import { isPolicyError, isPolicyFixResult } from "./policy.js";
@@ -25,7 +26,14 @@ function isOperation<T>(value: unknown): value is Operation<T> {
 	);
 }
 
-function matches(regex: RegExp, value: string): boolean {
+/**
+ * Tests a file path against a policy regex, ignoring any `lastIndex` state left by global or sticky regexes.
+ *
+ * @remarks
+ * The native engine translates policy regexes to Rust; `test/data/regex-conformance.json` checks that both agree
+ * with this function.
+ */
+export function matches(regex: RegExp, value: string): boolean {
 	if (!(regex.global || regex.sticky)) {
 		return regex.test(value);
 	}
@@ -48,6 +56,8 @@ export interface PolicyFileResult {
 	policy: PolicyName;
 	/** The raw result from the policy handler */
 	outcome: PolicyHandlerResult;
+	/** The severity of the failure, from the result or the policy's config. Defaults to `"error"`. */
+	severity?: PolicySeverity | undefined;
 	/** Set when a standalone resolver was attempted (legacy resolver path) */
 	resolution?: PolicyFixResult;
 }
@@ -171,6 +181,9 @@ export class PolicyRunner {
 				file: relPath,
 				policy: policy.name,
 				outcome: result,
+				severity:
+					(isPolicyError(result) ? result.severity : undefined) ??
+					policy.severity,
 			};
 
 			// Check if resolution should be attempted: result is a failure that
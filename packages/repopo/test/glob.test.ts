@@ -0,0 +1,41 @@
+import { readFileSync } from "node:fs";
+import { describe, expect, it } from "vitest";
+import { glob } from "../src/glob.js";
+import { matches } from "../src/runner.js";
+
+interface ConformanceCase {
+	glob: string;
+	ignoreCase: boolean;
+	cases: [path: string, expected: boolean][];
+}
+
+// The native engine's glob matcher is checked against the same fixture.
+const suite: ConformanceCase[] = JSON.parse(
+	readFileSync(
+		new URL("./data/glob-conformance.json", import.meta.url),
+		"utf8",
+	),
+);
+
+describe("glob", () => {
+	it("keeps the original glob", () => {
+		expect(glob("**/*.ts").glob).toBe("**/*.ts");
+	});
+
+	it("is case-insensitive when copied with the i flag, like excludeFiles", () => {
+		expect(new RegExp(glob("dist/**"), "i").test("DIST/a.js")).toBe(true);
+	});
+
+	describe("conformance", () => {
+		for (const { glob: pattern, ignoreCase, cases } of suite) {
+			it(`${pattern}${ignoreCase ? " (ignore case)" : ""}`, () => {
+				const regex = ignoreCase
+					? new RegExp(glob(pattern), "i")
+					: glob(pattern);
+				for (const [path, expected] of cases) {
+					expect(matches(regex, path), path).toBe(expected);
+				}
+			});
+		}
+	});
+});
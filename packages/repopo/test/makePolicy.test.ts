@@ -444,6 +444,59 @@ describe("policy()", () => {
 			expect(result.exclude).toEqual(["vendor/*"]);
 			expect(result.config).toBeUndefined();
 		});
+
+		it("should accept options with a severity", () => {
+			const result = policy(noConfigPolicyDef, {
+				exclude: ["vendor/*"],
+				severity: "warning",
+			});
+
+			expect(result.severity).toBe("warning");
+			expect(result.exclude).toEqual(["vendor/*"]);
+			expect(result.config).toBeUndefined();
+		});
+
+		it("should treat an object with only severity as options", () => {
+			const result = policy(basePolicyDef, { severity: "info" });
+
+			expect(result.severity).toBe("info");
+			expect(result.config).toEqual({ threshold: 50 });
+		});
+	});
+
+	describe("two-argument form: config that looks like options", () => {
+		interface SeverityConfig {
+			severity: string;
+			exclude?: string;
+		}
+
+		const severityPolicyDef: PolicyShape<SeverityConfig> = {
+			name: "SeverityPolicy",
+			description: "Policy with a severity setting of its own",
+			match: /\.ts$/,
+			handler: async () => true as const,
+		};
+
+		it("should treat a severity that is not a policy severity as config", () => {
+			const result = policy(severityPolicyDef, { severity: "high" });
+
+			expect(result.config).toEqual({ severity: "high" });
+			expect(result.severity).toBeUndefined();
+		});
+
+		it("should treat an exclude that is not an array as config", () => {
+			const result = policy(severityPolicyDef, {
+				severity: "warning",
+				exclude: "vendor/*",
+			});
+
+			expect(result.config).toEqual({
+				severity: "warning",
+				exclude: "vendor/*",
+			});
+			expect(result.severity).toBeUndefined();
+			expect(result.exclude).toBeUndefined();
+		});
 	});
 
 	describe("two-argument form: policy(def, config)", () => {
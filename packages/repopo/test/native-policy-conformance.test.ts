@@ -0,0 +1,110 @@
+import {
+	mkdirSync,
+	mkdtempSync,
+	readFileSync,
+	rmSync,
+	writeFileSync,
+} from "node:fs";
+import { tmpdir } from "node:os";
+import { dirname, join } from "pathe";
+import { describe, expect, it } from "vitest";
+import { CargoLintsConfigured } from "../src/policies/CargoLintsConfigured.js";
+import { HtmlFileHeaders } from "../src/policies/HtmlFileHeaders.js";
+import { JsTsFileHeaders } from "../src/policies/JsTsFileHeaders.js";
+import { NoLargeBinaryFiles } from "../src/policies/NoLargeBinaryFiles.js";
+import { NoTargetArtifacts } from "../src/policies/NoTargetArtifacts.js";
+import { PackageEsmType } from "../src/policies/PackageEsmType.js";
+import { PackageJsonSorted } from "../src/policies/PackageJsonSorted.js";
+import { PackageLicense } from "../src/policies/PackageLicense.js";
+import { PackagePrivateField } from "../src/policies/PackagePrivateField.js";
+import { WorkspaceInheritance } from "../src/policies/WorkspaceInheritance.js";
+import type {
+	PolicyError,
+	PolicyFailure,
+	PolicyShape,
+} from "../src/policy.js";
+import { matches } from "../src/runner.js";
+import { runHandler } from "./test-helpers.js";
+
+interface ConformanceCase {
+	policy: string;
+	config?: unknown;
+	/** Sizes of the files to create, or their contents, by path. */
+	files: Record<string, number | string>;
+	/**
+	 * The expected failures, by path. Every other file passes. A failure with
+	 * a single `error` lists it in `errorMessages`.
+	 */
+	failures: Record<string, { errorMessages: string[]; manualFix?: string }>;
+}
+
+// The native engine's ports of these policies are checked against the same fixture.
+const suite: ConformanceCase[] = JSON.parse(
+	readFileSync(
+		new URL("./data/native-policy-conformance.json", import.meta.url),
+		"utf8",
+	),
+);
+
+// biome-ignore lint/suspicious/noExplicitAny: policies with different settings
+const policies: Record<string, PolicyShape<any>> = {
+	CargoLintsConfigured,
+	HtmlFileHeaders,
+	JsTsFileHeaders,
+	NoLargeBinaryFiles,
+	NoTargetArtifacts,
+	PackageEsmType,
+	PackageJsonSorted,
+	PackageLicense,
+	PackagePrivateField,
+	WorkspaceInheritance,
+};
+
+describe("native policy conformance", () => {
+	for (const [index, testCase] of suite.entries()) {
+		const { policy: name, config, files, failures } = testCase;
+		it(`${name} #${index}`, async () => {
+			const policy = policies[name];
+			if (policy === undefined) {
+				throw new Error(`Unknown policy ${name}`);
+			}
+
+			const root = mkdtempSync(join(tmpdir(), "repopo-conformance-"));
+			try {
+				for (const [file, contents] of Object.entries(files)) {
+					mkdirSync(dirname(join(root, file)), { recursive: true });
+					writeFileSync(
+						join(root, file),
+						typeof contents === "number" ? Buffer.alloc(contents) : contents,
+					);
+				}
+
+				const actual: ConformanceCase["failures"] = {};
+				for (const file of Object.keys(files)) {
+					if (!matches(policy.match, file)) {
+						continue;
+					}
+					const result = await runHandler(policy.handler, {
+						file,
+						root,
+						resolve: false,
+						config,
+					});
+					if (result !== true) {
+						const failure = result as Partial<PolicyFailure & PolicyError>;
+						const { errorMessages, error, manualFix } = failure;
+						actual[file] = {
+							errorMessages:
+								errorMessages ?? (error === undefined ? [] : [error]),
+							...(manualFix === undefined ? {} : { manualFix }),
+						};
+					}
+				}
+
+				expect(actual).toEqual(failures);
+			} finally {
+				rmSync(root, { recursive: true, force: true });
+			}
+		});
+	}
+});
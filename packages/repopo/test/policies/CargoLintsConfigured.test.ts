@@ -53,6 +53,28 @@ describe("CargoLintsConfigured", () => {
 			expect(result).toBe(true);
 		});
 
+		it("should pass when the crate inherits the workspace lints", async () => {
+			writeFileSync(
+				join(testDir, "Cargo.toml"),
+				[
+					"[package]",
+					'name = "test"',
+					"",
+					"[lints]",
+					"workspace = true",
+				].join("\n"),
+			);
+
+			const result = await runHandler(CargoLintsConfigured.handler, {
+				file: "Cargo.toml",
+				root: testDir,
+				resolve: false,
+				config: { required: { pedantic: "warn" } },
+			});
+
+			expect(result).toBe(true);
+		});
+
 		it("should skip workspace-only Cargo.toml", async () => {
 			writeFileSync(
 				join(testDir, "Cargo.toml"),
@@ -137,6 +159,33 @@ describe("CargoLintsConfigured", () => {
 			expect(result).toBe(true);
 		});
 
+		it("should check the level of a lint set to a table", async () => {
+			writeFileSync(
+				join(testDir, "Cargo.toml"),
+				[
+					"[package]",
+					'name = "test"',
+					"",
+					"[lints.clippy]",
+					'pedantic = { level = "warn", priority = -1 }',
+					"nursery = { priority = -1 }",
+				].join("\n"),
+			);
+
+			const result = await runHandler(CargoLintsConfigured.handler, {
+				file: "Cargo.toml",
+				root: testDir,
+				resolve: false,
+				config: { required: { pedantic: "warn", nursery: "warn" } },
+			});
+
+			expect(result).toEqual({
+				error: 'Clippy lint nursery has no level, expected "warn"',
+				manualFix:
+					"Update the [lints.clippy] section with the required lint levels.",
+			});
+		});
+
 		it("should fail when required lint has wrong level", async () => {
 			writeFileSync(
 				join(testDir, "Cargo.toml"),
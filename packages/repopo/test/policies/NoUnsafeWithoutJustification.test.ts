@@ -120,6 +120,7 @@ describe("NoUnsafeWithoutJustification", () => {
 			if (typeof result === "object" && "error" in result) {
 				expect(result.error).toContain("unsafe block without");
 				expect(result.error).toContain("Line 2");
+				expect(result.line).toBe(2);
 			}
 		});
 
@@ -0,0 +1,28 @@
+import { readFileSync } from "node:fs";
+import { describe, expect, it } from "vitest";
+import { matches } from "../src/runner.js";
+
+interface ConformanceCase {
+	pattern: string;
+	flags: string;
+	cases: [input: string, expected: boolean][];
+}
+
+// The native engine's JS regex translation is checked against the same fixture.
+const suite: ConformanceCase[] = JSON.parse(
+	readFileSync(
+		new URL("./data/regex-conformance.json", import.meta.url),
+		"utf8",
+	),
+);
+
+describe("regex conformance", () => {
+	for (const { pattern, flags, cases } of suite) {
+		it(`/${pattern}/${flags}`, () => {
+			const regex = new RegExp(pattern, flags);
+			for (const [input, expected] of cases) {
+				expect(matches(regex, input), JSON.stringify(input)).toBe(expected);
+			}
+		});
+	}
+});
@@ -0,0 +1,19 @@
+import { readFileSync } from "node:fs";
+import { describe, expect, it } from "vitest";
+import { tryCorrectLicence } from "../src/policyDefiners/spdxValidator.js";
+
+// The native SPDX validator's corrections are checked against the same fixture.
+const cases: [input: string, expected: string | null][] = JSON.parse(
+	readFileSync(
+		new URL("./data/spdx-conformance.json", import.meta.url),
+		"utf8",
+	),
+);
+
+describe("spdx conformance", () => {
+	for (const [input, expected] of cases) {
+		it(JSON.stringify(input), async () => {
+			expect(await tryCorrectLicence(input)).toBe(expected);
+		});
+	}
+});
@@ -23,7 +23,13 @@ const vendoredPackages = ["packages/btree-typescript"];
 const vendoredPackageJsons = vendoredPackages.map((p) => `${p}/package.json`);
 
 const config: RepopoConfig = {
-	excludeFiles: ["test/data", "fixtures", "config/package.json"],
+	excludeFiles: [
+		"test/data",
+		"fixtures",
+		"config/package.json",
+		// A test fixture, not a nested config for packages/repopo/test
+		"packages/repopo/test/repopo.config.ts",
+	],
 	policies: [
 		makePolicy(NoJsFileExtensions, undefined, {
 			excludeFiles: [